@@ -1,8 +1,87 @@
 use anyhow::Result;
-use aws_config::BehaviorVersion;
+use aws_config::{BehaviorVersion, SdkConfig};
+use aws_sdk_cloudwatchlogs::config::{Credentials, ProvideCredentials};
 use aws_sdk_cloudwatchlogs::Client;
+use thiserror::Error;
 
-pub async fn create_client(profile: Option<&str>, region: Option<&str>) -> Result<Client> {
+/// Error codes and messages that mean the credentials are missing, expired or rejected
+const CREDENTIAL_ERROR_MARKERS: &[&str] = &[
+    "ExpiredToken",
+    "UnrecognizedClientException",
+    "InvalidClientTokenId",
+    "InvalidSignatureException",
+    "no providers in chain provided credentials",
+    "the credential provider was not enabled",
+    "Token has expired",
+];
+
+/// AWS credentials could not be loaded for a profile
+#[derive(Debug, Error)]
+#[error("AWS credentials are missing or expired for profile '{profile}': {reason}")]
+pub struct CredentialsError {
+    pub profile: String,
+    pub reason: String,
+}
+
+/// Broad category of an AWS failure, reported as `kind` in JSON errors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AwsErrorKind {
+    Credentials,
+    Other,
+}
+
+impl AwsErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Credentials => "credentials",
+            Self::Other => "aws",
+        }
+    }
+}
+
+/// Classify an error from client creation or a CloudWatch call
+pub fn error_kind(error: &anyhow::Error) -> AwsErrorKind {
+    if error.downcast_ref::<CredentialsError>().is_some() {
+        return AwsErrorKind::Credentials;
+    }
+
+    let message = format!("{:#}", error);
+    if CREDENTIAL_ERROR_MARKERS.iter().any(|marker| message.contains(marker)) {
+        AwsErrorKind::Credentials
+    } else {
+        AwsErrorKind::Other
+    }
+}
+
+/// Resolve credentials up front so a missing or expired login fails before any query runs
+pub async fn check_credentials(config: &SdkConfig, profile: Option<&str>) -> Result<Credentials> {
+    let credentials_error = |reason: String| CredentialsError {
+        profile: profile.unwrap_or("default").to_string(),
+        reason,
+    };
+
+    let provider = config
+        .credentials_provider()
+        .ok_or_else(|| credentials_error("no credentials provider configured".to_string()))?;
+
+    let credentials = provider
+        .provide_credentials()
+        .await
+        .map_err(|e| credentials_error(aws_sdk_cloudwatchlogs::error::DisplayErrorContext(e).to_string()))?;
+
+    Ok(credentials)
+}
+
+pub async fn create_client(profile: Option<&str>, region: Option<&str>, endpoint_url: Option<&str>) -> Result<Client> {
+    let config = load_sdk_config(profile, region, endpoint_url).await;
+    check_credentials(&config, profile).await?;
+    let client = Client::new(&config);
+
+    Ok(client)
+}
+
+/// Load the shared AWS config for a profile, region and endpoint (--endpoint-url), falling back to the SDK defaults
+pub async fn load_sdk_config(profile: Option<&str>, region: Option<&str>, endpoint_url: Option<&str>) -> SdkConfig {
     let mut config_loader = aws_config::defaults(BehaviorVersion::latest());
 
     if let Some(profile_name) = profile {
@@ -13,8 +92,71 @@ pub async fn create_client(profile: Option<&str>, region: Option<&str>) -> Resul
         config_loader = config_loader.region(aws_config::Region::new(region_name.to_string()));
     }
 
-    let config = config_loader.load().await;
-    let client = Client::new(&config);
+    if let Some(endpoint_url) = endpoint_url {
+        config_loader = config_loader.endpoint_url(endpoint_url);
+    }
 
-    Ok(client)
+    config_loader.load().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_error_kind_detects_credential_failures() {
+        let typed = anyhow::Error::new(CredentialsError {
+            profile: "prod".to_string(),
+            reason: "the SSO session has expired".to_string(),
+        });
+        assert_eq!(error_kind(&typed), AwsErrorKind::Credentials);
+
+        let expired = anyhow!("service error: ExpiredTokenException: The security token included in the request is expired");
+        assert_eq!(error_kind(&expired), AwsErrorKind::Credentials);
+
+        let wrapped = anyhow!("UnrecognizedClientException: invalid token").context("Query failed for app/prod");
+        assert_eq!(error_kind(&wrapped), AwsErrorKind::Credentials);
+
+        let other = anyhow!("ResourceNotFoundException: The specified log group does not exist");
+        assert_eq!(error_kind(&other), AwsErrorKind::Other);
+    }
+
+    #[tokio::test]
+    async fn test_custom_endpoint_receives_requests() {
+        use aws_sdk_cloudwatchlogs::config::retry::RetryConfig;
+        use tokio::io::AsyncReadExt;
+
+        // A stand-in for LocalStack: capture the first request's head, then hang up
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let received = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut head = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                match socket.read(&mut buf).await.unwrap() {
+                    0 => break,
+                    n => head.extend_from_slice(&buf[..n]),
+                }
+            }
+            String::from_utf8_lossy(&head).into_owned()
+        });
+
+        let sdk_config = load_sdk_config(None, Some("us-east-1"), Some(&endpoint)).await;
+        assert_eq!(sdk_config.endpoint_url(), Some(endpoint.as_str()));
+
+        // Static credentials so the request is signed and sent without a real profile
+        let config = aws_sdk_cloudwatchlogs::config::Builder::from(&sdk_config)
+            .credentials_provider(Credentials::new("AKID", "secret", None, None, "test"))
+            .retry_config(RetryConfig::disabled())
+            .build();
+        let client = Client::from_conf(config);
+        assert!(client.describe_log_groups().send().await.is_err());
+
+        let head = received.await.unwrap();
+        assert!(head.starts_with("POST / HTTP/1.1"), "{}", head);
+        let host = format!("host: {}", endpoint.trim_start_matches("http://"));
+        assert!(head.to_lowercase().contains(&host), "{}", head);
+    }
 }
@@ -0,0 +1,74 @@
+use crate::time::TimeRange;
+use chrono::SecondsFormat;
+
+/// Logs Insights query a console link opens for a result's log group (--hyperlinks)
+#[derive(Debug, Clone)]
+pub struct ConsoleQuery {
+    /// Region for entries that don't carry one (--region / AWS_REGION)
+    pub default_region: Option<String>,
+    pub time_range: TimeRange,
+    pub query: String,
+}
+
+impl ConsoleQuery {
+    /// Deep link for a log group, or None when its region isn't known
+    pub fn url(&self, region: Option<&str>, log_group: &str) -> Option<String> {
+        let region = region.or(self.default_region.as_deref())?;
+        Some(insights_url(region, log_group, &self.time_range, &self.query))
+    }
+}
+
+/// CloudWatch console URL opening Logs Insights on one log group, time range and query
+/// The console keeps its state in the fragment as `~(key~'value~...)`, with `?`/`=` written as
+/// `$3F`/`$3D` and each value percent-encoded using `*` instead of `%`
+pub fn insights_url(region: &str, log_group: &str, time_range: &TimeRange, query: &str) -> String {
+    let timestamp = |t: &chrono::DateTime<chrono::Utc>| t.to_rfc3339_opts(SecondsFormat::Millis, true);
+    format!(
+        "https://{region}.console.aws.amazon.com/cloudwatch/home?region={region}#logsV2:logs-insights\
+         $3FqueryDetail$3D~(end~'{end}~start~'{start}~timeType~'ABSOLUTE~tz~'UTC~editorString~'{query}~source~(~'{group}))",
+        region = region,
+        end = encode(&timestamp(&time_range.end)),
+        start = encode(&timestamp(&time_range.start)),
+        query = encode(query),
+        group = encode(log_group),
+    )
+}
+
+/// Percent-encode with `*` as the escape, leaving only alphanumerics and `-_.` as is
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("*{:02x}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_insights_url() {
+        let end = Utc.with_ymd_and_hms(2026, 1, 31, 12, 0, 0).unwrap();
+        let range = TimeRange::ending_at("1h", end).unwrap();
+
+        let url = insights_url("us-east-1", "/aws/lambda/api", &range, "fields @message\n| limit 10");
+        assert_eq!(
+            url,
+            "https://us-east-1.console.aws.amazon.com/cloudwatch/home?region=us-east-1#logsV2:logs-insights\
+             $3FqueryDetail$3D~(end~'2026-01-31T12*3a00*3a00.000Z~start~'2026-01-31T11*3a00*3a00.000Z\
+             ~timeType~'ABSOLUTE~tz~'UTC~editorString~'fields*20*40message*0a*7c*20limit*2010\
+             ~source~(~'*2faws*2flambda*2fapi))"
+        );
+
+        let links = ConsoleQuery { default_region: Some("eu-west-1".to_string()), time_range: range, query: String::new() };
+        assert!(links.url(None, "app").unwrap().starts_with("https://eu-west-1.console"));
+        assert!(links.url(Some("ap-northeast-1"), "app").unwrap().contains("region=ap-northeast-1#"));
+        let no_region = ConsoleQuery { default_region: None, ..links };
+        assert_eq!(no_region.url(None, "app"), None);
+    }
+}
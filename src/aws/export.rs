@@ -0,0 +1,135 @@
+use crate::time::TimeRange;
+use anyhow::{anyhow, bail, Result};
+use aws_sdk_cloudwatchlogs::types::ExportTaskStatusCode;
+use aws_sdk_cloudwatchlogs::Client;
+use std::fmt;
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// Export tasks take minutes, so there's no point polling them as often as Insights queries
+const EXPORT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// S3 bucket and key prefix an export is written to (`--export-s3 bucket/prefix`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct S3Destination {
+    pub bucket: String,
+    pub prefix: Option<String>,
+}
+
+impl S3Destination {
+    /// Parse `bucket`, `bucket/prefix` or `s3://bucket/prefix`
+    pub fn parse(input: &str) -> Result<Self> {
+        let path = input.trim().trim_start_matches("s3://").trim_end_matches('/');
+        let (bucket, prefix) = match path.split_once('/') {
+            Some((bucket, prefix)) => (bucket, Some(prefix.trim_matches('/').to_string())),
+            None => (path, None),
+        };
+        if bucket.is_empty() {
+            bail!("Invalid --export-s3 '{}': expected a bucket, optionally followed by /prefix", input);
+        }
+        Ok(Self { bucket: bucket.to_string(), prefix: prefix.filter(|p| !p.is_empty()) })
+    }
+
+    /// Where CloudWatch writes a task's objects: one gzipped file per log stream under `<prefix>/<task id>/`
+    pub fn task_url(&self, task_id: &str) -> String {
+        format!("{}/{}/", self, task_id)
+    }
+}
+
+impl fmt::Display for S3Destination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.prefix {
+            Some(prefix) => write!(f, "s3://{}/{}", self.bucket, prefix),
+            None => write!(f, "s3://{}", self.bucket),
+        }
+    }
+}
+
+/// Start exporting a log group's events in a time range to S3; returns the task id
+/// The bucket policy must allow the CloudWatch Logs service principal to write to it
+pub async fn start_export(
+    client: &Client,
+    log_group: &str,
+    time_range: &TimeRange,
+    destination: &S3Destination,
+    stream_prefix: Option<&str>,
+) -> Result<String> {
+    let response = client
+        .create_export_task()
+        .task_name(format!("log-hound-{}", time_range.start.format("%Y%m%d%H%M%S")))
+        .log_group_name(log_group)
+        .set_log_stream_name_prefix(stream_prefix.map(str::to_string))
+        .from(time_range.start.timestamp_millis())
+        .to(time_range.end.timestamp_millis())
+        .destination(&destination.bucket)
+        .set_destination_prefix(destination.prefix.clone())
+        .send()
+        .await
+        .map_err(|e| anyhow!("{}", aws_sdk_cloudwatchlogs::error::DisplayErrorContext(e)))?;
+
+    response.task_id.ok_or_else(|| anyhow!("No export task ID returned"))
+}
+
+/// Poll an export task until it completes, calling `on_status` whenever its status changes
+/// Fails if the task fails or is cancelled
+pub async fn wait_for_export(client: &Client, task_id: &str, mut on_status: impl FnMut(&str)) -> Result<()> {
+    let mut last_status = String::new();
+    loop {
+        let response = client
+            .describe_export_tasks()
+            .task_id(task_id)
+            .send()
+            .await
+            .map_err(|e| anyhow!("{}", aws_sdk_cloudwatchlogs::error::DisplayErrorContext(e)))?;
+
+        let status = response
+            .export_tasks
+            .unwrap_or_default()
+            .into_iter()
+            .next()
+            .and_then(|task| task.status)
+            .ok_or_else(|| anyhow!("Export task {} not found", task_id))?;
+        let code = status.code.unwrap_or(ExportTaskStatusCode::Pending);
+
+        if code.as_str() != last_status {
+            last_status = code.as_str().to_string();
+            on_status(&last_status);
+        }
+
+        match code {
+            ExportTaskStatusCode::Completed => return Ok(()),
+            ExportTaskStatusCode::Failed | ExportTaskStatusCode::Cancelled => {
+                bail!(
+                    "Export task {} {}: {}",
+                    task_id,
+                    code.as_str().to_lowercase(),
+                    status.message.as_deref().unwrap_or("no reason given")
+                );
+            }
+            _ => sleep(EXPORT_POLL_INTERVAL).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_s3_destination() {
+        let destination = S3Destination::parse("s3://log-archive/exports/app/").unwrap();
+        assert_eq!(
+            destination,
+            S3Destination { bucket: "log-archive".to_string(), prefix: Some("exports/app".to_string()) }
+        );
+        assert_eq!(destination.to_string(), "s3://log-archive/exports/app");
+        assert_eq!(destination.task_url("abc-123"), "s3://log-archive/exports/app/abc-123/");
+
+        let destination = S3Destination::parse("log-archive").unwrap();
+        assert_eq!(destination.prefix, None);
+        assert_eq!(destination.task_url("abc-123"), "s3://log-archive/abc-123/");
+
+        assert!(S3Destination::parse("s3://").is_err());
+        assert!(S3Destination::parse("/exports").is_err());
+    }
+}
@@ -1,11 +1,42 @@
 use crate::aws::multi_region::{MultiRegionClientPool, RegionalLogGroup};
+use crate::cli::MatchField;
+use crate::deadline::{self, Deadline};
+use crate::time::TimeRange;
 use anyhow::{anyhow, Result};
+use aws_sdk_cloudwatchlogs::types::{LiveTailSessionLogEvent, StartLiveTailResponseStream};
 use aws_sdk_cloudwatchlogs::Client;
 use chrono::{DateTime, NaiveDateTime, Utc};
+use futures::stream::{FuturesUnordered, Stream};
+use regex::Regex;
 use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 use tokio::time::sleep;
 
+/// StartLiveTail accepts at most this many log groups per session
+const LIVE_TAIL_MAX_GROUPS: usize = 10;
+
+/// Delay before the second poll of a running Insights query; doubles after each poll
+const POLL_INITIAL_DELAY: Duration = Duration::from_millis(50);
+/// Longest delay between polls of a running Insights query
+const POLL_MAX_DELAY: Duration = Duration::from_secs(1);
+
+/// How long to wait for StopQuery calls after a --timeout before exiting anyway
+const QUERY_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Insights returns at most this many rows per query
+const INSIGHTS_MAX_ROWS: i32 = 10_000;
+
+/// Logs Insights list price per GB of data scanned (us-east-1)
+const INSIGHTS_USD_PER_GB: f64 = 0.005;
+
+/// How much of the end of the range --explain-query probes
+pub const PROBE_WINDOW_MINUTES: i64 = 5;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct LogEntry {
     pub timestamp: DateTime<Utc>,
@@ -15,6 +46,59 @@ pub struct LogEntry {
     pub log_stream: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub region: Option<String>,
+    /// Other log groups the same line was found in (--merge-duplicates-across-groups)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub also_in: Vec<String>,
+    /// Insights record pointer (@ptr), for fetching the whole original event with `fetch-context`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ptr: Option<String>,
+}
+
+/// Insights query statistics, summed over every log group in a search
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct QueryStats {
+    pub bytes_scanned: f64,
+    pub records_matched: f64,
+    pub records_scanned: f64,
+}
+
+impl QueryStats {
+    fn from_statistics(statistics: &aws_sdk_cloudwatchlogs::types::QueryStatistics) -> Self {
+        Self {
+            bytes_scanned: statistics.bytes_scanned,
+            records_matched: statistics.records_matched,
+            records_scanned: statistics.records_scanned,
+        }
+    }
+
+    pub fn add(&mut self, other: &QueryStats) {
+        self.bytes_scanned += other.bytes_scanned;
+        self.records_matched += other.records_matched;
+        self.records_scanned += other.records_scanned;
+    }
+
+    /// Approximate cost of the scan at the Insights list price
+    pub fn estimated_cost_usd(&self) -> f64 {
+        self.bytes_scanned / (1024.0 * 1024.0 * 1024.0) * INSIGHTS_USD_PER_GB
+    }
+
+    /// Scale stats measured over `probed` up to `full`, assuming logs arrive at the same rate throughout
+    pub fn extrapolate(&self, probed: &TimeRange, full: &TimeRange) -> Self {
+        let probed_ms = (probed.end - probed.start).num_milliseconds();
+        let full_ms = (full.end - full.start).num_milliseconds();
+        let factor = if probed_ms > 0 { full_ms as f64 / probed_ms as f64 } else { 1.0 };
+        Self {
+            bytes_scanned: self.bytes_scanned * factor,
+            records_matched: self.records_matched * factor,
+            records_scanned: self.records_scanned * factor,
+        }
+    }
+}
+
+/// Results of a multi-group search: one result per log group, in order, plus the combined statistics
+pub struct SearchResults {
+    pub groups: Vec<Result<Vec<LogEntry>>>,
+    pub stats: QueryStats,
 }
 
 /// Search parameters including patterns and exclusions
@@ -23,12 +107,253 @@ pub struct SearchParams {
     pub patterns: Vec<String>,
     pub exclude: Vec<String>,
     pub limit: i32,
+    /// Keep the oldest `limit` entries in the window instead of the newest
+    pub oldest: bool,
+    /// Only search this log stream (or streams with this prefix)
+    pub stream: Option<StreamFilter>,
+    /// Raw Insights query template sent instead of the generated query (--query)
+    pub query: Option<String>,
+    /// Match patterns and excludes as whole words only
+    pub word: bool,
+    /// Select messages that don't match the patterns (excludes still apply)
+    pub invert_match: bool,
+    /// Match patterns against the message, the log stream, or either
+    pub match_field: MatchField,
+    /// Only messages starting with this (an Insights regex anchored with ^)
+    pub starts_with: Option<String>,
+    /// Only messages ending with this (an Insights regex anchored with $)
+    pub ends_with: Option<String>,
+    /// Split the time range into successive queries when one hits the Insights row cap
+    pub split_windows: bool,
+    /// Give up on log groups still being searched when this passes (--timeout)
+    pub deadline: Option<Deadline>,
 }
 
 impl SearchParams {
     pub fn new(patterns: Vec<String>, exclude: Vec<String>, limit: i32) -> Self {
-        Self { patterns, exclude, limit }
+        Self { patterns, exclude, limit, oldest: false, stream: None, query: None, word: false, invert_match: false, match_field: MatchField::Message, starts_with: None, ends_with: None, split_windows: false, deadline: None }
+    }
+
+    pub fn with_oldest(mut self, oldest: bool) -> Self {
+        self.oldest = oldest;
+        self
+    }
+
+    pub fn with_stream(mut self, stream: Option<StreamFilter>) -> Self {
+        self.stream = stream;
+        self
+    }
+
+    pub fn with_query(mut self, query: Option<String>) -> Self {
+        self.query = query;
+        self
+    }
+
+    pub fn with_word(mut self, word: bool) -> Self {
+        self.word = word;
+        self
+    }
+
+    pub fn with_invert_match(mut self, invert_match: bool) -> Self {
+        self.invert_match = invert_match;
+        self
+    }
+
+    pub fn with_match_field(mut self, match_field: MatchField) -> Self {
+        self.match_field = match_field;
+        self
+    }
+
+    pub fn with_starts_with(mut self, starts_with: Option<String>) -> Self {
+        self.starts_with = starts_with;
+        self
+    }
+
+    pub fn with_ends_with(mut self, ends_with: Option<String>) -> Self {
+        self.ends_with = ends_with;
+        self
+    }
+
+    pub fn with_split_windows(mut self, split_windows: bool) -> Self {
+        self.split_windows = split_windows;
+        self
     }
+
+    pub fn with_deadline(mut self, deadline: Option<Deadline>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// The Insights query sent for these parameters
+    pub fn insights_query(&self) -> String {
+        build_insights_query(self)
+    }
+}
+
+/// Restricts a search to log streams within the log groups (--stream / --stream-prefix)
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamFilter {
+    Name(String),
+    Prefix(String),
+}
+
+impl StreamFilter {
+    /// Insights filter condition on @logStream
+    fn insights_condition(&self) -> String {
+        match self {
+            Self::Name(name) => format!("@logStream = \"{}\"", name.replace('\\', "\\\\").replace('"', "\\\"")),
+            Self::Prefix(prefix) => format!("@logStream like /^{}/", regex::escape(prefix).replace('/', "\\/")),
+        }
+    }
+}
+
+/// Client-side filter for live tail events, matching the Insights query semantics:
+/// every pattern and no exclude must match, each as a regex (literal if it isn't a valid regex)
+#[derive(Debug, Clone)]
+struct LiveTailFilter {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+    invert_match: bool,
+    /// --starts-with/--ends-with, always required
+    anchors: Vec<Regex>,
+}
+
+impl LiveTailFilter {
+    fn new(params: &SearchParams) -> Self {
+        let wrap = |p: &str| if params.word { format!(r"\b(?:{})\b", p) } else { p.to_string() };
+        let compile = |patterns: &[String]| -> Vec<Regex> {
+            patterns
+                .iter()
+                .filter(|p| !p.is_empty())
+                .filter_map(|p| Regex::new(&wrap(p)).or_else(|_| Regex::new(&wrap(&regex::escape(p)))).ok())
+                .collect()
+        };
+
+        Self {
+            include: compile(&params.patterns),
+            exclude: compile(&params.exclude),
+            invert_match: params.invert_match,
+            anchors: anchor_patterns(params).iter().filter_map(|p| Regex::new(p).ok()).collect(),
+        }
+    }
+
+    fn matches(&self, message: &str) -> bool {
+        let included = self.include.iter().all(|r| r.is_match(message));
+        let selected = if self.invert_match && !self.include.is_empty() { !included } else { included };
+        selected
+            && self.anchors.iter().all(|r| r.is_match(message))
+            && !self.exclude.iter().any(|r| r.is_match(message))
+    }
+}
+
+/// Whether a search starting at `start` reaches back before a log group's retention window
+/// Groups without a retention setting keep logs forever
+pub fn predates_retention(start: DateTime<Utc>, now: DateTime<Utc>, retention_days: Option<i32>) -> bool {
+    match retention_days {
+        Some(days) => start < now - chrono::Duration::days(days as i64),
+        None => false,
+    }
+}
+
+/// Delays between polls of a running query: short at first so quick queries return fast,
+/// then doubling up to POLL_MAX_DELAY so slow ones don't hammer the API
+fn poll_delays() -> impl Iterator<Item = Duration> {
+    std::iter::successors(Some(POLL_INITIAL_DELAY), |delay| Some((*delay * 2).min(POLL_MAX_DELAY)))
+}
+
+/// Sort entries by timestamp (ascending) and keep the newest `limit`, or the oldest with `oldest`
+/// Returns whether any entries were dropped
+pub fn keep_window(entries: &mut Vec<LogEntry>, limit: usize, oldest: bool) -> bool {
+    entries.sort_by_key(|e| e.timestamp);
+    if entries.len() <= limit {
+        return false;
+    }
+
+    if oldest {
+        entries.truncate(limit);
+    } else {
+        entries.drain(..entries.len() - limit);
+    }
+    true
+}
+
+/// Query a time range page by page. Each query returns at most `page_cap` rows from the kept end of
+/// the window (newest, or oldest with `oldest`); when one comes back full and the limit isn't reached
+/// yet, the window shrinks to the part not covered and is queried again (with `split_windows`).
+/// Insights works in whole seconds, so the boundary second is queried twice and its repeats dropped.
+async fn collect_windows<F, Fut>(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    params: &SearchParams,
+    page_cap: i32,
+    mut run_query: F,
+) -> Result<(Vec<LogEntry>, QueryStats)>
+where
+    F: FnMut(DateTime<Utc>, DateTime<Utc>, i32) -> Fut,
+    Fut: std::future::Future<Output = Result<(Vec<LogEntry>, QueryStats)>>,
+{
+    let second = chrono::Duration::seconds(1);
+    let whole_second = |t: DateTime<Utc>| DateTime::from_timestamp(t.timestamp(), 0).unwrap_or(t);
+    let limit = params.limit.max(0) as usize;
+
+    let mut entries: Vec<LogEntry> = Vec::new();
+    let mut stats = QueryStats::default();
+    let (mut start, mut end) = (whole_second(start), whole_second(end));
+    // Entries of the boundary second already collected from the previous page
+    let mut boundary: Vec<LogEntry> = Vec::new();
+
+    loop {
+        // Ask for the repeats of the boundary second on top of what's still missing
+        let page_limit = (limit - entries.len() + boundary.len()).min(page_cap as usize);
+        let (page, page_stats) = run_query(start, end, page_limit as i32).await?;
+        stats.add(&page_stats);
+
+        let full = page.len() >= page_limit;
+        let edge = if params.oldest {
+            page.iter().map(|e| e.timestamp).max()
+        } else {
+            page.iter().map(|e| e.timestamp).min()
+        };
+
+        for entry in page {
+            let repeat = boundary.iter().position(|b| {
+                b.timestamp == entry.timestamp && b.message == entry.message && b.log_stream == entry.log_stream
+            });
+            match repeat {
+                Some(i) => {
+                    boundary.swap_remove(i);
+                }
+                None => entries.push(entry),
+            }
+        }
+
+        // A raw --query decides its own limit, so it can't be paged
+        let more = params.split_windows && params.query.is_none() && full && entries.len() < limit;
+        let (Some(edge), true) = (edge, more) else {
+            break;
+        };
+
+        // Continue from the boundary second; skip past it if the whole page fell inside it
+        let edge = whole_second(edge);
+        let in_edge_second = |e: &&LogEntry| whole_second(e.timestamp) == edge;
+        boundary = entries.iter().filter(in_edge_second).cloned().collect();
+        if params.oldest && edge > start {
+            start = edge;
+        } else if params.oldest {
+            start = edge + second;
+            boundary.clear();
+        } else if edge < end {
+            end = edge;
+        } else {
+            end = edge - second;
+            boundary.clear();
+        }
+        if start > end {
+            break;
+        }
+    }
+
+    Ok((entries, stats))
 }
 
 pub struct LogSearcher {
@@ -40,6 +365,19 @@ impl LogSearcher {
         Self { client }
     }
 
+    /// Fetch every field of the event an Insights @ptr points to
+    pub async fn get_log_record(&self, ptr: &str) -> Result<BTreeMap<String, String>> {
+        let response = self
+            .client
+            .get_log_record()
+            .log_record_pointer(ptr)
+            .send()
+            .await
+            .map_err(|e| anyhow!("{}", aws_sdk_cloudwatchlogs::error::DisplayErrorContext(e)))?;
+
+        Ok(response.log_record.unwrap_or_default().into_iter().collect())
+    }
+
     /// List log groups, optionally filtered by prefix
     pub async fn list_log_groups(&self, prefix: Option<&str>) -> Result<Vec<String>> {
         let mut log_groups = Vec::new();
@@ -74,132 +412,126 @@ impl LogSearcher {
 
         Ok(log_groups)
     }
+}
 
-    /// Search a single log group with the given patterns (AND condition) and exclusions
-    pub async fn search_log_group(
-        &self,
-        log_group: &str,
-        params: &SearchParams,
-        start_time: DateTime<Utc>,
-        end_time: DateTime<Utc>,
-    ) -> Result<Vec<LogEntry>> {
-        let query = build_insights_query(&params.patterns, &params.exclude, params.limit);
-
-        if std::env::var("LOG_HOUND_DEBUG").is_ok() {
-            eprintln!("DEBUG: Log group: {}", log_group);
-            eprintln!("DEBUG: Query:\n{}", query);
-        }
+/// Insights filter conditions for the stream, include patterns (AND) and exclude patterns (NOT)
+/// Regexes for --starts-with/--ends-with: the text is literal and case-insensitive, like Kamal's,
+/// and --word puts a word boundary on its open side
+fn anchor_patterns(params: &SearchParams) -> Vec<String> {
+    let boundary = if params.word { r"\b" } else { "" };
+    let prefix = params.starts_with.as_deref().filter(|p| !p.is_empty()).map(|p| format!("(?i)^{}{}", regex::escape(p), boundary));
+    let suffix = params.ends_with.as_deref().filter(|p| !p.is_empty()).map(|p| format!("(?i){}{}$", boundary, regex::escape(p)));
+    prefix.into_iter().chain(suffix).collect()
+}
 
-        let start_epoch = start_time.timestamp();
-        let end_epoch = end_time.timestamp();
+fn filter_conditions(params: &SearchParams) -> Vec<String> {
+    let mut filter_conditions: Vec<String> = Vec::new();
 
-        // Start the query
-        let start_response = self
-            .client
-            .start_query()
-            .log_group_name(log_group)
-            .start_time(start_epoch)
-            .end_time(end_epoch)
-            .query_string(&query)
-            .send()
-            .await?;
+    // Scope to a log stream first
+    if let Some(stream) = &params.stream {
+        filter_conditions.push(stream.insights_condition());
+    }
 
-        let query_id = start_response
-            .query_id
-            .ok_or_else(|| anyhow!("No query ID returned"))?;
+    // --word wraps each pattern in word boundaries
+    let regex = |p: &str| {
+        let escaped = p.replace('\'', "\\'");
+        if params.word {
+            format!(r"\b{}\b", escaped)
+        } else {
+            escaped
+        }
+    };
 
-        // Poll for results
-        let results = self.poll_query_results(&query_id, log_group).await?;
+    // --match-field picks the field(s) a pattern is looked for in; with `any` either will do
+    let found = |p: &str| match params.match_field {
+        MatchField::Message => format!("@message like /{}/", regex(p)),
+        MatchField::Stream => format!("@logStream like /{}/", regex(p)),
+        MatchField::Any => format!("(@message like /{0}/ or @logStream like /{0}/)", regex(p)),
+    };
+    let missing = |p: &str| match params.match_field {
+        MatchField::Message => format!("@message not like /{}/", regex(p)),
+        MatchField::Stream => format!("@logStream not like /{}/", regex(p)),
+        MatchField::Any => format!("(@message not like /{0}/ and @logStream not like /{0}/)", regex(p)),
+    };
 
-        Ok(results)
+    // Add include patterns (AND condition); empty patterns match everything
+    // --invert-match negates them as a whole: a message is kept when any pattern is missing
+    let includes: Vec<&String> = params.patterns.iter().filter(|p| !p.is_empty()).collect();
+    if params.invert_match && !includes.is_empty() {
+        let negated: Vec<String> = includes.iter().map(|p| missing(p)).collect();
+        if negated.len() == 1 {
+            filter_conditions.extend(negated);
+        } else {
+            filter_conditions.push(format!("({})", negated.join(" or ")));
+        }
+    } else {
+        for p in includes {
+            filter_conditions.push(found(p));
+        }
     }
 
-    async fn poll_query_results(
-        &self,
-        query_id: &str,
-        log_group: &str,
-    ) -> Result<Vec<LogEntry>> {
-        let mut entries = Vec::new();
-
-        loop {
-            let response = self
-                .client
-                .get_query_results()
-                .query_id(query_id)
-                .send()
-                .await?;
-
-            let status = response
-                .status
-                .map(|s| s.as_str().to_string())
-                .unwrap_or_default();
-
-            match status.as_str() {
-                "Complete" => {
-                    if let Some(results) = response.results {
-                        for result in results {
-                            let entry = parse_log_result(&result, log_group);
-                            if let Some(e) = entry {
-                                entries.push(e);
-                            }
-                        }
-                    }
-                    break;
-                }
-                "Failed" | "Cancelled" | "Timeout" => {
-                    return Err(anyhow!("Query {}: {}", query_id, status));
-                }
-                _ => {
-                    // Still running, wait and poll again
-                    sleep(Duration::from_millis(500)).await;
-                }
-            }
-        }
+    // Anchored matches are extra AND conditions, unaffected by --invert-match
+    for anchor in anchor_patterns(params) {
+        filter_conditions.push(format!("@message like /{}/", anchor.replace('/', "\\/").replace('\'', "\\'")));
+    }
 
-        Ok(entries)
+    // Add exclude patterns (NOT condition)
+    for p in params.exclude.iter().filter(|p| !p.is_empty()) {
+        filter_conditions.push(format!("@message not like /{}/", regex(p)));
     }
+
+    filter_conditions
 }
 
 /// Build CloudWatch Insights query for message filtering
 /// Supports include patterns (AND) and exclude patterns (NOT)
-fn build_insights_query(patterns: &[String], exclude: &[String], limit: i32) -> String {
-    let mut filter_conditions: Vec<String> = Vec::new();
-
-    // Add include patterns (AND condition)
-    for p in patterns {
-        let escaped = p.replace('\'', "\\'");
-        filter_conditions.push(format!("@message like /{}/", escaped));
+/// With no patterns and no excludes the filter line is omitted, matching everything in range
+fn build_insights_query(params: &SearchParams) -> String {
+    if let Some(template) = &params.query {
+        return render_query_template(template, params);
     }
 
-    // Add exclude patterns (NOT condition)
-    for p in exclude {
-        let escaped = p.replace('\'', "\\'");
-        filter_conditions.push(format!("@message not like /{}/", escaped));
+    let filter_conditions = filter_conditions(params);
+
+    let mut query = String::from("fields @timestamp, @message, @logStream, @ptr\n");
+    if !filter_conditions.is_empty() {
+        query.push_str(&format!("| filter {}\n", filter_conditions.join(" and ")));
     }
+    // The limit applies after sorting, so the sort order decides which end of the window is kept
+    let order = if params.oldest { "asc" } else { "desc" };
+    query.push_str(&format!("| sort @timestamp {}\n| limit {}", order, params.limit));
 
-    let filter_clause = if filter_conditions.is_empty() {
-        "1=1".to_string() // No filter, match all
+    query
+}
+
+/// Fill in a --query template: `{limit}` becomes the limit and `{patterns}` the filter
+/// expression built from the patterns, excludes and stream (`ispresent(@message)` when there are none)
+fn render_query_template(template: &str, params: &SearchParams) -> String {
+    let conditions = filter_conditions(params);
+    let filter = if conditions.is_empty() {
+        "ispresent(@message)".to_string()
     } else {
-        filter_conditions.join(" and ")
+        conditions.join(" and ")
     };
 
-    format!(
-        r#"fields @timestamp, @message, @logStream
-| filter {}
-| sort @timestamp desc
-| limit {}"#,
-        filter_clause, limit
-    )
+    template
+        .replace("{limit}", &params.limit.to_string())
+        .replace("{patterns}", &filter)
 }
 
 /// Parse a Log Insights result row into a LogEntry
+/// Rows without a usable @timestamp (custom or `stats` queries) are kept at `fallback_time`,
+/// the end of the queried window; only rows with nothing to show are dropped
 fn parse_log_result(
     result: &[aws_sdk_cloudwatchlogs::types::ResultField],
     log_group: &str,
+    fallback_time: DateTime<Utc>,
 ) -> Option<LogEntry> {
     let mut timestamp: Option<DateTime<Utc>> = None;
     let mut message: Option<String> = None;
     let mut log_stream: Option<String> = None;
+    let mut ptr: Option<String> = None;
+    let mut other_fields: Vec<String> = Vec::new();
 
     for field in result {
         match field.field.as_deref() {
@@ -215,16 +547,83 @@ fn parse_log_result(
             Some("@logStream") => {
                 log_stream = field.value.clone();
             }
+            Some("@ptr") => {
+                ptr = field.value.clone();
+            }
+            // Custom queries may return other fields; keep them as the message if @message is absent
+            Some(name) => {
+                if let Some(val) = &field.value {
+                    other_fields.push(format!("{}={}", name, val));
+                }
+            }
             _ => {}
         }
     }
 
+    if message.is_none() && !other_fields.is_empty() {
+        message = Some(other_fields.join(" "));
+    }
+    let message = message?;
+
+    let timestamp = timestamp.unwrap_or_else(|| {
+        tracing::debug!(%log_group, %fallback_time, %message, "result row has no @timestamp, using the end of the window");
+        fallback_time
+    });
+
     Some(LogEntry {
-        timestamp: timestamp?,
-        message: message?,
+        timestamp,
+        message,
         log_group: log_group.to_string(),
         log_stream,
         region: None,
+        also_in: Vec::new(),
+        ptr,
+    })
+}
+
+/// Look up the ARN of each log group, which StartLiveTail requires instead of the name
+/// Returns (arn, name) pairs
+async fn resolve_log_group_arns(client: &Client, log_groups: &[String]) -> Result<Vec<(String, String)>> {
+    let mut arns = Vec::new();
+
+    for name in log_groups {
+        let response = client.describe_log_groups().log_group_name_prefix(name).send().await?;
+
+        let arn = response
+            .log_groups
+            .unwrap_or_default()
+            .into_iter()
+            .find(|g| g.log_group_name.as_deref() == Some(name.as_str()))
+            .and_then(|g| g.log_group_arn.or_else(|| g.arn.map(|a| a.trim_end_matches(":*").to_string())))
+            .ok_or_else(|| anyhow!("Log group not found: {}", name))?;
+
+        arns.push((arn, name.clone()));
+    }
+
+    Ok(arns)
+}
+
+/// Convert a Live Tail event into a LogEntry, naming the log group as the user did rather than by ARN
+fn parse_live_tail_event(
+    event: &LiveTailSessionLogEvent,
+    names: &HashMap<String, String>,
+    region: Option<&str>,
+) -> Option<LogEntry> {
+    let identifier = event.log_group_identifier().unwrap_or_default();
+    let log_group = names
+        .get(identifier.trim_end_matches(":*"))
+        .or_else(|| names.values().find(|name| identifier.ends_with(&format!(":{}", name))))
+        .cloned()
+        .unwrap_or_else(|| identifier.to_string());
+
+    Some(LogEntry {
+        timestamp: DateTime::from_timestamp_millis(event.timestamp()?)?,
+        message: event.message()?.to_string(),
+        log_group,
+        log_stream: event.log_stream_name().map(|s| s.to_string()),
+        region: region.map(|r| r.to_string()),
+        also_in: Vec::new(),
+        ptr: None,
     })
 }
 
@@ -254,15 +653,47 @@ fn parse_cloudwatch_timestamp(val: &str) -> Option<DateTime<Utc>> {
 /// Multi-region log searcher that can search across different AWS regions
 pub struct MultiRegionSearcher {
     client_pool: MultiRegionClientPool,
+    /// StopQuery calls for Insights queries abandoned at the deadline
+    query_stops: Mutex<Vec<JoinHandle<()>>>,
 }
 
 impl MultiRegionSearcher {
     pub fn new(profile: Option<String>, default_region: Option<String>) -> Self {
         Self {
             client_pool: MultiRegionClientPool::new(profile, default_region),
+            query_stops: Mutex::new(Vec::new()),
         }
     }
 
+    /// Wait (briefly) for the queries abandoned at a --timeout deadline to be stopped, so they
+    /// don't keep scanning (and billing) after log-hound exits
+    pub async fn stop_abandoned_queries(&self) {
+        let stops = std::mem::take(&mut *self.query_stops.lock().unwrap());
+        let _ = tokio::time::timeout(QUERY_STOP_TIMEOUT, futures::future::join_all(stops)).await;
+    }
+
+    /// The default AWS profile for searches without a per-region/per-group override
+    pub fn profile(&self) -> Option<&str> {
+        self.client_pool.profile()
+    }
+
+    /// Switch to a different default AWS profile (e.g. from the TUI)
+    pub fn set_profile(&mut self, profile: Option<String>) {
+        self.client_pool.set_profile(profile);
+    }
+
+    /// Use a different AWS profile for log groups in the given regions
+    pub fn with_region_profiles(mut self, region_profiles: HashMap<String, String>) -> Self {
+        self.client_pool = self.client_pool.with_region_profiles(region_profiles);
+        self
+    }
+
+    /// Send every request to this endpoint instead of AWS's (--endpoint-url)
+    pub fn with_endpoint_url(mut self, endpoint_url: Option<String>) -> Self {
+        self.client_pool = self.client_pool.with_endpoint_url(endpoint_url);
+        self
+    }
+
     /// Search multiple log groups, potentially across different regions
     pub async fn search_log_groups(
         &self,
@@ -270,22 +701,51 @@ impl MultiRegionSearcher {
         params: &SearchParams,
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
-    ) -> Vec<Result<Vec<LogEntry>>> {
+    ) -> SearchResults {
         let regional_groups = RegionalLogGroup::parse_many(log_groups);
 
         let futures: Vec<_> = regional_groups
             .into_iter()
             .map(|rg| {
-                self.search_single_log_group(
-                    rg,
-                    params.clone(),
-                    start_time,
-                    end_time,
+                deadline::within(
+                    params.deadline,
+                    self.search_single_log_group(rg, params.clone(), start_time, end_time),
                 )
             })
             .collect();
 
-        futures::future::join_all(futures).await
+        let mut stats = QueryStats::default();
+        let groups = futures::future::join_all(futures)
+            .await
+            .into_iter()
+            .map(|result| {
+                result.map(|(entries, group_stats)| {
+                    stats.add(&group_stats);
+                    entries
+                })
+            })
+            .collect();
+
+        SearchResults { groups, stats }
+    }
+
+    /// Search multiple log groups like `search_log_groups`, but yield each group's results (with its
+    /// index in `log_groups`) as soon as that group finishes, for output that streams
+    pub fn search_log_groups_as_completed<'a>(
+        &'a self,
+        log_groups: &[String],
+        params: &'a SearchParams,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> impl Stream<Item = (usize, Result<(Vec<LogEntry>, QueryStats)>)> + 'a {
+        RegionalLogGroup::parse_many(log_groups)
+            .into_iter()
+            .enumerate()
+            .map(|(index, rg)| async move {
+                let search = self.search_single_log_group(rg, params.clone(), start_time, end_time);
+                (index, deadline::within(params.deadline, search).await)
+            })
+            .collect::<FuturesUnordered<_>>()
     }
 
     async fn search_single_log_group(
@@ -294,78 +754,120 @@ impl MultiRegionSearcher {
         params: SearchParams,
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
-    ) -> Result<Vec<LogEntry>> {
+    ) -> Result<(Vec<LogEntry>, QueryStats)> {
         let client = self
             .client_pool
-            .get_client(regional_group.region.as_deref())
+            .get_client(regional_group.profile.as_deref(), regional_group.region.as_deref())
             .await?;
 
-        let query = build_insights_query(&params.patterns, &params.exclude, params.limit);
+        let (client, regional_group, params) = (&client, &regional_group, &params);
+        collect_windows(start_time, end_time, params, INSIGHTS_MAX_ROWS, |start, end, limit| async move {
+            let page_params = SearchParams { limit, ..params.clone() };
+            run_insights_query(client, regional_group, &page_params, start, end, &self.query_stops).await
+        })
+        .await
+    }
 
-        if std::env::var("LOG_HOUND_DEBUG").is_ok() {
-            eprintln!(
-                "DEBUG: Region: {:?}, Log group: {}",
-                regional_group.region, regional_group.log_group
-            );
-            eprintln!("DEBUG: Query:\n{}", query);
+    /// Stream new events for the given log groups with CloudWatch Live Tail and send them through a channel
+    /// Returns once every session has started; fails if Live Tail isn't available so callers can fall back to polling
+    /// Use the stop_flag to signal when to stop following
+    pub async fn follow_log_groups(
+        &self,
+        log_groups: &[String],
+        params: &SearchParams,
+        sender: mpsc::Sender<LogEntry>,
+        stop_flag: Arc<AtomicBool>,
+    ) -> Result<()> {
+        // One session per profile/region, each limited to LIVE_TAIL_MAX_GROUPS log groups
+        let mut by_client: HashMap<(Option<String>, Option<String>), Vec<String>> = HashMap::new();
+        for rg in RegionalLogGroup::parse_many(log_groups) {
+            by_client.entry((rg.profile, rg.region)).or_default().push(rg.log_group);
         }
 
-        let start_epoch = start_time.timestamp();
-        let end_epoch = end_time.timestamp();
+        let filter = LiveTailFilter::new(params);
+        let mut streams = Vec::new();
 
-        // Start the query
-        let start_response = client
-            .start_query()
-            .log_group_name(&regional_group.log_group)
-            .start_time(start_epoch)
-            .end_time(end_epoch)
-            .query_string(&query)
-            .send()
-            .await?;
+        // Start every session before spawning readers, so an unsupported region fails the whole follow
+        for ((profile, region), groups) in by_client {
+            let client = self.client_pool.get_client(profile.as_deref(), region.as_deref()).await?;
+            let arns = resolve_log_group_arns(&client, &groups).await?;
 
-        let query_id = start_response
-            .query_id
-            .ok_or_else(|| anyhow!("No query ID returned"))?;
+            for chunk in arns.chunks(LIVE_TAIL_MAX_GROUPS) {
+                let mut request = client
+                    .start_live_tail()
+                    .set_log_group_identifiers(Some(chunk.iter().map(|(arn, _)| arn.clone()).collect()));
+                request = match &params.stream {
+                    Some(StreamFilter::Name(name)) => request.log_stream_names(name),
+                    Some(StreamFilter::Prefix(prefix)) => request.log_stream_name_prefixes(prefix),
+                    None => request,
+                };
 
-        // Poll for results
-        let mut entries = Vec::new();
+                let output = request
+                    .send()
+                    .await
+                    .map_err(|e| anyhow!("StartLiveTail failed: {}", aws_sdk_cloudwatchlogs::error::DisplayErrorContext(e)))?;
 
-        loop {
-            let response = client
-                .get_query_results()
-                .query_id(&query_id)
-                .send()
-                .await?;
-
-            let status = response
-                .status
-                .map(|s| s.as_str().to_string())
-                .unwrap_or_default();
-
-            match status.as_str() {
-                "Complete" => {
-                    if let Some(results) = response.results {
-                        for result in results {
-                            if let Some(mut entry) =
-                                parse_log_result(&result, &regional_group.log_group)
-                            {
-                                entry.region = regional_group.region.clone();
-                                entries.push(entry);
+                let names: HashMap<String, String> = chunk.iter().cloned().collect();
+                streams.push((output.response_stream, names, region.clone()));
+            }
+        }
+
+        for (mut stream, names, region) in streams {
+            let sender = sender.clone();
+            let stop_flag = stop_flag.clone();
+            let filter = filter.clone();
+
+            tokio::spawn(async move {
+                while !stop_flag.load(Ordering::Relaxed) {
+                    tokio::select! {
+                        event = stream.recv() => {
+                            let update = match event {
+                                Ok(Some(StartLiveTailResponseStream::SessionUpdate(update))) => update,
+                                Ok(Some(_)) => continue, // Session start and unknown events
+                                Ok(None) | Err(_) => break, // Session ended or timed out (after 3 hours)
+                            };
+
+                            for event in update.session_results() {
+                                let Some(entry) = parse_live_tail_event(event, &names, region.as_deref()) else {
+                                    continue;
+                                };
+                                if filter.matches(&entry.message) && sender.send(entry).await.is_err() {
+                                    return; // Receiver dropped
+                                }
                             }
                         }
+                        _ = sleep(Duration::from_millis(100)) => {
+                            // Check stop flag periodically
+                        }
                     }
-                    break;
-                }
-                "Failed" | "Cancelled" | "Timeout" => {
-                    return Err(anyhow!("Query {}: {}", query_id, status));
                 }
-                _ => {
-                    sleep(Duration::from_millis(500)).await;
-                }
-            }
+            });
         }
 
-        Ok(entries)
+        Ok(())
+    }
+
+    /// Look up the retention period (in days) of each log group
+    /// None when the group keeps logs forever or couldn't be described
+    pub async fn retention_days(&self, log_groups: &[String]) -> Vec<Option<i32>> {
+        let futures = RegionalLogGroup::parse_many(log_groups).into_iter().map(|rg| async move {
+            let client = self.client_pool.get_client(rg.profile.as_deref(), rg.region.as_deref()).await.ok()?;
+            let response = client
+                .describe_log_groups()
+                .log_group_name_prefix(&rg.log_group)
+                .send()
+                .await
+                .ok()?;
+
+            response
+                .log_groups
+                .unwrap_or_default()
+                .into_iter()
+                .find(|g| g.log_group_name.as_deref() == Some(rg.log_group.as_str()))?
+                .retention_in_days
+        });
+
+        futures::future::join_all(futures).await
     }
 
     /// List log groups from a specific region
@@ -374,7 +876,7 @@ impl MultiRegionSearcher {
         region: Option<&str>,
         prefix: Option<&str>,
     ) -> Result<Vec<String>> {
-        let client = self.client_pool.get_client(region).await?;
+        let client = self.client_pool.get_client(None, region).await?;
 
         let mut log_groups = Vec::new();
         let mut next_token: Option<String> = None;
@@ -409,3 +911,535 @@ impl MultiRegionSearcher {
         Ok(log_groups)
     }
 }
+
+/// A started Insights query; if it's dropped before finishing (the --timeout deadline passed),
+/// a StopQuery call is spawned and queued on `stops` so the query doesn't run on in the background
+struct RunningQuery<'a> {
+    client: &'a Client,
+    query_id: String,
+    stops: &'a Mutex<Vec<JoinHandle<()>>>,
+    finished: bool,
+}
+
+impl Drop for RunningQuery<'_> {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let request = self.client.stop_query().query_id(&self.query_id);
+        let stop = tokio::spawn(async move {
+            let _ = request.send().await;
+        });
+        self.stops.lock().unwrap().push(stop);
+    }
+}
+
+/// Run one Insights query over a time window and wait for its results
+#[tracing::instrument(
+    name = "query",
+    level = "debug",
+    skip_all,
+    fields(group = %regional_group.log_group, region = %regional_group.region.as_deref().unwrap_or("default"))
+)]
+async fn run_insights_query(
+    client: &Client,
+    regional_group: &RegionalLogGroup,
+    params: &SearchParams,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    stops: &Mutex<Vec<JoinHandle<()>>>,
+) -> Result<(Vec<LogEntry>, QueryStats)> {
+    let query = build_insights_query(params);
+    tracing::debug!(%start_time, %end_time, "starting query:\n{}", query);
+
+    let start_epoch = start_time.timestamp();
+    let end_epoch = end_time.timestamp();
+
+    // Start the query
+    let start_response = client
+        .start_query()
+        .log_group_name(&regional_group.log_group)
+        .start_time(start_epoch)
+        .end_time(end_epoch)
+        .query_string(&query)
+        .send()
+        .await
+        .map_err(|e| anyhow!("{}", aws_sdk_cloudwatchlogs::error::DisplayErrorContext(e)))?;
+
+    let query_id = start_response
+        .query_id
+        .ok_or_else(|| anyhow!("No query ID returned"))?;
+    let mut running = RunningQuery { client, query_id: query_id.clone(), stops, finished: false };
+
+    // Poll for results
+    let mut entries = Vec::new();
+    let mut stats = QueryStats::default();
+    let mut delays = poll_delays();
+
+    loop {
+        let response = client
+            .get_query_results()
+            .query_id(&query_id)
+            .send()
+            .await?;
+
+        let status = response
+            .status
+            .map(|s| s.as_str().to_string())
+            .unwrap_or_default();
+
+        match status.as_str() {
+            "Complete" => {
+                running.finished = true;
+                if let Some(statistics) = &response.statistics {
+                    stats = QueryStats::from_statistics(statistics);
+                }
+                if let Some(results) = response.results {
+                    for result in results {
+                        if let Some(mut entry) =
+                            parse_log_result(&result, &regional_group.log_group, end_time)
+                        {
+                            entry.region = regional_group.region.clone();
+                            entries.push(entry);
+                        }
+                    }
+                }
+                break;
+            }
+            "Failed" | "Cancelled" | "Timeout" => {
+                running.finished = true;
+                return Err(anyhow!("Query {}: {}", query_id, status));
+            }
+            _ => {
+                sleep(delays.next().unwrap_or(POLL_MAX_DELAY)).await;
+            }
+        }
+    }
+
+    tracing::info!(
+        group = %regional_group.log_group,
+        rows = entries.len(),
+        bytes_scanned = stats.bytes_scanned,
+        "query complete"
+    );
+    Ok((entries, stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn build_insights_query(patterns: &[String], exclude: &[String], limit: i32) -> String {
+        super::build_insights_query(&SearchParams::new(patterns.to_vec(), exclude.to_vec(), limit))
+    }
+
+    #[test]
+    fn test_query_without_patterns_has_no_filter() {
+        let query = build_insights_query(&[], &[], 100);
+        assert!(!query.contains("filter"));
+        assert_eq!(query, "fields @timestamp, @message, @logStream, @ptr\n| sort @timestamp desc\n| limit 100");
+
+        let query = build_insights_query(&strings(&[""]), &[], 100);
+        assert!(!query.contains("filter"));
+    }
+
+    #[test]
+    fn test_query_sort_order_follows_oldest() {
+        let newest = super::build_insights_query(&SearchParams::new(vec![], vec![], 10));
+        assert!(newest.contains("| sort @timestamp desc\n| limit 10"));
+
+        let oldest = super::build_insights_query(&SearchParams::new(vec![], vec![], 10).with_oldest(true));
+        assert!(oldest.contains("| sort @timestamp asc\n| limit 10"));
+    }
+
+    #[test]
+    fn test_keep_window_newest_and_oldest() {
+        use chrono::TimeZone;
+
+        let entries: Vec<LogEntry> = (0..5)
+            .map(|i| LogEntry {
+                timestamp: Utc.with_ymd_and_hms(2026, 1, 23, 5, 0, i).unwrap(),
+                message: format!("line {}", i),
+                log_group: "app/prod".to_string(),
+                log_stream: None,
+                region: None,
+                also_in: Vec::new(),
+                ptr: None,
+            })
+            .rev()
+            .collect();
+        let messages = |entries: &[LogEntry]| entries.iter().map(|e| e.message.clone()).collect::<Vec<_>>();
+
+        let mut newest = entries.clone();
+        assert!(keep_window(&mut newest, 2, false));
+        assert_eq!(messages(&newest), vec!["line 3", "line 4"]);
+
+        let mut oldest = entries.clone();
+        assert!(keep_window(&mut oldest, 2, true));
+        assert_eq!(messages(&oldest), vec!["line 0", "line 1"]);
+
+        let mut all = entries;
+        assert!(!keep_window(&mut all, 5, true));
+        assert_eq!(all.len(), 5);
+    }
+
+    #[test]
+    fn test_live_tail_filter_matches_like_insights() {
+        let params = SearchParams::new(strings(&["ERROR", r"user_id=\d+"]), strings(&["healthcheck"]), 100);
+        let filter = LiveTailFilter::new(&params);
+
+        assert!(filter.matches("ERROR user_id=42 failed"));
+        assert!(!filter.matches("ERROR user_id=abc failed"));
+        assert!(!filter.matches("error user_id=42 failed"));
+        assert!(!filter.matches("ERROR user_id=42 healthcheck"));
+
+        // Invalid regexes fall back to literal matching
+        let filter = LiveTailFilter::new(&SearchParams::new(strings(&["[wip"]), vec![], 100));
+        assert!(filter.matches("deploy [wip] done"));
+        assert!(LiveTailFilter::new(&SearchParams::default()).matches("anything"));
+    }
+
+    #[test]
+    fn test_query_stats_aggregate() {
+        use aws_sdk_cloudwatchlogs::types::QueryStatistics;
+
+        let gb = 1024.0 * 1024.0 * 1024.0;
+        let mut total = QueryStats::default();
+        for (bytes, matched, scanned) in [(3.0 * gb, 12.0, 4000.0), (gb, 3.0, 1000.0)] {
+            let statistics = QueryStatistics::builder()
+                .bytes_scanned(bytes)
+                .records_matched(matched)
+                .records_scanned(scanned)
+                .build();
+            total.add(&QueryStats::from_statistics(&statistics));
+        }
+
+        assert_eq!(total.records_matched, 15.0);
+        assert_eq!(total.records_scanned, 5000.0);
+        assert_eq!(total.bytes_scanned, 4.0 * gb);
+        assert!((total.estimated_cost_usd() - 0.02).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_verbose_emits_query_spans() {
+        use crate::cli::Cli;
+        use crate::diagnostics::{self, Captured};
+        use aws_sdk_cloudwatchlogs::config::{retry::RetryConfig, BehaviorVersion, Credentials, Region};
+        use clap::Parser;
+
+        // Nothing listens on port 1, so the query fails right away without touching AWS
+        let config = aws_sdk_cloudwatchlogs::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .endpoint_url("http://127.0.0.1:1")
+            .credentials_provider(Credentials::new("AKID", "secret", None, None, "test"))
+            .retry_config(RetryConfig::disabled())
+            .build();
+        let client = Client::from_conf(config);
+        let group = RegionalLogGroup::parse("us-east-1:app/prod");
+        let params = SearchParams::new(strings(&["ERROR"]), vec![], 10);
+        let end = Utc::now();
+
+        let run = |verbosity: u8| {
+            let captured = Captured::default();
+            let subscriber = diagnostics::subscriber(diagnostics::level(verbosity, false), captured.clone(), false);
+            let guard = tracing::subscriber::set_default(subscriber);
+            let stops = Mutex::new(Vec::new());
+            let client = client.clone();
+            let group = group.clone();
+            let params = params.clone();
+            async move {
+                let result = run_insights_query(&client, &group, &params, end - chrono::Duration::hours(1), end, &stops).await;
+                assert!(result.is_err());
+                drop(guard);
+                captured.text()
+            }
+        };
+
+        let verbose = Cli::try_parse_from(["log-hound", "--verbose", "--verbose", "groups"]).unwrap().verbose;
+        assert_eq!(verbose, 2);
+        let text = run(verbose).await;
+        assert!(text.contains("DEBUG query{group=app/prod region=us-east-1}: starting query:"), "{}", text);
+        assert!(text.contains("@message like /ERROR/"), "{}", text);
+        assert!(text.contains("DEBUG query{group=app/prod region=us-east-1}: close time.busy="), "{}", text);
+
+        // A single --verbose leaves query spans out
+        assert!(!run(1).await.contains("query{"));
+    }
+
+    #[test]
+    fn test_probe_stats_extrapolate_to_full_range() {
+        use chrono::TimeZone;
+
+        let end = Utc.with_ymd_and_hms(2026, 1, 31, 12, 0, 0).unwrap();
+        let full = TimeRange { start: end - chrono::Duration::days(7), end };
+        let probed = full.tail(chrono::Duration::minutes(PROBE_WINDOW_MINUTES));
+        assert_eq!(probed.start, end - chrono::Duration::minutes(5));
+
+        // 5 minutes scanning 10 MB is 2016 times that over a week
+        let mb = 1024.0 * 1024.0;
+        let probe = QueryStats { bytes_scanned: 10.0 * mb, records_matched: 2.0, records_scanned: 500.0 };
+        let estimate = probe.extrapolate(&probed, &full);
+        assert_eq!(estimate.bytes_scanned, 20_160.0 * mb);
+        assert_eq!(estimate.records_matched, 4032.0);
+        assert_eq!(estimate.records_scanned, 1_008_000.0);
+        assert!((estimate.estimated_cost_usd() - 20_160.0 / 1024.0 * 0.005).abs() < 1e-9);
+
+        // A range shorter than the probe window is probed whole, so nothing is scaled
+        let short = TimeRange { start: end - chrono::Duration::minutes(2), end };
+        assert_eq!(short.tail(chrono::Duration::minutes(PROBE_WINDOW_MINUTES)), short);
+        assert_eq!(probe.extrapolate(&short, &short), probe);
+
+        // An empty probe can't be scaled
+        let empty = TimeRange { start: end, end };
+        assert_eq!(probe.extrapolate(&empty, &full), probe);
+    }
+
+    type Page = std::future::Ready<Result<(Vec<LogEntry>, QueryStats)>>;
+
+    /// Answers like Insights: rows in [start, end] (whole seconds, inclusive), sorted from the kept end, at most `limit`
+    fn capped_pager(data: &[LogEntry], oldest: bool) -> impl FnMut(DateTime<Utc>, DateTime<Utc>, i32) -> Page + '_ {
+        move |start, end, limit| {
+            let end = end + chrono::Duration::milliseconds(999);
+            let mut page: Vec<LogEntry> = data.iter().filter(|e| e.timestamp >= start && e.timestamp <= end).cloned().collect();
+            if !oldest {
+                page.reverse();
+            }
+            page.truncate(limit as usize);
+            let stats = QueryStats { records_matched: page.len() as f64, ..QueryStats::default() };
+            std::future::ready(Ok((page, stats)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collect_windows_pages_through_capped_results() {
+        use chrono::TimeZone;
+
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let data: Vec<LogEntry> = (0..1000)
+            .map(|i| LogEntry {
+                timestamp: start + chrono::Duration::milliseconds(i * 100),
+                message: format!("line {}", i),
+                log_group: "app".to_string(),
+                log_stream: None,
+                region: None,
+                also_in: Vec::new(),
+                ptr: None,
+            })
+            .collect();
+        let end = start + chrono::Duration::seconds(100);
+
+        // Newest 250 with pages of 40 (the boundary second is queried twice, never duplicated)
+        let params = SearchParams::new(vec![], vec![], 250).with_split_windows(true);
+        let (entries, stats) = collect_windows(start, end, &params, 40, capped_pager(&data, false)).await.unwrap();
+        let expected: Vec<String> = (750..1000).rev().map(|i| format!("line {}", i)).collect();
+        assert_eq!(entries.iter().map(|e| e.message.clone()).collect::<Vec<_>>(), expected);
+        assert!(stats.records_matched >= 250.0);
+
+        // Oldest end, and the whole range when the limit is larger than the data
+        let params = SearchParams::new(vec![], vec![], 5000).with_split_windows(true).with_oldest(true);
+        let (entries, _) = collect_windows(start, end, &params, 300, capped_pager(&data, true)).await.unwrap();
+        assert_eq!(entries.len(), 1000);
+        assert_eq!(entries[0].message, "line 0");
+        assert_eq!(entries[999].message, "line 999");
+
+        // Without splitting only the first capped page comes back
+        let params = SearchParams::new(vec![], vec![], 250);
+        let (entries, _) = collect_windows(start, end, &params, 40, capped_pager(&data, false)).await.unwrap();
+        assert_eq!(entries.len(), 40);
+    }
+
+    #[test]
+    fn test_poll_delays_grow_to_cap() {
+        let delays: Vec<u64> = poll_delays().take(8).map(|d| d.as_millis() as u64).collect();
+        assert_eq!(delays, vec![50, 100, 200, 400, 800, 1000, 1000, 1000]);
+    }
+
+    #[test]
+    fn test_word_matching() {
+        let params = SearchParams::new(strings(&["id"]), strings(&["ping"]), 10).with_word(true);
+        let query = super::build_insights_query(&params);
+        assert!(query.contains(r"| filter @message like /\bid\b/ and @message not like /\bping\b/"));
+
+        let filter = LiveTailFilter::new(&params);
+        assert!(filter.matches("lookup id=5"));
+        assert!(!filter.matches("video width=640"));
+        assert!(filter.matches("id=5 pinged"));
+        assert!(!filter.matches("id=5 ping"));
+    }
+
+    #[test]
+    fn test_anchored_query() {
+        let params = SearchParams::new(strings(&["db"]), vec![], 10)
+            .with_starts_with(Some("ERROR".to_string()))
+            .with_ends_with(Some("done".to_string()));
+        let query = super::build_insights_query(&params);
+        assert!(query.contains("| filter @message like /db/ and @message like /(?i)^ERROR/ and @message like /(?i)done$/"));
+
+        let filter = LiveTailFilter::new(&params);
+        assert!(filter.matches("ERROR db migration done"));
+        assert!(filter.matches("error db migration Done"));
+        assert!(!filter.matches("INFO ERROR db migration done"));
+        assert!(!filter.matches("ERROR db migration done twice"));
+
+        // Taken literally: `/` can't end the Insights regex early and `.` is just a dot
+        let params = SearchParams::new(vec![], vec![], 10).with_starts_with(Some("GET /api/v1.2".to_string()));
+        let query = super::build_insights_query(&params);
+        assert!(query.contains(r"| filter @message like /(?i)^GET \/api\/v1\.2/"), "{}", query);
+        let filter = LiveTailFilter::new(&params);
+        assert!(filter.matches("get /api/v1.2/users 200"));
+        assert!(!filter.matches("GET /api/v1x2/users 200"));
+
+        // --word: the anchored text must end (or start) on a word boundary
+        let params = SearchParams::new(vec![], vec![], 10)
+            .with_starts_with(Some("ERR".to_string()))
+            .with_ends_with(Some("ok".to_string()))
+            .with_word(true);
+        let query = super::build_insights_query(&params);
+        assert!(query.contains(r"| filter @message like /(?i)^ERR\b/ and @message like /(?i)\bok$/"), "{}", query);
+        let filter = LiveTailFilter::new(&params);
+        assert!(filter.matches("ERR retry ok"));
+        assert!(!filter.matches("ERROR retry ok"));
+        assert!(!filter.matches("ERR retry took"));
+    }
+
+    #[test]
+    fn test_invert_match_query() {
+        let params = SearchParams::new(strings(&["INFO"]), strings(&["health"]), 10).with_invert_match(true);
+        let query = super::build_insights_query(&params);
+        assert!(query.contains("| filter @message not like /INFO/ and @message not like /health/"));
+
+        let params = SearchParams::new(strings(&["INFO", "request"]), vec![], 10).with_invert_match(true);
+        let query = super::build_insights_query(&params);
+        assert!(query.contains("| filter (@message not like /INFO/ or @message not like /request/)"));
+
+        let filter = LiveTailFilter::new(&params);
+        assert!(!filter.matches("INFO request completed"));
+        assert!(filter.matches("INFO cache warmed"));
+    }
+
+    #[test]
+    fn test_match_field_query() {
+        let query = |match_field| {
+            super::build_insights_query(
+                &SearchParams::new(strings(&["web"]), strings(&["health"]), 10).with_match_field(match_field),
+            )
+        };
+        assert!(query(MatchField::Message).contains("| filter @message like /web/ and @message not like /health/"));
+        assert!(query(MatchField::Stream).contains("| filter @logStream like /web/ and @message not like /health/"));
+        assert!(query(MatchField::Any)
+            .contains("| filter (@message like /web/ or @logStream like /web/) and @message not like /health/"));
+
+        // Inverted, a pattern counts as missing only when it's in neither field
+        let params = SearchParams::new(strings(&["web"]), vec![], 10)
+            .with_match_field(MatchField::Any)
+            .with_invert_match(true);
+        assert!(super::build_insights_query(&params)
+            .contains("| filter (@message not like /web/ and @logStream not like /web/)"));
+        let params = params.with_match_field(MatchField::Stream);
+        assert!(super::build_insights_query(&params).contains("| filter @logStream not like /web/"));
+    }
+
+    #[test]
+    fn test_parse_live_tail_event_maps_arn_to_name() {
+        let arn = "arn:aws:logs:ap-east-2:123456789012:log-group:app/prod";
+        let names = HashMap::from([(arn.to_string(), "app/prod".to_string())]);
+        let event = LiveTailSessionLogEvent::builder()
+            .log_group_identifier(format!("{}:*", arn))
+            .log_stream_name("web-1")
+            .message("ERROR boom")
+            .timestamp(1_769_146_565_200)
+            .build();
+
+        let entry = parse_live_tail_event(&event, &names, Some("ap-east-2")).unwrap();
+        assert_eq!(entry.log_group, "app/prod");
+        assert_eq!(entry.message, "ERROR boom");
+        assert_eq!(entry.region.as_deref(), Some("ap-east-2"));
+        assert_eq!(entry.timestamp.timestamp_millis(), 1_769_146_565_200);
+    }
+
+    #[test]
+    fn test_parse_log_result_without_timestamp() {
+        use aws_sdk_cloudwatchlogs::types::ResultField;
+        use chrono::TimeZone;
+
+        let field = |name: &str, value: &str| ResultField::builder().field(name).value(value).build();
+        let end = Utc.with_ymd_and_hms(2026, 1, 23, 6, 0, 0).unwrap();
+
+        let row = [field("@timestamp", "2026-01-23 05:36:05.200"), field("@message", "ERROR boom"), field("@ptr", "x")];
+        let entry = parse_log_result(&row, "app/prod", end).unwrap();
+        assert_eq!(entry.message, "ERROR boom");
+        assert_eq!(entry.ptr.as_deref(), Some("x"));
+        assert_eq!(entry.timestamp, Utc.with_ymd_and_hms(2026, 1, 23, 5, 36, 5).unwrap() + chrono::Duration::milliseconds(200));
+
+        // A `stats` row has neither @timestamp nor @message: kept at the end of the window
+        let row = [field("status", "500"), field("count(*)", "42")];
+        let entry = parse_log_result(&row, "app/prod", end).unwrap();
+        assert_eq!(entry.message, "status=500 count(*)=42");
+        assert_eq!(entry.timestamp, end);
+
+        let row = [field("@message", "ERROR no time"), field("@logStream", "web-1")];
+        let entry = parse_log_result(&row, "app/prod", end).unwrap();
+        assert_eq!((entry.message.as_str(), entry.timestamp), ("ERROR no time", end));
+        assert_eq!(entry.log_stream.as_deref(), Some("web-1"));
+        assert_eq!(entry.ptr, None);
+
+        // Nothing to show
+        assert!(parse_log_result(&[field("@ptr", "x")], "app/prod", end).is_none());
+    }
+
+    #[test]
+    fn test_query_with_stream_filter() {
+        let params = SearchParams::new(strings(&["ERROR"]), vec![], 10)
+            .with_stream(Some(StreamFilter::Name("ecs/web/abc\"1".to_string())));
+        let query = super::build_insights_query(&params);
+        assert!(query.contains("| filter @logStream = \"ecs/web/abc\\\"1\" and @message like /ERROR/\n"));
+
+        let params = SearchParams::new(vec![], vec![], 10).with_stream(Some(StreamFilter::Prefix("ecs/web.1".to_string())));
+        let query = super::build_insights_query(&params);
+        assert!(query.contains("| filter @logStream like /^ecs\\/web\\.1/\n"));
+    }
+
+    #[test]
+    fn test_predates_retention() {
+        use chrono::TimeZone;
+
+        let now = Utc.with_ymd_and_hms(2026, 3, 31, 12, 0, 0).unwrap();
+        let days_ago = |days| now - chrono::Duration::days(days);
+
+        assert!(predates_retention(days_ago(90), now, Some(30)));
+        assert!(!predates_retention(days_ago(29), now, Some(30)));
+        assert!(!predates_retention(days_ago(30), now, Some(30)));
+        assert!(!predates_retention(days_ago(3650), now, None));
+    }
+
+    #[test]
+    fn test_custom_query_template() {
+        let params = SearchParams::new(strings(&["ERROR"]), strings(&["health"]), 25)
+            .with_query(Some("fields @timestamp, @message | filter {patterns} | limit {limit}".to_string()));
+        assert_eq!(
+            super::build_insights_query(&params),
+            "fields @timestamp, @message | filter @message like /ERROR/ and @message not like /health/ | limit 25"
+        );
+
+        let params = SearchParams::new(vec![], vec![], 5).with_query(Some("fields @timestamp | filter {patterns}".to_string()));
+        assert_eq!(super::build_insights_query(&params), "fields @timestamp | filter ispresent(@message)");
+    }
+
+    #[test]
+    fn test_query_with_only_excludes() {
+        let query = build_insights_query(&[], &strings(&["health"]), 50);
+        assert!(query.contains("| filter @message not like /health/\n"));
+        assert!(!query.contains(" like /health/ and"));
+    }
+
+    #[test]
+    fn test_query_with_patterns_and_excludes() {
+        let query = build_insights_query(&strings(&["ERROR", "timeout"]), &strings(&["health"]), 10);
+        assert!(query.contains("| filter @message like /ERROR/ and @message like /timeout/ and @message not like /health/\n"));
+    }
+}
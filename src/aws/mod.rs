@@ -1,7 +1,13 @@
 mod client;
+mod console;
+mod export;
 mod logs;
 mod multi_region;
+mod profiles;
 
-pub use client::create_client;
-pub use logs::{LogEntry, LogSearcher, MultiRegionSearcher, SearchParams};
+pub use client::{check_credentials, create_client, error_kind, load_sdk_config, AwsErrorKind};
+pub use console::ConsoleQuery;
+pub use export::{start_export, wait_for_export, S3Destination};
+pub use logs::{keep_window, predates_retention, LogEntry, PROBE_WINDOW_MINUTES, LogSearcher, MultiRegionSearcher, QueryStats, SearchParams, SearchResults, StreamFilter};
 pub use multi_region::RegionalLogGroup;
+pub use profiles::{load_profiles, AwsProfile};
@@ -1,29 +1,35 @@
 use anyhow::Result;
-use aws_config::BehaviorVersion;
+use crate::aws::client::{check_credentials, load_sdk_config};
 use aws_sdk_cloudwatchlogs::Client;
 use std::collections::HashMap;
 use tokio::sync::RwLock;
 
-/// Parsed log group with optional region override
+/// Parsed log group with optional profile and region overrides
 #[derive(Debug, Clone)]
 pub struct RegionalLogGroup {
+    pub profile: Option<String>,
     pub region: Option<String>,
     pub log_group: String,
 }
 
 impl RegionalLogGroup {
-    /// Parse a log group string, optionally prefixed with region
-    /// Format: "region:log-group" or just "log-group"
+    /// Parse a log group string, optionally prefixed with region (and profile)
+    /// Format: "profile@region:log-group", "region:log-group" or just "log-group"
     pub fn parse(input: &str) -> Self {
         let input = input.trim();
 
-        // Check for region:log-group format
+        // Check for [profile@]region:log-group format
         if let Some(colon_pos) = input.find(':') {
-            let potential_region = &input[..colon_pos];
+            let prefix = &input[..colon_pos];
+            let (profile, potential_region) = match prefix.rsplit_once('@') {
+                Some((profile, region)) if !profile.is_empty() => (Some(profile), region),
+                _ => (None, prefix),
+            };
 
             // Validate it looks like a region (e.g., ap-east-2, us-west-1)
             if is_valid_region_format(potential_region) {
                 return Self {
+                    profile: profile.map(|p| p.to_string()),
                     region: Some(potential_region.to_string()),
                     log_group: input[colon_pos + 1..].to_string(),
                 };
@@ -32,6 +38,7 @@ impl RegionalLogGroup {
 
         // No region prefix
         Self {
+            profile: None,
             region: None,
             log_group: input.to_string(),
         }
@@ -64,11 +71,22 @@ fn is_valid_region_format(s: &str) -> bool {
     true
 }
 
-/// Manages CloudWatch clients for multiple regions
+/// Cache key for pooled clients: the same region under different profiles needs separate clients
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ClientKey {
+    profile: Option<String>,
+    region: Option<String>,
+}
+
+/// Manages CloudWatch clients for multiple regions and profiles
 pub struct MultiRegionClientPool {
     profile: Option<String>,
     default_region: Option<String>,
-    clients: RwLock<HashMap<String, Client>>,
+    /// Per-region profile overrides (for log groups in other accounts)
+    region_profiles: HashMap<String, String>,
+    /// Endpoint every client talks to instead of AWS's (--endpoint-url)
+    endpoint_url: Option<String>,
+    clients: RwLock<HashMap<ClientKey, Client>>,
 }
 
 impl MultiRegionClientPool {
@@ -76,44 +94,67 @@ impl MultiRegionClientPool {
         Self {
             profile,
             default_region,
+            region_profiles: HashMap::new(),
+            endpoint_url: None,
             clients: RwLock::new(HashMap::new()),
         }
     }
 
-    /// Get or create a client for the specified region
-    pub async fn get_client(&self, region: Option<&str>) -> Result<Client> {
-        let region_key = region
-            .or(self.default_region.as_deref())
-            .unwrap_or("default")
-            .to_string();
+    pub fn with_region_profiles(mut self, region_profiles: HashMap<String, String>) -> Self {
+        self.region_profiles = region_profiles;
+        self
+    }
+
+    pub fn with_endpoint_url(mut self, endpoint_url: Option<String>) -> Self {
+        self.endpoint_url = endpoint_url;
+        self
+    }
+
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Switch the default profile and drop cached clients so new requests use it
+    pub fn set_profile(&mut self, profile: Option<String>) {
+        self.profile = profile;
+        self.clients.get_mut().clear();
+    }
+
+    /// Resolve the profile and region a client is created with
+    /// Profile precedence: explicit (profile@region:group) > region_profiles > pool profile
+    fn client_key(&self, profile: Option<&str>, region: Option<&str>) -> ClientKey {
+        let region = region.or(self.default_region.as_deref());
+        let profile = profile
+            .or_else(|| region.and_then(|r| self.region_profiles.get(r)).map(|p| p.as_str()))
+            .or(self.profile.as_deref());
+
+        ClientKey {
+            profile: profile.map(|p| p.to_string()),
+            region: region.map(|r| r.to_string()),
+        }
+    }
+
+    /// Get or create a client for the specified profile and region
+    pub async fn get_client(&self, profile: Option<&str>, region: Option<&str>) -> Result<Client> {
+        let key = self.client_key(profile, region);
 
         // Check if we already have this client
         {
             let clients = self.clients.read().await;
-            if let Some(client) = clients.get(&region_key) {
+            if let Some(client) = clients.get(&key) {
                 return Ok(client.clone());
             }
         }
 
         // Create new client
-        let mut config_loader = aws_config::defaults(BehaviorVersion::latest());
-
-        if let Some(profile_name) = &self.profile {
-            config_loader = config_loader.profile_name(profile_name);
-        }
-
-        let effective_region = region.or(self.default_region.as_deref());
-        if let Some(region_name) = effective_region {
-            config_loader = config_loader.region(aws_config::Region::new(region_name.to_string()));
-        }
-
-        let config = config_loader.load().await;
+        let config = load_sdk_config(key.profile.as_deref(), key.region.as_deref(), self.endpoint_url.as_deref()).await;
+        check_credentials(&config, key.profile.as_deref()).await?;
         let client = Client::new(&config);
 
         // Store for reuse
         {
             let mut clients = self.clients.write().await;
-            clients.insert(region_key, client.clone());
+            clients.insert(key, client.clone());
         }
 
         Ok(client)
@@ -146,6 +187,39 @@ mod tests {
         assert_eq!(parsed.log_group, "my-app:production");
     }
 
+    #[test]
+    fn test_parse_with_profile_and_region() {
+        let parsed = RegionalLogGroup::parse("billing-prod@us-east-1:/aws/app/rails");
+        assert_eq!(parsed.profile, Some("billing-prod".to_string()));
+        assert_eq!(parsed.region, Some("us-east-1".to_string()));
+        assert_eq!(parsed.log_group, "/aws/app/rails");
+
+        // A profile needs a region; otherwise the whole string is the log group
+        let parsed = RegionalLogGroup::parse("billing@app:production");
+        assert_eq!(parsed.profile, None);
+        assert_eq!(parsed.region, None);
+        assert_eq!(parsed.log_group, "billing@app:production");
+    }
+
+    #[test]
+    fn test_client_key_profile_precedence() {
+        let pool = MultiRegionClientPool::new(Some("default".to_string()), Some("ap-northeast-1".to_string()))
+            .with_region_profiles(HashMap::from([("us-east-1".to_string(), "us-account".to_string())]));
+
+        let key = |profile, region| {
+            let k = pool.client_key(profile, region);
+            (k.profile, k.region)
+        };
+        let owned = |p: &str, r: &str| (Some(p.to_string()), Some(r.to_string()));
+
+        assert_eq!(key(None, None), owned("default", "ap-northeast-1"));
+        assert_eq!(key(None, Some("us-east-1")), owned("us-account", "us-east-1"));
+        assert_eq!(key(Some("explicit"), Some("us-east-1")), owned("explicit", "us-east-1"));
+
+        // Same region under different profiles must not share a cached client
+        assert_ne!(pool.client_key(None, Some("us-east-1")), pool.client_key(Some("explicit"), Some("us-east-1")));
+    }
+
     #[test]
     fn test_valid_region_format() {
         assert!(is_valid_region_format("us-east-1"));
@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// A profile found in the shared AWS config or credentials file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AwsProfile {
+    pub name: String,
+    /// `region` set for the profile; the config file wins over the credentials file
+    pub region: Option<String>,
+    /// Has a section in ~/.aws/config
+    pub in_config: bool,
+    /// Has a section in ~/.aws/credentials
+    pub in_credentials: bool,
+}
+
+/// Sections of an INI file with their `key = value` pairs, in file order
+/// Indented lines under a key with no value (e.g. `s3 =` settings) are nested properties and skipped
+fn parse_ini(text: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut sections: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    let mut nested = false;
+
+    for line in text.lines() {
+        let indented = line.starts_with([' ', '\t']);
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((name.trim().to_string(), Vec::new()));
+            nested = false;
+            continue;
+        }
+        if nested && indented {
+            continue;
+        }
+        let (Some((_, pairs)), Some((key, value))) = (sections.last_mut(), line.split_once('=')) else {
+            continue;
+        };
+        let value = value.trim();
+        nested = value.is_empty();
+        pairs.push((key.trim().to_string(), value.to_string()));
+    }
+    sections
+}
+
+/// Profiles defined by the text of an AWS config file and credentials file, sorted by name
+/// Config sections are `[default]` or `[profile <name>]`; `[sso-session ...]` and the like aren't
+/// profiles. Credentials sections are the bare profile name.
+pub fn parse_profiles(config: Option<&str>, credentials: Option<&str>) -> Vec<AwsProfile> {
+    let mut profiles: BTreeMap<String, AwsProfile> = BTreeMap::new();
+    let region = |pairs: &[(String, String)]| pairs.iter().find(|(key, _)| key == "region").map(|(_, value)| value.clone());
+
+    for (section, pairs) in parse_ini(credentials.unwrap_or_default()) {
+        let profile = profiles.entry(section.clone()).or_insert_with(|| AwsProfile { name: section, ..AwsProfile::default() });
+        profile.in_credentials = true;
+        profile.region = region(&pairs).or(profile.region.take());
+    }
+    for (section, pairs) in parse_ini(config.unwrap_or_default()) {
+        let name = match section.strip_prefix("profile ") {
+            Some(name) => name.trim().to_string(),
+            None if section == "default" => section,
+            None => continue,
+        };
+        let profile = profiles.entry(name.clone()).or_insert_with(|| AwsProfile { name, ..AwsProfile::default() });
+        profile.in_config = true;
+        profile.region = region(&pairs).or(profile.region.take());
+    }
+    profiles.into_values().collect()
+}
+
+/// The shared config file: AWS_CONFIG_FILE, or ~/.aws/config
+pub fn config_file_path() -> Option<PathBuf> {
+    std::env::var_os("AWS_CONFIG_FILE")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".aws").join("config")))
+}
+
+/// The shared credentials file: AWS_SHARED_CREDENTIALS_FILE, or ~/.aws/credentials
+pub fn credentials_file_path() -> Option<PathBuf> {
+    std::env::var_os("AWS_SHARED_CREDENTIALS_FILE")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".aws").join("credentials")))
+}
+
+/// Read a file that may not exist
+fn read_optional(path: Option<PathBuf>) -> Result<Option<String>> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(text) => Ok(Some(text)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+/// Profiles configured in the shared AWS config and credentials files
+pub fn load_profiles() -> Result<Vec<AwsProfile>> {
+    let config = read_optional(config_file_path())?;
+    let credentials = read_optional(credentials_file_path())?;
+    Ok(parse_profiles(config.as_deref(), credentials.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_from_config_and_credentials() {
+        let credentials = "\
+[default]
+aws_access_key_id = AKIADEFAULT
+aws_secret_access_key = secret
+
+# legacy keys
+[staging]
+aws_access_key_id=AKIASTAGING
+aws_secret_access_key=secret
+region=eu-central-1
+
+[ci]
+aws_access_key_id = AKIACI
+";
+        let config = "\
+[default]
+region = us-east-1
+
+[profile staging]
+region = eu-west-1
+s3 =
+  region = ignored
+
+[profile prod-sso]
+sso_session = corp
+sso_account_id = 123456789012
+; no region: the SDK falls back to AWS_REGION
+
+[sso-session corp]
+sso_region = us-east-1
+";
+        let profiles = parse_profiles(Some(config), Some(credentials));
+        let summary: Vec<(&str, Option<&str>, bool, bool)> = profiles
+            .iter()
+            .map(|p| (p.name.as_str(), p.region.as_deref(), p.in_config, p.in_credentials))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("ci", None, false, true),
+                ("default", Some("us-east-1"), true, true),
+                ("prod-sso", None, true, false),
+                ("staging", Some("eu-west-1"), true, true),
+            ]
+        );
+
+        // A credentials file alone still lists its profiles
+        let names: Vec<String> = parse_profiles(None, Some(credentials)).into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["ci", "default", "staging"]);
+        assert!(parse_profiles(None, None).is_empty());
+    }
+}
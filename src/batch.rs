@@ -0,0 +1,275 @@
+use crate::cli::{resolve_search, Cli, Commands, OutputMode, PagerMode, ResolvedSearch};
+use crate::config::Config;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::Parser;
+use futures::stream::{self, StreamExt};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+/// One search of a batch file
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchSearch {
+    /// Names its section of the combined output, or its file in --output-dir
+    pub name: String,
+    /// Arguments as given to `log-hound search`
+    pub args: Vec<String>,
+    /// 1-based line in the batch file, for errors
+    pub line: usize,
+}
+
+/// A resolved batch search and the file its results are written to
+#[derive(Debug, Clone)]
+pub struct BatchJob {
+    pub name: String,
+    pub search: ResolvedSearch,
+    pub path: PathBuf,
+}
+
+/// Parse a batch file: one search per line, with blank lines and `#` comments skipped
+/// `name: ERROR -g app/prod --last 1h` runs those search arguments under that name; a line with
+/// only a name runs the preset of that name (`production` is `production: -p production`)
+pub fn parse_batch(text: &str) -> Result<Vec<BatchSearch>> {
+    let mut searches = Vec::new();
+    let mut names = HashSet::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (name, args) = match line.split_once(':') {
+            Some((name, spec)) => {
+                let args = split_words(spec).with_context(|| format!("Line {}", number))?;
+                (name.trim(), args)
+            }
+            None => (line, vec!["-p".to_string(), line.to_string()]),
+        };
+        // Names become file names in --output-dir
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) {
+            bail!("Line {}: expected `name: <search arguments>` or a preset name, with names made of letters, digits, - _ .", number);
+        }
+        if !names.insert(name.to_string()) {
+            bail!("Line {}: a search named '{}' is already listed", number, name);
+        }
+        searches.push(BatchSearch { name: name.to_string(), args, line: number });
+    }
+
+    if searches.is_empty() {
+        bail!("The batch file lists no searches");
+    }
+    Ok(searches)
+}
+
+/// Split search arguments like a shell: on whitespace, except inside '...' or "...", with `\`
+/// escaping the next character outside single quotes
+fn split_words(input: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => word.get_or_insert_with(String::new).push(c),
+            (_, '\\') => {
+                let escaped = chars.next().ok_or_else(|| anyhow!("Trailing backslash"))?;
+                word.get_or_insert_with(String::new).push(escaped);
+            }
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if let Some(q) = quote {
+        bail!("Unterminated {} quote", q);
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Resolve each search as `log-hound search` would, with presets and config defaults applied
+/// Global flags on a line (--profile, --region, --source) win over the ones `batch` was run with
+pub fn resolve_batch(cli: &Cli, config: &Config, searches: &[BatchSearch]) -> Result<Vec<(String, ResolvedSearch)>> {
+    searches
+        .iter()
+        .map(|batch| {
+            let argv = ["log-hound", "search"].into_iter().map(str::to_string).chain(batch.args.iter().cloned());
+            let parsed = Cli::try_parse_from(argv).with_context(|| format!("Line {} ({})", batch.line, batch.name))?;
+            let line_cli = Cli {
+                profile: parsed.profile.clone().or_else(|| cli.profile.clone()),
+                region: parsed.region.clone().or_else(|| cli.region.clone()),
+                source: parsed.source.clone().or_else(|| cli.source.clone()),
+                ..parsed
+            };
+            let Commands::Search(args) = &line_cli.command else {
+                unreachable!("batch lines are parsed as searches");
+            };
+
+            let search = resolve_search(&line_cli, args, config)
+                .with_context(|| format!("Line {} ({})", batch.line, batch.name))?;
+            if search.follow {
+                bail!("Line {} ({}): --follow never finishes, so it can't be part of a batch", batch.line, batch.name);
+            }
+            Ok((batch.name.clone(), search))
+        })
+        .collect()
+}
+
+/// Point a search's results at `<dir>/<name>.<ext>`, with nothing else on stdout
+/// `combined` searches are written as JSON so `combine` can gather them into one document
+pub fn prepare(name: String, mut search: ResolvedSearch, dir: &Path, combined: bool) -> BatchJob {
+    if combined && !search.output.is_json() {
+        search.output = OutputMode::JsonCompact;
+    }
+    let extension = match search.output {
+        _ if search.output.is_json() => "json",
+        OutputMode::Csv => "csv",
+        _ => "txt",
+    };
+    let path = dir.join(format!("{}.{}", name, extension));
+
+    search.output_file = Some(path.display().to_string());
+    search.gzip = false;
+    search.copy = false;
+    search.pager = PagerMode::Never;
+    search.quiet = true;
+    BatchJob { name, search, path }
+}
+
+/// Run the jobs, at most `concurrency` at a time, returning their outcomes in file order
+pub async fn run_all<F, Fut>(jobs: Vec<BatchJob>, concurrency: usize, run: F) -> Vec<(BatchJob, Result<()>)>
+where
+    F: Fn(ResolvedSearch) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    stream::iter(jobs)
+        .map(|job| {
+            let outcome = run(job.search.clone());
+            async move { (job, outcome.await) }
+        })
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+/// One JSON array holding each search's results document under its name, or its error
+pub fn combine(outcomes: &[(BatchJob, Result<()>)]) -> Value {
+    let sections = outcomes.iter().map(|(job, outcome)| {
+        let mut section = Map::new();
+        section.insert("name".to_string(), Value::String(job.name.clone()));
+
+        let document = outcome.as_ref().map_err(|e| format!("{:#}", e)).and_then(|()| {
+            let text = std::fs::read_to_string(&job.path).map_err(|_| "the search wrote no results".to_string())?;
+            serde_json::from_str::<Value>(&text).map_err(|e| format!("unreadable results: {}", e))
+        });
+        match document {
+            Ok(Value::Object(fields)) => section.extend(fields),
+            Ok(other) => {
+                section.insert("results".to_string(), other);
+            }
+            Err(error) => {
+                section.insert("error".to_string(), Value::String(error));
+            }
+        }
+        Value::Object(section)
+    });
+    Value::Array(sections.collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Preset;
+
+    fn cli() -> Cli {
+        Cli::try_parse_from(["log-hound", "--region", "us-east-1", "doctor"]).unwrap()
+    }
+
+    #[test]
+    fn test_parse_batch_file() {
+        let text = "\
+# nightly report
+errors: ERROR -g app/prod --last 1h
+
+slow: 'took [0-9]{4}ms' -g \"api/prod\" --oldest
+production
+";
+        let searches = parse_batch(text).unwrap();
+        let summary: Vec<(&str, Vec<&str>, usize)> = searches
+            .iter()
+            .map(|s| (s.name.as_str(), s.args.iter().map(String::as_str).collect(), s.line))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("errors", vec!["ERROR", "-g", "app/prod", "--last", "1h"], 2),
+                ("slow", vec!["took [0-9]{4}ms", "-g", "api/prod", "--oldest"], 4),
+                ("production", vec!["-p", "production"], 5),
+            ]
+        );
+
+        assert!(parse_batch("# nothing\n\n").is_err());
+        assert!(parse_batch("a: x\na: y").unwrap_err().to_string().contains("already listed"));
+        assert!(parse_batch("-g us-east-1:app").is_err());
+        assert!(parse_batch("a: 'open").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_batch_runs_file_backed_searches_into_one_document() {
+        let mut config = Config::default();
+        config.presets.insert(
+            "production".to_string(),
+            Preset { groups: vec!["app/prod".to_string()], ..Preset::default() },
+        );
+        let searches = parse_batch("errors: ERROR -g api/prod --last 1h\nproduction\nbroken: -g api/prod").unwrap();
+        let resolved = resolve_batch(&cli(), &config, &searches).unwrap();
+        assert_eq!(resolved[0].1.groups, vec!["api/prod"]);
+        assert_eq!(resolved[0].1.region.as_deref(), Some("us-east-1"));
+        assert_eq!(resolved[1].1.groups, vec!["app/prod"]);
+
+        let dir = std::env::temp_dir().join(format!("log-hound-batch-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let jobs: Vec<BatchJob> = resolved.into_iter().map(|(name, search)| prepare(name, search, &dir, true)).collect();
+        assert_eq!(jobs[0].path, dir.join("errors.json"));
+        assert_eq!(jobs[0].search.output, OutputMode::JsonCompact);
+
+        // Stand-in for the real search: write a results document where the search was pointed
+        let outcomes = run_all(jobs, 2, |search| async move {
+            if search.patterns.is_empty() && search.preset.is_none() {
+                bail!("no patterns");
+            }
+            let document = serde_json::json!({ "total": 1, "results": [{ "message": search.groups.join(",") }] });
+            std::fs::write(search.output_file.unwrap(), document.to_string())?;
+            Ok(())
+        })
+        .await;
+        let combined = combine(&outcomes);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            combined,
+            serde_json::json!([
+                { "name": "errors", "total": 1, "results": [{ "message": "api/prod" }] },
+                { "name": "production", "total": 1, "results": [{ "message": "app/prod" }] },
+                { "name": "broken", "error": "no patterns" },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_follow_is_rejected() {
+        let searches = parse_batch("tail: -g app/prod -f").unwrap();
+        let error = resolve_batch(&cli(), &Config::default(), &searches).unwrap_err();
+        assert!(error.to_string().contains("--follow"), "{}", error);
+    }
+}
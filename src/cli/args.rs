@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser, Debug)]
 #[command(name = "log-hound")]
@@ -16,9 +16,17 @@ pub struct Cli {
     #[arg(long, global = true, env = "AWS_REGION")]
     pub region: Option<String>,
 
-    /// Log source to use
-    #[arg(long, global = true, default_value = "cloudwatch")]
-    pub source: LogSource,
+    /// Send CloudWatch requests to this endpoint instead of AWS's (LocalStack, VPC endpoints)
+    #[arg(long, global = true, env = "AWS_ENDPOINT_URL", value_name = "URL")]
+    pub endpoint_url: Option<String>,
+
+    /// Log source to use [default: cloudwatch]
+    #[arg(long, global = true)]
+    pub source: Option<LogSource>,
+
+    /// Diagnostics on stderr: once for info, twice for query/SSH timings (debug), three times for trace
+    #[arg(long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
 }
 
 #[derive(ValueEnum, Clone, Debug, Default, PartialEq)]
@@ -30,6 +38,8 @@ pub enum LogSource {
     Kamal,
 }
 
+// Parsed once at startup, so the size of SearchArgs doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Search logs with a filter string
@@ -44,50 +54,36 @@ pub enum Commands {
   log-hound search \"timeout\" --source kamal -d config/deploy.saiens.yml --last 30m
   log-hound search -p saiens \"ERROR\"  # Preset with kamal source
   log-hound search --source kamal -d config/deploy.yml -f  # Follow/tail logs live")]
-    Search {
-        /// Search patterns to match in @message (multiple = AND condition)
-        #[arg(required_unless_present = "preset")]
-        patterns: Vec<String>,
-
-        /// Log groups to search (CloudWatch) - comma-separated for multiple
-        #[arg(short, long, value_delimiter = ',')]
-        groups: Vec<String>,
-
-        /// Kamal deploy.yml file path (for --source kamal)
-        #[arg(short = 'd', long = "deploy")]
-        deploy_file: Option<String>,
+    Search(SearchArgs),
 
-        /// Use a saved preset from config
-        #[arg(short, long)]
-        preset: Option<String>,
+    /// Export a log group to S3 with a CloudWatch export task, for windows too large to search
+    #[command(after_help = "Examples:
+  log-hound export -g app/production --export-s3 log-archive/exports --last 7d
+  log-hound export -g us-east-1:api/logs --export-s3 s3://log-archive --start 2026-01-01 --end 2026-01-15")]
+    Export(ExportArgs),
 
-        /// Exclude patterns (NOT condition, comma-separated)
-        #[arg(short = 'x', long, value_delimiter = ',')]
-        exclude: Vec<String>,
+    /// Run the searches listed in a file, printing them as one JSON array or writing a file each
+    #[command(after_help = "Examples:
+  log-hound batch nightly.txt > report.json
+  log-hound batch nightly.txt --output-dir reports/ --concurrency 4
 
-        /// Time range: e.g., "1h", "30m", "2d"
-        #[arg(short, long, default_value = "1h")]
-        last: String,
+Batch file, one search per line (`#` starts a comment):
+  errors: ERROR -g app/prod --last 24h
+  slow-api: \"took [0-9]{4}ms\" -g api/prod -o csv
+  production                 # a preset name alone runs that preset")]
+    Batch(BatchArgs),
 
-        /// Start time (alternative to --last)
-        #[arg(long)]
-        start: Option<String>,
+    /// Fetch the full original event behind a CloudWatch search result, by its `ptr` (from -o json)
+    #[command(after_help = "Examples:
+  log-hound search \"ERROR\" -g app/prod -o json | jq -r '.results[0].ptr' | xargs log-hound fetch-context
+  log-hound fetch-context CmAKJgoi... --region us-east-1 --json")]
+    FetchContext {
+        /// Record pointer: the `ptr` of a JSON search result (use the --region it was searched in)
+        ptr: String,
 
-        /// End time (used with --start)
+        /// Print the record as a JSON object
         #[arg(long)]
-        end: Option<String>,
-
-        /// Output mode for results
-        #[arg(short, long, default_value = "interleaved")]
-        output: OutputMode,
-
-        /// Maximum number of results per log group
-        #[arg(long, default_value = "100")]
-        limit: i32,
-
-        /// Follow/tail logs in real-time (Kamal source only)
-        #[arg(short = 'f', long)]
-        follow: bool,
+        json: bool,
     },
 
     /// List available log groups
@@ -97,9 +93,19 @@ pub enum Commands {
         prefix: Option<String>,
     },
 
+    /// List the AWS profiles in ~/.aws/config and ~/.aws/credentials, with their regions
+    Profiles,
+
     /// Launch interactive TUI mode
     #[command(alias = "ui")]
-    Tui,
+    Tui {
+        /// History to load before streaming when following starts, e.g. 15m (default 5m)
+        #[arg(long, value_name = "DURATION")]
+        follow_backfill: Option<String>,
+    },
+
+    /// Check the environment: config file, AWS credentials and connectivity, ssh/docker
+    Doctor,
 
     /// Manage configuration
     Config {
@@ -108,6 +114,338 @@ pub enum Commands {
     },
 }
 
+#[derive(Args, Debug, Clone)]
+pub struct SearchArgs {
+    /// Search patterns to match in @message (multiple = AND condition, none = match all)
+    pub patterns: Vec<String>,
+
+    /// Add a search pattern taken verbatim: no comma splitting or trimming (repeatable)
+    #[arg(long = "pattern", value_name = "PATTERN")]
+    pub verbatim_patterns: Vec<String>,
+
+    /// Log groups to search (CloudWatch) - comma-separated for multiple
+    #[arg(short, long, value_delimiter = ',')]
+    pub groups: Vec<String>,
+
+    /// Kamal deploy.yml file path (for --source kamal)
+    #[arg(short = 'd', long = "deploy")]
+    pub deploy_file: Option<String>,
+
+    /// Kamal destination: reads deploy.<ENV>.yml merged over deploy.yml, like `kamal -d <ENV>`
+    #[arg(long, value_name = "ENV", conflicts_with = "deploy_file")]
+    pub env: Option<String>,
+
+    /// Use a saved preset from config
+    #[arg(short, long)]
+    pub preset: Option<String>,
+
+    /// Search the log groups of an account from config, with its profile and regions
+    #[arg(long)]
+    pub account: Option<String>,
+
+    /// Exclude patterns (NOT condition, comma-separated; write a literal comma as \,)
+    #[arg(short = 'x', long)]
+    pub exclude: Vec<String>,
+
+    /// Don't apply the config's global_exclude to this search
+    #[arg(long)]
+    pub no_global_exclude: bool,
+
+    /// Time range: e.g., "1h", "30m", "2d" [default: 1h]
+    #[arg(short, long)]
+    pub last: Option<String>,
+
+    /// Start time (alternative to --last)
+    #[arg(long)]
+    pub start: Option<String>,
+
+    /// End time: with --start, or alone to end the --last window there instead of now
+    #[arg(long)]
+    pub end: Option<String>,
+
+    /// Only search this log stream within the log groups (CloudWatch only)
+    #[arg(long, value_name = "NAME", conflicts_with = "stream_prefix")]
+    pub stream: Option<String>,
+
+    /// Only search log streams starting with this prefix (CloudWatch only)
+    #[arg(long, value_name = "PREFIX")]
+    pub stream_prefix: Option<String>,
+
+    /// Drop results whose log stream doesn't contain this substring (applied after fetching)
+    #[arg(long, value_name = "SUBSTR")]
+    pub stream_contains: Option<String>,
+
+    /// Match patterns and excludes as whole words only (e.g. "id" matches "id=5" but not "video")
+    #[arg(short = 'w', long)]
+    pub word: bool,
+
+    /// Show only messages that don't match the patterns, like grep -v (excludes still apply)
+    #[arg(short = 'v', long)]
+    pub invert_match: bool,
+
+    /// What patterns are matched against: the message, the log stream (Kamal: service and server), or either
+    #[arg(long, value_enum, default_value = "message")]
+    pub match_field: MatchField,
+
+    /// Only messages starting with this, e.g. a log level (combined with the patterns)
+    #[arg(long, value_name = "PREFIX")]
+    pub starts_with: Option<String>,
+
+    /// Only messages ending with this (combined with the patterns)
+    #[arg(long, value_name = "SUFFIX")]
+    pub ends_with: Option<String>,
+
+    /// Raw CloudWatch Insights query to run instead of the generated one (must reference @timestamp)
+    /// `{limit}` and `{patterns}` are replaced with the limit and the pattern/exclude filter expression
+    #[arg(long, value_name = "INSIGHTS_QUERY")]
+    pub query: Option<String>,
+
+    /// Drop results whose log stream contains this substring (CloudWatch, comma-separated)
+    #[arg(long, value_name = "SUBSTR", value_delimiter = ',')]
+    pub exclude_stream: Vec<String>,
+
+    /// Skip this server of the Kamal deployment (comma-separated)
+    #[arg(long, value_name = "HOST", value_delimiter = ',')]
+    pub exclude_server: Vec<String>,
+
+    /// Docker name filter (regex) for the Kamal container, instead of the `{service}-{role}-` prefix
+    #[arg(long, value_name = "REGEX")]
+    pub container_pattern: Option<String>,
+
+    /// Read the logs of this Kamal container (ID or name) on the first server, skipping the lookup; it may have exited
+    #[arg(long, value_name = "ID", conflicts_with = "container_pattern")]
+    pub container: Option<String>,
+
+    /// Search only the first (primary) Kamal server, as --follow does, instead of every server
+    #[arg(long)]
+    pub primary_only: bool,
+
+    /// Search Kamal logs since the container on the first server started, i.e. the last deploy (instead of --last)
+    #[arg(long, conflicts_with_all = ["last", "since_last", "follow"])]
+    pub since_deploy: bool,
+
+    /// Print only results: no search banner or progress (informational lines go to stderr otherwise)
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Output mode for results
+    #[arg(short, long, default_value = "interleaved")]
+    pub output: OutputMode,
+
+    /// Section grouped output by log group, region or log stream (implies -o grouped)
+    #[arg(long, value_name = "KEY")]
+    pub group_by: Option<GroupBy>,
+
+    /// Reformat single-line JSON messages as indented blocks (human output modes only)
+    #[arg(long)]
+    pub pretty_json: bool,
+
+    /// Include the log stream (CloudWatch stream or Kamal service) in human output
+    #[arg(long)]
+    pub show_stream: bool,
+
+    /// strftime pattern for timestamps in human output, e.g. "%H:%M:%S" or "%s" (default "%Y-%m-%d %H:%M:%S%.3f")
+    #[arg(long, value_name = "STRFTIME")]
+    pub time_format: Option<String>,
+
+    /// Link each log group prefix to the search in the CloudWatch console (OSC 8, terminals only)
+    #[arg(long)]
+    pub hyperlinks: bool,
+
+    /// Print each message on a single line, showing line breaks as ⏎ (human output modes only)
+    #[arg(long, conflicts_with = "pretty_json")]
+    pub flatten_newlines: bool,
+
+    /// Nest JSON results by region, then log group (implies -o json)
+    #[arg(long, conflicts_with = "correlate")]
+    pub json_nested: bool,
+
+    /// Print only the messages, one per line (same as -o raw)
+    #[arg(long, conflicts_with_all = ["correlate", "json_nested"])]
+    pub raw: bool,
+
+    /// Field separator for CSV output: one character, or \t for tabs (implies -o csv)
+    #[arg(long, value_name = "CHAR", conflicts_with_all = ["raw", "json_nested"])]
+    pub delimiter: Option<String>,
+
+    /// Show lines found in several log groups (same timestamp and message) once, listing every group
+    #[arg(long)]
+    pub merge_duplicates_across_groups: bool,
+
+    /// Show a message once per burst: repeats less than this long after its last occurrence are dropped (e.g. 5m)
+    #[arg(long, value_name = "DURATION")]
+    pub dedupe_window: Option<String>,
+
+    /// Write results to a file instead of stdout (gzip-compressed when the path ends in .gz)
+    #[arg(long, value_name = "PATH")]
+    pub output_file: Option<String>,
+
+    /// Gzip-compress --output-file regardless of its extension
+    #[arg(long, requires = "output_file")]
+    pub gzip: bool,
+
+    /// Copy the formatted results to the clipboard instead of printing them
+    #[arg(long, conflicts_with_all = ["output_file", "follow"])]
+    pub copy: bool,
+
+    /// Show results in $PAGER (less by default); auto pages human output on a terminal
+    #[arg(long, value_name = "WHEN", default_value = "auto")]
+    pub pager: PagerMode,
+
+    /// Maximum number of results per log group [default: 100]
+    #[arg(long)]
+    pub limit: Option<i32>,
+
+    /// Keep the oldest results in the time window instead of the newest (applies to --limit and --limit-total)
+    #[arg(long)]
+    pub oldest: bool,
+
+    /// Hard cap on the total results printed after merging all groups (keeps the newest)
+    /// Unlike --limit this applies across groups; JSON output sets "truncated": true when it cuts
+    #[arg(long)]
+    pub limit_total: Option<usize>,
+
+    /// Show an evenly drawn random sample of this many results (the same sample on every rerun)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub sample: Option<u64>,
+
+    /// Show only the earliest match of each log group (with --last-only, the latest too)
+    #[arg(long, conflicts_with_all = ["sample", "page", "correlate"])]
+    pub first: bool,
+
+    /// Show only the latest match of each log group (with --first, the earliest too)
+    #[arg(long, conflicts_with_all = ["sample", "page", "correlate"])]
+    pub last_only: bool,
+
+    /// Show only this page of the sorted results (1-based; see --page-size)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub page: Option<u64>,
+
+    /// Results per page for --page [default: 100]
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub page_size: Option<u64>,
+
+    /// Group results by request/trace id: a field name (e.g. request_id) or a regex with a capture group
+    /// Used by `-o correlated`; JSON output nests results under each id
+    #[arg(long, value_name = "FIELD_OR_REGEX")]
+    pub correlate: Option<String>,
+
+    /// Keep only JSON messages whose field matches, e.g. duration_ms>1000 or level==error (repeatable)
+    /// Operators: > < >= <= == != and ~= (regex, e.g. path~=^/health); applied after fetching
+    #[arg(long = "where", value_name = "FIELD<OP>VALUE")]
+    pub where_clauses: Vec<String>,
+
+    /// Drop JSON messages whose field matches, e.g. status==200 to hide successful requests (repeatable)
+    /// Same comparisons as --where; messages that aren't JSON or lack the field are kept
+    #[arg(long = "exclude-where", value_name = "FIELD<OP>VALUE")]
+    pub exclude_where_clauses: Vec<String>,
+
+    /// Drop results before this time, within the searched range (RFC3339 or YYYY-MM-DD HH:MM:SS)
+    #[arg(long, value_name = "DATETIME")]
+    pub min_timestamp: Option<String>,
+
+    /// Drop results after this time, within the searched range (RFC3339 or YYYY-MM-DD HH:MM:SS)
+    #[arg(long, value_name = "DATETIME")]
+    pub max_timestamp: Option<String>,
+
+    /// Drop results inside this window, e.g. a deploy: "2026-01-23 05:00..2026-01-23 05:15" (repeatable)
+    #[arg(long, value_name = "START..END")]
+    pub exclude_range: Vec<String>,
+
+    /// Print the data scanned by the Insights queries and an approximate cost (CloudWatch only)
+    #[arg(long)]
+    pub stats: bool,
+
+    /// Don't re-query older time windows when a log group hits the 10,000-row Insights cap
+    #[arg(long)]
+    pub no_window_split: bool,
+
+    /// Skip the check that warns when the time range predates a log group's retention
+    #[arg(long)]
+    pub no_retention_check: bool,
+
+    /// Print how the search was resolved (groups, time range, limits and where each came from) to stderr
+    #[arg(long)]
+    pub explain: bool,
+
+    /// Resolve and print the search plan without running it
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Probe the last few minutes first, estimate the data the full range would scan, and ask before running (CloudWatch only)
+    #[arg(long)]
+    pub explain_query: bool,
+
+    /// Run after --explain-query without asking (required when stdin isn't a terminal)
+    #[arg(short = 'y', long, requires = "explain_query")]
+    pub yes: bool,
+
+    /// Give up on log groups/servers still being searched after this long, e.g. 30s (shows what finished)
+    #[arg(long, value_name = "DURATION", conflicts_with = "follow")]
+    pub timeout: Option<String>,
+
+    /// Follow/tail logs in real-time (Kamal source only)
+    #[arg(short = 'f', long)]
+    pub follow: bool,
+
+    /// While following, also show the next N lines after each match, even if they don't match
+    #[arg(long, value_name = "N", requires = "follow")]
+    pub follow_context: Option<usize>,
+
+    /// Stop following once no matching lines arrive for this long, e.g. 30m (off by default)
+    #[arg(long, value_name = "DURATION", requires = "follow")]
+    pub follow_idle_timeout: Option<String>,
+
+    /// Only show logs newer than the last run of this search (falls back to --last on first run)
+    #[arg(long)]
+    pub since_last: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ExportArgs {
+    /// Log group to export, optionally prefixed with its region (e.g. us-east-1:app/prod)
+    #[arg(short, long)]
+    pub group: String,
+
+    /// Destination bucket and optional key prefix, e.g. log-archive/exports (the bucket policy must allow CloudWatch Logs)
+    #[arg(long = "export-s3", value_name = "BUCKET/PREFIX")]
+    pub destination: String,
+
+    /// Time range: e.g., "7d", "12h"
+    #[arg(short, long, default_value = "1d")]
+    pub last: String,
+
+    /// Start time (alternative to --last)
+    #[arg(long)]
+    pub start: Option<String>,
+
+    /// End time: with --start, or alone to end the --last window there instead of now
+    #[arg(long)]
+    pub end: Option<String>,
+
+    /// Only export log streams starting with this prefix
+    #[arg(long, value_name = "PREFIX")]
+    pub stream_prefix: Option<String>,
+
+    /// Print the task ID and return without waiting for the export to finish
+    #[arg(long)]
+    pub no_wait: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct BatchArgs {
+    /// File listing one search per line: `name: <search arguments>`, or a preset name
+    pub file: String,
+
+    /// Write each search's results to <DIR>/<name>.json (.csv or .txt for those output modes) instead of one JSON array on stdout
+    #[arg(long, value_name = "DIR")]
+    pub output_dir: Option<String>,
+
+    /// How many searches run at the same time
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    pub concurrency: u64,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum ConfigAction {
     /// Show current configuration
@@ -127,8 +465,67 @@ pub enum OutputMode {
     Interleaved,
     /// Results grouped by log group source
     Grouped,
+    /// Interleaved results with region/group prefixes aligned into columns
+    Columns,
+    /// Results grouped into one block per request/trace id (requires --correlate)
+    Correlated,
     /// Results displayed as they arrive
     Streaming,
     /// JSON output for AI/programmatic use
     Json,
+    /// JSON output on a single line (for piping)
+    JsonCompact,
+    /// Single-line JSON written as results arrive, without collecting them first
+    JsonStream,
+    /// Messages only, one per line, without timestamps, groups or colors (for jq, awk, ...)
+    Raw,
+    /// One row per result with a header line, for spreadsheets (see --delimiter)
+    Csv,
+}
+
+impl OutputMode {
+    /// Whether this mode produces JSON rather than human-readable output
+    pub fn is_json(&self) -> bool {
+        matches!(self, OutputMode::Json | OutputMode::JsonCompact | OutputMode::JsonStream)
+    }
+
+    /// Whether stdout holds results only, without notes like "Found N results" around them
+    pub fn is_bare(&self) -> bool {
+        self.is_json() || matches!(self, OutputMode::Raw | OutputMode::Csv)
+    }
+}
+
+/// What `-o grouped` starts a new section for
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum GroupBy {
+    /// One section per log group (or Kamal server)
+    #[default]
+    Group,
+    /// One section per AWS region
+    Region,
+    /// One section per log stream (CloudWatch instance/task, or Kamal service)
+    Stream,
+}
+
+/// What search patterns are matched against (`--match-field`)
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum MatchField {
+    /// The log message
+    #[default]
+    Message,
+    /// The log stream (CloudWatch) or the service and server (Kamal)
+    Stream,
+    /// Either the message or the stream
+    Any,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum PagerMode {
+    /// Page human-readable output when stdout is a terminal
+    #[default]
+    Auto,
+    /// Always page (unless writing to a file or the clipboard)
+    Always,
+    /// Never page
+    Never,
 }
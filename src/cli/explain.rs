@@ -0,0 +1,220 @@
+use super::{LogSource, MatchField, Origin, OutputMode, ResolvedSearch};
+use crate::aws::RegionalLogGroup;
+use crate::time::TimeRange;
+use std::io::{self, Write};
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S UTC";
+
+/// Write the fully resolved search plan, noting where each layered setting came from
+pub fn write_explain(out: &mut dyn Write, search: &ResolvedSearch) -> io::Result<()> {
+    let origins = &search.origins;
+
+    writeln!(out, "Search plan:")?;
+    writeln!(out, "  source:     {:?}", search.source)?;
+    if let Some(account) = &search.account {
+        writeln!(out, "  account:    {}", account)?;
+    }
+    if let Some(preset) = &search.preset {
+        writeln!(out, "  preset:     {}", preset)?;
+    }
+
+    match search.source {
+        LogSource::Cloudwatch => {
+            writeln!(out, "  groups:     [{}]", origins.groups)?;
+            for group in &search.groups {
+                let parsed = RegionalLogGroup::parse(group);
+                let region = parsed
+                    .region
+                    .or_else(|| search.region.clone())
+                    .unwrap_or_else(|| "default region".to_string());
+                match parsed.profile {
+                    Some(profile) => writeln!(out, "    {} ({}, profile {})", parsed.log_group, region, profile)?,
+                    None => writeln!(out, "    {} ({})", parsed.log_group, region)?,
+                }
+            }
+        }
+        LogSource::Kamal => match &search.env {
+            Some(env) => writeln!(out, "  deploy:     deploy.{}.yml over deploy.yml [{}]", env, origins.deploy_file)?,
+            None => writeln!(
+                out,
+                "  deploy:     {} [{}]",
+                search.deploy_file.as_deref().unwrap_or_default(),
+                origins.deploy_file
+            )?,
+        },
+    }
+
+    if let Some(query) = &search.query {
+        writeln!(out, "  query:      {} (raw Insights query)", query)?;
+    }
+    writeln!(out, "  patterns:   {}", with_origins(&search.patterns, 0, origins.preset_patterns))?;
+    writeln!(
+        out,
+        "  exclude:    {}",
+        with_origins(&search.exclude, origins.global_exclude, origins.preset_exclude)
+    )?;
+    writeln!(
+        out,
+        "  match:      {} {} and {}no exclude{}",
+        match (&search.match_field, &search.source) {
+            (MatchField::Message, _) => "message",
+            (MatchField::Stream, LogSource::Cloudwatch) => "log stream",
+            (MatchField::Stream, LogSource::Kamal) => "service/server",
+            (MatchField::Any, LogSource::Cloudwatch) => "message or log stream",
+            (MatchField::Any, LogSource::Kamal) => "message or service/server",
+        },
+        if search.invert_match { "lacks at least one pattern" } else { "contains every pattern" },
+        if search.match_field == MatchField::Message { "" } else { "message has " },
+        if search.word { ", as whole words" } else { "" }
+    )?;
+    if let Some(prefix) = &search.starts_with {
+        writeln!(out, "  starts:     '{}'", prefix)?;
+    }
+    if let Some(suffix) = &search.ends_with {
+        writeln!(out, "  ends:       '{}'", suffix)?;
+    }
+    if let Some(stream) = &search.stream {
+        writeln!(out, "  stream:     {:?}", stream)?;
+    }
+    if let Some(stream_contains) = &search.stream_contains {
+        writeln!(out, "  stream has: '{}' (after fetching)", stream_contains)?;
+    }
+    if !search.where_clauses.is_empty() {
+        writeln!(out, "  where:      {} (JSON messages, after fetching)", search.where_clauses.join(" and "))?;
+    }
+    if !search.exclude_where_clauses.is_empty() {
+        writeln!(out, "  not where:  {} (JSON messages, after fetching)", search.exclude_where_clauses.join(" or "))?;
+    }
+    if search.min_timestamp.is_some() || search.max_timestamp.is_some() {
+        writeln!(
+            out,
+            "  clip:       {} to {} (after fetching)",
+            search.min_timestamp.as_deref().unwrap_or("start"),
+            search.max_timestamp.as_deref().unwrap_or("end")
+        )?;
+    }
+    for range in &search.exclude_ranges {
+        writeln!(out, "  skip:       {} (after fetching)", range)?;
+    }
+
+    writeln!(out, "  time range: {}", describe_time_range(search))?;
+    writeln!(
+        out,
+        "  limit:      {} per group [{}], keeping the {}",
+        search.limit,
+        origins.limit,
+        if search.oldest { "oldest" } else { "newest" }
+    )?;
+    if let Some(total) = search.limit_total {
+        writeln!(out, "  total:      {}", total)?;
+    }
+    if let Some(timeout) = &search.timeout {
+        writeln!(out, "  timeout:    {} (partial results after that)", timeout)?;
+    }
+    if let Some(sample) = search.sample {
+        writeln!(out, "  sample:     {} results (after fetching)", sample)?;
+    }
+    if search.first || search.last_only {
+        let ends = match (search.first, search.last_only) {
+            (true, true) => "earliest and latest",
+            (true, false) => "earliest",
+            _ => "latest",
+        };
+        writeln!(out, "  bounds:     {} match per {:?} (after fetching)", ends, search.group_by)?;
+    }
+    if search.output == OutputMode::Grouped {
+        writeln!(out, "  output:     {:?} by {:?}", search.output, search.group_by)?;
+    } else {
+        writeln!(out, "  output:     {:?}", search.output)?;
+    }
+    writeln!(out, "  profile:    {} [{}]", search.profile.as_deref().unwrap_or("-"), origins.profile)?;
+    writeln!(out, "  region:     {} [{}]", search.region.as_deref().unwrap_or("-"), origins.region)?;
+    Ok(())
+}
+
+/// List values as `'value' [origin]`; the first `from_config` came from the config,
+/// the next `from_preset` from the preset
+fn with_origins(values: &[String], from_config: usize, from_preset: usize) -> String {
+    if values.is_empty() {
+        return "(none)".to_string();
+    }
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let origin = if i < from_config {
+                Origin::Config
+            } else if i < from_config + from_preset {
+                Origin::Preset
+            } else {
+                Origin::Cli
+            };
+            format!("'{}' [{}]", v, origin)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn describe_time_range(search: &ResolvedSearch) -> String {
+    if search.since_deploy {
+        return "since the container on the first server started (--since-deploy) [cli]".to_string();
+    }
+    let range = TimeRange::resolve(search.start.as_deref(), search.end.as_deref(), &search.last);
+    let description = match (&search.start, &search.end) {
+        (Some(_), _) => "--start/--end [cli]".to_string(),
+        (None, Some(end)) => format!("last {} [{}] ending {} [cli]", search.last, search.origins.last, end),
+        (None, None) => format!("last {} [{}]", search.last, search.origins.last),
+    };
+
+    let absolute = match range {
+        Ok(range) => format!("{} → {}", range.start.format(TIME_FORMAT), range.end.format(TIME_FORMAT)),
+        Err(e) => format!("invalid ({})", e),
+    };
+
+    if search.since_last {
+        format!("since the previous run, else {} ({})", absolute, description)
+    } else {
+        format!("{} ({})", absolute, description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Origins;
+
+    #[test]
+    fn test_explain_lists_groups_and_origins() {
+        let search = ResolvedSearch {
+            preset: Some("prod".to_string()),
+            groups: vec!["us-east-1:app/prod".to_string(), "api/prod".to_string()],
+            patterns: vec!["ERROR".to_string(), "timeout".to_string()],
+            last: "2h".to_string(),
+            limit: 200,
+            region: Some("ap-northeast-1".to_string()),
+            origins: Origins {
+                groups: Origin::Preset,
+                last: Origin::Preset,
+                limit: Origin::Config,
+                region: Origin::Cli,
+                preset_patterns: 1,
+                ..Origins::default()
+            },
+            ..ResolvedSearch::default()
+        };
+
+        let mut out = Vec::new();
+        write_explain(&mut out, &search).unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert!(text.contains("preset:     prod"));
+        assert!(text.contains("groups:     [preset]"));
+        assert!(text.contains("    app/prod (us-east-1)"));
+        assert!(text.contains("    api/prod (ap-northeast-1)"));
+        assert!(text.contains("'ERROR' [preset], 'timeout' [cli]"));
+        assert!(text.contains("exclude:    (none)"));
+        assert!(text.contains("(last 2h [preset])"));
+        assert!(text.contains("200 per group [config], keeping the newest"));
+        assert!(text.contains("region:     ap-northeast-1 [cli]"));
+    }
+}
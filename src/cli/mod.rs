@@ -1,3 +1,7 @@
 mod args;
+mod explain;
+mod resolve;
 
 pub use args::*;
+pub use explain::write_explain;
+pub use resolve::*;
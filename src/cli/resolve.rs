@@ -0,0 +1,909 @@
+use super::{Cli, GroupBy, LogSource, MatchField, OutputMode, PagerMode, SearchArgs};
+use crate::aws::{RegionalLogGroup, StreamFilter};
+use crate::config::{Account, Config};
+use crate::output::Page;
+use std::collections::HashMap;
+use thiserror::Error;
+
+const DEFAULT_TIME_RANGE: &str = "1h";
+/// Results per source when neither --limit, the preset nor the config sets one
+pub const DEFAULT_LIMIT: i32 = 100;
+const DEFAULT_DEPLOY_FILE: &str = "config/deploy.yml";
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Where a resolved setting came from
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Origin {
+    Cli,
+    Account,
+    Preset,
+    Config,
+    #[default]
+    Default,
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Origin::Cli => "cli",
+            Origin::Account => "account",
+            Origin::Preset => "preset",
+            Origin::Config => "config",
+            Origin::Default => "default",
+        })
+    }
+}
+
+/// Origins of the settings that can come from more than one place
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Origins {
+    pub groups: Origin,
+    pub last: Origin,
+    pub limit: Origin,
+    pub deploy_file: Origin,
+    pub profile: Origin,
+    pub region: Origin,
+    /// How many of the leading patterns/excludes came from the preset
+    pub preset_patterns: usize,
+    pub preset_exclude: usize,
+    /// How many of the leading excludes are the config's global_exclude (before the preset's)
+    pub global_exclude: usize,
+}
+
+/// A search with CLI flags, preset values and config defaults applied
+///
+/// Precedence for each setting is: CLI flag > preset > config default > built-in default.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResolvedSearch {
+    pub source: LogSource,
+    /// Preset the search was built from, if any
+    pub preset: Option<String>,
+    /// Account the search was built from, if any
+    pub account: Option<String>,
+    /// Log groups to search (CloudWatch only)
+    pub groups: Vec<String>,
+    pub patterns: Vec<String>,
+    pub exclude: Vec<String>,
+    /// Match patterns and excludes as whole words
+    pub word: bool,
+    /// Select messages that don't match the patterns
+    pub invert_match: bool,
+    /// What patterns are matched against
+    pub match_field: MatchField,
+    /// Required message prefix
+    pub starts_with: Option<String>,
+    /// Required message suffix
+    pub ends_with: Option<String>,
+    pub last: String,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// Log stream name or prefix to scope CloudWatch searches to
+    pub stream: Option<StreamFilter>,
+    /// Post-fetch filter on the log stream name
+    pub stream_contains: Option<String>,
+    /// Post-fetch filter dropping these log streams
+    pub exclude_streams: Vec<String>,
+    /// Kamal servers to skip (Kamal only)
+    pub exclude_servers: Vec<String>,
+    /// Docker name filter for Kamal containers
+    pub container_pattern: Option<String>,
+    /// Kamal container ID to read instead of looking one up
+    pub container: Option<String>,
+    /// Search only the first Kamal server
+    pub primary_only: bool,
+    /// Raw Insights query template replacing the generated query (CloudWatch only)
+    pub query: Option<String>,
+    pub output: OutputMode,
+    /// Section key for grouped output
+    pub group_by: GroupBy,
+    /// Indent JSON messages in human output
+    pub pretty_json: bool,
+    /// Include the log stream in human output
+    pub show_stream: bool,
+    /// strftime pattern for timestamps in human output
+    pub time_format: Option<String>,
+    /// Region labels for the columns view
+    pub region_aliases: HashMap<String, String>,
+    /// Link log group prefixes to the CloudWatch console
+    pub hyperlinks: bool,
+    /// One line per message in human output
+    pub flatten_newlines: bool,
+    /// Nest JSON results by region and log group
+    pub json_nested: bool,
+    /// CSV field separator as given (--delimiter)
+    pub delimiter: Option<String>,
+    /// Collapse identical lines found in several log groups
+    pub merge_duplicates: bool,
+    /// Drop repeats of a message within this long of its last occurrence
+    pub dedupe_window: Option<String>,
+    /// Suppress the search banner and other informational output
+    pub quiet: bool,
+    /// Write results here instead of stdout
+    pub output_file: Option<String>,
+    /// Gzip-compress the output file
+    pub gzip: bool,
+    /// Copy the results to the clipboard instead of printing them
+    pub copy: bool,
+    /// When to show results in a pager
+    pub pager: PagerMode,
+    pub limit: i32,
+    /// Cap on total results after merging groups/servers
+    pub limit_total: Option<usize>,
+    /// Size of the random sample drawn from the merged results
+    pub sample: Option<usize>,
+    /// Show only the earliest/latest match of each group (--first/--last-only)
+    pub first: bool,
+    pub last_only: bool,
+    /// Page of the sorted results to show, with the page size (--page/--page-size)
+    pub page: Option<Page>,
+    /// Keep the oldest entries in the window instead of the newest
+    pub oldest: bool,
+    /// Page through the time range when a query hits the Insights row cap (CloudWatch only)
+    pub split_windows: bool,
+    /// Field name or regex used to extract a correlation id
+    pub correlate: Option<String>,
+    /// Field comparisons on JSON messages, applied after fetching
+    pub where_clauses: Vec<String>,
+    /// Field comparisons dropping JSON messages, applied after fetching
+    pub exclude_where_clauses: Vec<String>,
+    /// Bounds on result timestamps, applied after fetching
+    pub min_timestamp: Option<String>,
+    pub max_timestamp: Option<String>,
+    /// Windows whose results are dropped after fetching
+    pub exclude_ranges: Vec<String>,
+    /// Kamal deploy.yml file path (Kamal only)
+    pub deploy_file: Option<String>,
+    /// Kamal destination whose deploy.{env}.yml is looked up instead of deploy_file (Kamal only)
+    pub env: Option<String>,
+    /// Lines fetched per wanted result when tailing containers (Kamal only)
+    pub tail_multiplier: usize,
+    pub follow: bool,
+    /// Lines shown after each match while following (Kamal only)
+    pub follow_context: usize,
+    /// Stop following after this long without matching lines (Kamal only)
+    pub follow_idle_timeout: Option<String>,
+    /// Warn when the time range starts before a log group's retention (CloudWatch only)
+    pub retention_check: bool,
+    /// Print Insights scan statistics after the results (CloudWatch only)
+    pub stats: bool,
+    /// Estimate the scan from a short probe and confirm before searching (CloudWatch only)
+    pub explain_query: bool,
+    /// Don't ask for confirmation after --explain-query
+    pub yes: bool,
+    /// Deadline for the whole search
+    pub timeout: Option<String>,
+    /// Resume from the checkpoint of the previous run
+    pub since_last: bool,
+    /// Start a Kamal search when the container started (its last deploy)
+    pub since_deploy: bool,
+    pub profile: Option<String>,
+    pub region: Option<String>,
+    /// Where each layered setting came from (used by --explain)
+    pub origins: Origins,
+}
+
+/// Errors that prevent a search from being resolved
+#[derive(Debug, Error, PartialEq)]
+pub enum ResolveError {
+    #[error("Preset '{name}' not found")]
+    UnknownPreset { name: String, available: Vec<String> },
+
+    #[error("Account '{name}' not found")]
+    UnknownAccount { name: String, available: Vec<String> },
+
+    #[error("No log groups specified. Use --groups or configure defaults.")]
+    NoLogGroups,
+
+    #[error("Output mode 'correlated' requires --correlate <FIELD_OR_REGEX>")]
+    MissingCorrelate,
+
+    #[error("--query must reference @timestamp so results can be ordered and displayed")]
+    QueryWithoutTimestamp,
+
+    #[error("--since-deploy reads the deploy time from the Kamal container; use it with --source kamal")]
+    SinceDeployWithoutKamal,
+
+    #[error("Streaming output writes each entry as it arrives, so it can't be combined with {option}")]
+    StreamingWith { option: &'static str },
+}
+
+/// Resolve the effective search from CLI arguments, an optional preset and config defaults
+pub fn resolve_search(
+    cli: &Cli,
+    args: &SearchArgs,
+    config: &Config,
+) -> Result<ResolvedSearch, ResolveError> {
+    let preset = match &args.preset {
+        Some(name) => match config.get_preset(name) {
+            Some(p) => Some(p),
+            None => {
+                let mut available: Vec<String> = config.presets.keys().cloned().collect();
+                available.sort();
+                return Err(ResolveError::UnknownPreset {
+                    name: name.clone(),
+                    available,
+                });
+            }
+        },
+        None => None,
+    };
+
+    let account = match &args.account {
+        Some(name) => match config.get_account(name) {
+            Some(a) => Some(a),
+            None => {
+                let mut available: Vec<String> = config.accounts.keys().cloned().collect();
+                available.sort();
+                return Err(ResolveError::UnknownAccount {
+                    name: name.clone(),
+                    available,
+                });
+            }
+        },
+        None => None,
+    };
+
+    let source = cli.source.clone().unwrap_or_else(|| {
+        match preset.and_then(|p| p.source.as_deref()) {
+            Some("kamal") => LogSource::Kamal,
+            _ => LogSource::Cloudwatch,
+        }
+    });
+
+    let mut origins = Origins::default();
+
+    // Preset patterns/excludes are combined with the CLI ones
+    let mut patterns = preset.map(|p| p.patterns.clone()).unwrap_or_default();
+    origins.preset_patterns = patterns.len();
+    patterns.extend(args.patterns.iter().cloned());
+    patterns.extend(args.verbatim_patterns.iter().cloned());
+
+    // Config-wide noise excludes come first, unless opted out, then the source's own
+    let mut exclude = Vec::new();
+    if !args.no_global_exclude {
+        exclude.extend(config.global_exclude.iter().cloned());
+        if source == LogSource::Cloudwatch {
+            exclude.extend(config.cloudwatch.global_exclude.iter().cloned());
+        }
+    }
+    origins.global_exclude = exclude.len();
+    let preset_exclude = preset.map(|p| p.exclude.clone()).unwrap_or_default();
+    origins.preset_exclude = preset_exclude.len();
+    exclude.extend(preset_exclude);
+    exclude.extend(args.exclude.iter().flat_map(|x| split_list(x)));
+
+    let groups = if !args.groups.is_empty() {
+        origins.groups = Origin::Cli;
+        args.groups.clone()
+    } else if let Some(a) = account.filter(|a| !a.groups.is_empty()) {
+        origins.groups = Origin::Account;
+        account_groups(a)
+    } else if let Some(p) = preset.filter(|p| !p.groups.is_empty()) {
+        origins.groups = Origin::Preset;
+        p.groups.clone()
+    } else {
+        if !config.default_groups.is_empty() {
+            origins.groups = Origin::Config;
+        }
+        config.default_groups.clone()
+    };
+
+    if source == LogSource::Cloudwatch && groups.is_empty() {
+        return Err(ResolveError::NoLogGroups);
+    }
+
+    if source == LogSource::Cloudwatch && args.since_deploy {
+        return Err(ResolveError::SinceDeployWithoutKamal);
+    }
+
+    if args.output == OutputMode::Correlated && args.correlate.is_none() {
+        return Err(ResolveError::MissingCorrelate);
+    }
+
+    if args.query.as_deref().is_some_and(|q| !q.contains("@timestamp")) {
+        return Err(ResolveError::QueryWithoutTimestamp);
+    }
+
+    // These need every result before the first one can be written
+    if matches!(args.output, OutputMode::Streaming | OutputMode::JsonStream) {
+        let needs_all_results = [
+            ("--limit-total", args.limit_total.is_some()),
+            ("--sample", args.sample.is_some()),
+            ("--page", args.page.is_some() || args.page_size.is_some()),
+            ("--correlate", args.correlate.is_some()),
+            ("--merge-duplicates-across-groups", args.merge_duplicates_across_groups),
+            ("--dedupe-window", args.dedupe_window.is_some()),
+        ];
+        if let Some((option, _)) = needs_all_results.into_iter().find(|(_, set)| *set) {
+            return Err(ResolveError::StreamingWith { option });
+        }
+    }
+
+    let (last, last_origin) = pick(
+        args.last.clone(),
+        preset.and_then(|p| p.time_range.clone()),
+        config.default_time_range.clone(),
+    );
+    origins.last = last_origin;
+    let last = last.unwrap_or_else(|| DEFAULT_TIME_RANGE.to_string());
+
+    let source_limit = match source {
+        LogSource::Cloudwatch => config.cloudwatch.default_limit,
+        LogSource::Kamal => config.kamal.default_limit,
+    };
+    let (limit, limit_origin) =
+        pick(args.limit, preset.and_then(|p| p.limit), source_limit.or(config.default_limit));
+    origins.limit = limit_origin;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+
+    let env = args.env.clone().filter(|_| source == LogSource::Kamal);
+    let deploy_file = match source {
+        // The destination's file is found when the search runs
+        LogSource::Kamal if env.is_some() => {
+            origins.deploy_file = Origin::Cli;
+            None
+        }
+        LogSource::Kamal => {
+            let (deploy_file, deploy_origin) =
+                pick(args.deploy_file.clone(), preset.and_then(|p| p.deploy_file.clone()), None);
+            origins.deploy_file = deploy_origin;
+            Some(deploy_file.unwrap_or_else(|| DEFAULT_DEPLOY_FILE.to_string()))
+        }
+        LogSource::Cloudwatch => None,
+    };
+
+    let (profile, profile_origin) = pick(
+        cli.profile.clone(),
+        preset.and_then(|p| p.profile.clone()),
+        config.default_profile.clone(),
+    );
+    let (region, region_origin) = pick(
+        cli.region.clone(),
+        preset.and_then(|p| p.region.clone()),
+        config.default_region.clone(),
+    );
+
+    // An account's profile and (single) region sit between the CLI and the preset
+    let (profile, profile_origin) = match account.and_then(|a| a.profile.clone()) {
+        Some(p) if profile_origin != Origin::Cli => (Some(p), Origin::Account),
+        _ => (profile, profile_origin),
+    };
+    let (region, region_origin) = match account.filter(|a| a.regions.len() == 1) {
+        Some(a) if region_origin != Origin::Cli => (Some(a.regions[0].clone()), Origin::Account),
+        _ => (region, region_origin),
+    };
+    origins.profile = profile_origin;
+    origins.region = region_origin;
+
+    Ok(ResolvedSearch {
+        source,
+        preset: preset.and(args.preset.clone()),
+        account: account.and(args.account.clone()),
+        groups,
+        patterns,
+        exclude,
+        word: args.word,
+        invert_match: args.invert_match,
+        match_field: args.match_field,
+        starts_with: args.starts_with.clone(),
+        ends_with: args.ends_with.clone(),
+        last,
+        start: args.start.clone(),
+        end: args.end.clone(),
+        stream: args
+            .stream
+            .clone()
+            .map(StreamFilter::Name)
+            .or_else(|| args.stream_prefix.clone().map(StreamFilter::Prefix)),
+        stream_contains: args.stream_contains.clone(),
+        exclude_streams: args.exclude_stream.clone(),
+        exclude_servers: args.exclude_server.clone(),
+        container_pattern: args.container_pattern.clone(),
+        container: args.container.clone(),
+        primary_only: args.primary_only,
+        query: args.query.clone(),
+        // --json-nested only makes sense as JSON, --group-by as grouped output and --delimiter as CSV
+        output: if args.raw {
+            OutputMode::Raw
+        } else if args.json_nested && !args.output.is_json() {
+            OutputMode::Json
+        } else if args.group_by.is_some() && args.output == OutputMode::Interleaved {
+            OutputMode::Grouped
+        } else if args.delimiter.is_some() && args.output == OutputMode::Interleaved {
+            OutputMode::Csv
+        } else {
+            args.output.clone()
+        },
+        group_by: args.group_by.unwrap_or_default(),
+        pretty_json: args.pretty_json,
+        show_stream: args.show_stream,
+        time_format: args.time_format.clone().or_else(|| config.time_format.clone()),
+        region_aliases: config.region_aliases.clone(),
+        hyperlinks: args.hyperlinks,
+        flatten_newlines: args.flatten_newlines,
+        json_nested: args.json_nested,
+        delimiter: args.delimiter.clone(),
+        merge_duplicates: args.merge_duplicates_across_groups,
+        dedupe_window: args.dedupe_window.clone(),
+        quiet: args.quiet,
+        output_file: args.output_file.clone(),
+        gzip: args.gzip || args.output_file.as_deref().is_some_and(|p| p.ends_with(".gz")),
+        copy: args.copy,
+        pager: args.pager,
+        limit,
+        limit_total: args.limit_total,
+        sample: args.sample.map(|n| n as usize),
+        first: args.first,
+        last_only: args.last_only,
+        page: (args.page.is_some() || args.page_size.is_some()).then(|| Page {
+            number: args.page.unwrap_or(1) as usize,
+            size: args.page_size.map_or(DEFAULT_PAGE_SIZE, |size| size as usize),
+        }),
+        oldest: args.oldest,
+        split_windows: !args.no_window_split,
+        correlate: args.correlate.clone(),
+        where_clauses: args.where_clauses.clone(),
+        exclude_where_clauses: args.exclude_where_clauses.clone(),
+        min_timestamp: args.min_timestamp.clone(),
+        max_timestamp: args.max_timestamp.clone(),
+        exclude_ranges: args.exclude_range.clone(),
+        deploy_file,
+        env,
+        tail_multiplier: config.kamal.tail_multiplier(),
+        follow: args.follow,
+        follow_context: args.follow_context.unwrap_or_default(),
+        follow_idle_timeout: args.follow_idle_timeout.clone().or(config.follow_idle_timeout.clone()),
+        retention_check: !args.no_retention_check,
+        stats: args.stats,
+        explain_query: args.explain_query,
+        yes: args.yes,
+        timeout: args.timeout.clone(),
+        since_last: args.since_last,
+        since_deploy: args.since_deploy,
+        profile,
+        region,
+        origins,
+    })
+}
+
+/// An account's groups, each without a region prefix fanned out to every account region
+fn account_groups(account: &Account) -> Vec<String> {
+    account
+        .groups
+        .iter()
+        .flat_map(|group| {
+            if account.regions.is_empty() || RegionalLogGroup::parse(group).region.is_some() {
+                vec![group.clone()]
+            } else {
+                account.regions.iter().map(|region| format!("{}:{}", region, group)).collect()
+            }
+        })
+        .collect()
+}
+
+/// Split a comma-separated list, trimming each item and dropping empty ones
+/// A backslash escapes a comma (`\,`) or another backslash (`\\`) so either can appear in an item
+pub fn split_list(input: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(next @ (',' | '\\')) => current.push(next),
+                Some(next) => {
+                    current.push('\\');
+                    current.push(next);
+                }
+                None => current.push('\\'),
+            },
+            ',' => items.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    items.push(current);
+
+    items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// First of the CLI, preset and config values that is set, with where it came from
+fn pick<T>(cli: Option<T>, preset: Option<T>, config: Option<T>) -> (Option<T>, Origin) {
+    match (cli, preset, config) {
+        (Some(v), _, _) => (Some(v), Origin::Cli),
+        (None, Some(v), _) => (Some(v), Origin::Preset),
+        (None, None, Some(v)) => (Some(v), Origin::Config),
+        (None, None, None) => (None, Origin::Default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Commands;
+    use crate::config::Preset;
+    use clap::Parser;
+
+    fn resolve(argv: &[&str], config: &Config) -> Result<ResolvedSearch, ResolveError> {
+        let mut full = vec!["log-hound"];
+        full.extend_from_slice(argv);
+        let cli = Cli::try_parse_from(full).unwrap();
+        match &cli.command {
+            Commands::Search(args) => resolve_search(&cli, args, config),
+            _ => panic!("expected search command"),
+        }
+    }
+
+    fn config_with_presets() -> Config {
+        let mut config = Config {
+            default_groups: vec!["default/group".to_string()],
+            ..Config::default()
+        };
+        config.presets.insert(
+            "prod".to_string(),
+            Preset {
+                groups: vec!["app/prod".to_string(), "api/prod".to_string()],
+                exclude: vec!["health".to_string()],
+                time_range: Some("2h".to_string()),
+                limit: Some(200),
+                profile: Some("prod-account".to_string()),
+                ..Preset::default()
+            },
+        );
+        config.presets.insert(
+            "kamal-app".to_string(),
+            Preset {
+                source: Some("kamal".to_string()),
+                deploy_file: Some("config/deploy.staging.yml".to_string()),
+                ..Preset::default()
+            },
+        );
+        config
+    }
+
+    #[test]
+    fn test_preset_only_groups() {
+        let config = config_with_presets();
+        let resolved = resolve(&["search", "-p", "prod", "ERROR"], &config).unwrap();
+        assert_eq!(resolved.source, LogSource::Cloudwatch);
+        assert_eq!(resolved.groups, vec!["app/prod", "api/prod"]);
+        assert_eq!(resolved.patterns, vec!["ERROR"]);
+        assert_eq!(resolved.exclude, vec!["health"]);
+        assert_eq!(resolved.last, "2h");
+        assert_eq!(resolved.limit, 200);
+    }
+
+    #[test]
+    fn test_cli_overrides_preset() {
+        let config = config_with_presets();
+        let resolved = resolve(
+            &["search", "-p", "prod", "-g", "other/group", "--last", "15m", "--limit", "5", "-x", "ping", "ERROR"],
+            &config,
+        )
+        .unwrap();
+        assert_eq!(resolved.groups, vec!["other/group"]);
+        assert_eq!(resolved.exclude, vec!["health", "ping"]);
+        assert_eq!(resolved.last, "15m");
+        assert_eq!(resolved.limit, 5);
+    }
+
+    #[test]
+    fn test_config_defaults_without_preset() {
+        let config = Config {
+            default_time_range: Some("30m".to_string()),
+            default_limit: Some(50),
+            ..config_with_presets()
+        };
+        let resolved = resolve(&["search", "ERROR"], &config).unwrap();
+        assert_eq!(resolved.groups, vec!["default/group"]);
+        assert_eq!(resolved.last, "30m");
+        assert_eq!(resolved.limit, 50);
+        assert_eq!(resolved.deploy_file, None);
+    }
+
+    #[test]
+    fn test_origins_track_each_layer() {
+        let config = Config {
+            default_limit: Some(50),
+            ..config_with_presets()
+        };
+        let resolved = resolve(&["search", "-p", "prod", "--last", "15m", "-x", "ping", "ERROR"], &config).unwrap();
+        assert_eq!(resolved.preset.as_deref(), Some("prod"));
+        assert_eq!(resolved.origins.groups, Origin::Preset);
+        assert_eq!(resolved.origins.last, Origin::Cli);
+        assert_eq!(resolved.origins.limit, Origin::Preset);
+        assert_eq!(resolved.origins.preset_exclude, 1);
+        assert_eq!(resolved.origins.preset_patterns, 0);
+
+        let resolved = resolve(&["search", "ERROR"], &config).unwrap();
+        assert_eq!(resolved.origins.groups, Origin::Config);
+        assert_eq!(resolved.origins.limit, Origin::Config);
+        assert_eq!(resolved.origins.last, Origin::Default);
+    }
+
+    #[test]
+    fn test_preset_profile_overrides_config_default() {
+        let config = Config {
+            default_profile: Some("default-account".to_string()),
+            default_region: Some("us-east-1".to_string()),
+            ..config_with_presets()
+        };
+        let resolved = resolve(&["search", "-p", "prod", "ERROR"], &config).unwrap();
+        assert_eq!(resolved.profile.as_deref(), Some("prod-account"));
+        assert_eq!(resolved.origins.profile, Origin::Preset);
+        // The preset doesn't set a region, so the config default still applies
+        assert_eq!(resolved.region.as_deref(), Some("us-east-1"));
+
+        let resolved = resolve(&["--profile", "cli-account", "search", "-p", "prod", "ERROR"], &config).unwrap();
+        assert_eq!(resolved.profile.as_deref(), Some("cli-account"));
+    }
+
+    #[test]
+    fn test_kamal_preset_with_deploy_file() {
+        let config = config_with_presets();
+        let resolved = resolve(&["search", "-p", "kamal-app", "ERROR"], &config).unwrap();
+        assert_eq!(resolved.source, LogSource::Kamal);
+        assert_eq!(resolved.deploy_file.as_deref(), Some("config/deploy.staging.yml"));
+        assert_eq!(resolved.last, "1h");
+        assert_eq!(resolved.limit, 100);
+    }
+
+    #[test]
+    fn test_kamal_source_does_not_require_groups() {
+        let resolved = resolve(&["search", "--source", "kamal", "ERROR"], &Config::default()).unwrap();
+        assert_eq!(resolved.source, LogSource::Kamal);
+        assert_eq!(resolved.deploy_file.as_deref(), Some("config/deploy.yml"));
+    }
+
+    #[test]
+    fn test_env_replaces_deploy_file() {
+        let config = config_with_presets();
+        let resolved = resolve(&["search", "-p", "kamal-app", "--env", "production", "ERROR"], &config).unwrap();
+        assert_eq!(resolved.env.as_deref(), Some("production"));
+        assert_eq!(resolved.deploy_file, None);
+        assert_eq!(resolved.origins.deploy_file, Origin::Cli);
+
+        assert!(Cli::try_parse_from(["log-hound", "search", "--env", "production", "-d", "deploy.yml"]).is_err());
+    }
+
+    #[test]
+    fn test_no_patterns_matches_all() {
+        let resolved = resolve(&["search", "-g", "app/prod"], &Config::default()).unwrap();
+        assert!(resolved.patterns.is_empty());
+        assert_eq!(resolved.groups, vec!["app/prod"]);
+    }
+
+    #[test]
+    fn test_split_list_escapes() {
+        assert_eq!(split_list("a, b ,,c"), vec!["a", "b", "c"]);
+        assert_eq!(split_list(r"a\, b,c"), vec!["a, b", "c"]);
+        assert_eq!(split_list(r"x\\,y"), vec![r"x\", "y"]);
+        // Other backslashes (e.g. regex escapes) are kept
+        assert_eq!(split_list(r"\d+ms"), vec![r"\d+ms"]);
+    }
+
+    #[test]
+    fn test_verbatim_patterns_and_escaped_excludes() {
+        let config = Config::default();
+        let resolved = resolve(
+            &["search", "ERROR", "-g", "app/prod", "--pattern", ", ", "--pattern", " id=5", "-x", r"a\,b,c"],
+            &config,
+        )
+        .unwrap();
+        assert_eq!(resolved.patterns, vec!["ERROR", ", ", " id=5"]);
+        assert_eq!(resolved.exclude, vec!["a,b", "c"]);
+    }
+
+    #[test]
+    fn test_global_exclude_applied_to_every_search() {
+        let mut config = config_with_presets();
+        config.global_exclude = vec!["ELB-HealthChecker".to_string()];
+
+        let resolved = resolve(&["search", "-p", "prod", "-x", "debug"], &config).unwrap();
+        assert_eq!(resolved.exclude, vec!["ELB-HealthChecker", "health", "debug"]);
+        assert_eq!(resolved.origins.global_exclude, 1);
+        assert_eq!(resolved.origins.preset_exclude, 1);
+
+        let resolved = resolve(&["search", "--source", "kamal", "ERROR"], &config).unwrap();
+        assert_eq!(resolved.exclude, vec!["ELB-HealthChecker"]);
+
+        let resolved = resolve(&["search", "-p", "prod", "--no-global-exclude"], &config).unwrap();
+        assert_eq!(resolved.exclude, vec!["health"]);
+        assert_eq!(resolved.origins.global_exclude, 0);
+    }
+
+    #[test]
+    fn test_source_specific_defaults() {
+        let mut config = config_with_presets();
+        config.default_limit = Some(50);
+        config.global_exclude = vec!["ELB-HealthChecker".to_string()];
+        config.cloudwatch.default_limit = Some(100);
+        config.cloudwatch.global_exclude = vec!["kube-probe".to_string()];
+        config.kamal.default_limit = Some(2000);
+        config.kamal.tail_multiplier = Some(3);
+
+        let resolved = resolve(&["search", "ERROR"], &config).unwrap();
+        assert_eq!(resolved.limit, 100);
+        assert_eq!(resolved.origins.limit, Origin::Config);
+        assert_eq!(resolved.exclude, vec!["ELB-HealthChecker", "kube-probe"]);
+        assert_eq!(resolved.origins.global_exclude, 2);
+
+        let resolved = resolve(&["search", "--source", "kamal", "ERROR"], &config).unwrap();
+        assert_eq!(resolved.limit, 2000);
+        assert_eq!(resolved.exclude, vec!["ELB-HealthChecker"]);
+        assert_eq!(resolved.tail_multiplier, 3);
+
+        // Presets and flags still win over source defaults
+        let resolved = resolve(&["search", "-p", "prod", "ERROR"], &config).unwrap();
+        assert_eq!(resolved.limit, 200);
+        let resolved = resolve(&["search", "--source", "kamal", "--limit", "5", "ERROR"], &config).unwrap();
+        assert_eq!(resolved.limit, 5);
+        let resolved = resolve(&["search", "--no-global-exclude", "ERROR"], &config).unwrap();
+        assert!(resolved.exclude.is_empty());
+
+        // Without a source default, the top-level one applies
+        config.kamal = Default::default();
+        let resolved = resolve(&["search", "--source", "kamal", "ERROR"], &config).unwrap();
+        assert_eq!(resolved.limit, 50);
+        assert_eq!(resolved.tail_multiplier, 10);
+    }
+
+    #[test]
+    fn test_empty_groups_error() {
+        let err = resolve(&["search", "ERROR"], &Config::default()).unwrap_err();
+        assert_eq!(err, ResolveError::NoLogGroups);
+    }
+
+    #[test]
+    fn test_correlated_output_requires_correlate() {
+        let config = config_with_presets();
+        let err = resolve(&["search", "-p", "prod", "-o", "correlated"], &config).unwrap_err();
+        assert_eq!(err, ResolveError::MissingCorrelate);
+
+        let resolved = resolve(&["search", "-p", "prod", "-o", "correlated", "--correlate", "request_id"], &config).unwrap();
+        assert_eq!(resolved.correlate.as_deref(), Some("request_id"));
+    }
+
+    #[test]
+    fn test_streaming_output_rejects_options_that_need_all_results() {
+        let config = config_with_presets();
+        let err = resolve(&["search", "-p", "prod", "-o", "json-stream", "--limit-total", "50"], &config).unwrap_err();
+        assert_eq!(err, ResolveError::StreamingWith { option: "--limit-total" });
+        let err = resolve(&["search", "-p", "prod", "-o", "json-stream", "--page-size", "20"], &config).unwrap_err();
+        assert_eq!(err, ResolveError::StreamingWith { option: "--page" });
+        let err = resolve(&["search", "-p", "prod", "-o", "json-stream", "--correlate", "request_id"], &config).unwrap_err();
+        assert_eq!(err, ResolveError::StreamingWith { option: "--correlate" });
+        let err = resolve(&["search", "-p", "prod", "-o", "streaming", "--limit-total", "5"], &config).unwrap_err();
+        assert_eq!(err, ResolveError::StreamingWith { option: "--limit-total" });
+
+        // json-compact collects first, and --first/--last-only already fall back to collecting
+        assert!(resolve(&["search", "-p", "prod", "-o", "json-compact", "--limit-total", "50"], &config).is_ok());
+        assert!(resolve(&["search", "-p", "prod", "-o", "json-stream", "--first"], &config).is_ok());
+    }
+
+    #[test]
+    fn test_since_deploy_needs_kamal() {
+        let config = config_with_presets();
+        let err = resolve(&["search", "-p", "prod", "--since-deploy"], &config).unwrap_err();
+        assert_eq!(err, ResolveError::SinceDeployWithoutKamal);
+
+        let resolved = resolve(&["--source", "kamal", "search", "--since-deploy"], &config).unwrap();
+        assert!(resolved.since_deploy);
+    }
+
+    #[test]
+    fn test_query_requires_timestamp() {
+        let config = config_with_presets();
+        let err = resolve(&["search", "-p", "prod", "--query", "stats count(*) by bin(5m)"], &config).unwrap_err();
+        assert_eq!(err, ResolveError::QueryWithoutTimestamp);
+
+        let query = "fields @timestamp, @message | filter {patterns} | limit {limit}";
+        let resolved = resolve(&["search", "-p", "prod", "--query", query], &config).unwrap();
+        assert_eq!(resolved.query.as_deref(), Some(query));
+    }
+
+    #[test]
+    fn test_json_nested_implies_json() {
+        let config = config_with_presets();
+        let resolved = resolve(&["search", "-p", "prod", "--json-nested"], &config).unwrap();
+        assert_eq!(resolved.output, OutputMode::Json);
+
+        let resolved = resolve(&["search", "-p", "prod", "--json-nested", "-o", "json-compact"], &config).unwrap();
+        assert_eq!(resolved.output, OutputMode::JsonCompact);
+    }
+
+    #[test]
+    fn test_group_by_implies_grouped() {
+        let config = config_with_presets();
+        let resolved = resolve(&["search", "-p", "prod"], &config).unwrap();
+        assert_eq!((resolved.output, resolved.group_by), (OutputMode::Interleaved, GroupBy::Group));
+
+        let resolved = resolve(&["search", "-p", "prod", "--group-by", "region"], &config).unwrap();
+        assert_eq!((resolved.output, resolved.group_by), (OutputMode::Grouped, GroupBy::Region));
+
+        // Other modes are left alone
+        let resolved = resolve(&["search", "-p", "prod", "--group-by", "stream", "-o", "json"], &config).unwrap();
+        assert_eq!((resolved.output, resolved.group_by), (OutputMode::Json, GroupBy::Stream));
+    }
+
+    #[test]
+    fn test_delimiter_implies_csv() {
+        let config = config_with_presets();
+        let resolved = resolve(&["search", "-p", "prod", "--delimiter", "\\t"], &config).unwrap();
+        assert_eq!((resolved.output, resolved.delimiter.as_deref()), (OutputMode::Csv, Some("\\t")));
+
+        // An explicit mode wins, as with --group-by
+        let resolved = resolve(&["search", "-p", "prod", "--delimiter", ";", "-o", "json"], &config).unwrap();
+        assert_eq!(resolved.output, OutputMode::Json);
+    }
+
+    #[test]
+    fn test_gzip_from_output_file_extension() {
+        let config = config_with_presets();
+        let resolved = resolve(&["search", "-p", "prod", "--output-file", "out.ndjson.gz"], &config).unwrap();
+        assert!(resolved.gzip);
+
+        let resolved = resolve(&["search", "-p", "prod", "--output-file", "out.json"], &config).unwrap();
+        assert!(!resolved.gzip);
+
+        let resolved = resolve(&["search", "-p", "prod", "--output-file", "out.json", "--gzip"], &config).unwrap();
+        assert!(resolved.gzip);
+    }
+
+    #[test]
+    fn test_account_fans_out_groups_across_regions() {
+        let mut config = config_with_presets();
+        config.accounts.insert(
+            "prod-us".to_string(),
+            Account {
+                profile: Some("prod".to_string()),
+                regions: vec!["us-east-1".to_string(), "us-west-2".to_string()],
+                groups: vec!["app/prod".to_string(), "eu-west-1:api/prod".to_string()],
+                ..Account::default()
+            },
+        );
+
+        let resolved = resolve(&["search", "--account", "prod-us", "ERROR"], &config).unwrap();
+        assert_eq!(resolved.account.as_deref(), Some("prod-us"));
+        assert_eq!(resolved.groups, vec!["us-east-1:app/prod", "us-west-2:app/prod", "eu-west-1:api/prod"]);
+        assert_eq!(resolved.origins.groups, Origin::Account);
+        assert_eq!(resolved.profile.as_deref(), Some("prod"));
+        assert_eq!(resolved.origins.profile, Origin::Account);
+
+        // The account's profile beats the preset's, but not --profile
+        let resolved = resolve(&["search", "--account", "prod-us", "-p", "prod"], &config).unwrap();
+        assert_eq!(resolved.profile.as_deref(), Some("prod"));
+        let resolved = resolve(&["--profile", "other", "search", "--account", "prod-us"], &config).unwrap();
+        assert_eq!(resolved.profile.as_deref(), Some("other"));
+    }
+
+    #[test]
+    fn test_unknown_account_error() {
+        let mut config = config_with_presets();
+        config.accounts.insert("staging".to_string(), Account::default());
+        let err = resolve(&["search", "--account", "missing"], &config).unwrap_err();
+        assert_eq!(
+            err,
+            ResolveError::UnknownAccount {
+                name: "missing".to_string(),
+                available: vec!["staging".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_unknown_preset_error() {
+        let err = resolve(&["search", "-p", "missing"], &config_with_presets()).unwrap_err();
+        assert_eq!(
+            err,
+            ResolveError::UnknownPreset {
+                name: "missing".to_string(),
+                available: vec!["kamal-app".to_string(), "prod".to_string()],
+            }
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use anyhow::Result;
+
+/// Place text on the system clipboard
+/// Fails on headless systems (no display server) or when built without the `clipboard` feature
+#[cfg(all(feature = "clipboard", not(target_os = "linux")))]
+pub fn copy(text: &str) -> Result<()> {
+    let mut clipboard = arboard::Clipboard::new()?;
+    clipboard.set_text(text)?;
+    Ok(())
+}
+
+/// Place text on the system clipboard
+/// On X11 and Wayland the clipboard is owned by a running process and is lost when it exits, so the
+/// text is handed to `wl-copy`, `xclip` or `xsel`, which stay in the background to serve it
+#[cfg(all(feature = "clipboard", target_os = "linux"))]
+pub fn copy(text: &str) -> Result<()> {
+    use anyhow::{bail, Context};
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    if !wayland && std::env::var_os("DISPLAY").is_none() {
+        bail!("no display server (neither WAYLAND_DISPLAY nor DISPLAY is set)");
+    }
+
+    for (program, args) in copy_commands(wayland) {
+        let spawned = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).with_context(|| format!("Failed to run {}", program)),
+        };
+
+        // Closing stdin hands over the text; the tool then forks to keep serving the selection
+        let mut stdin = child.stdin.take().context("No stdin for the clipboard tool")?;
+        stdin.write_all(text.as_bytes()).with_context(|| format!("Failed to write to {}", program))?;
+        drop(stdin);
+        let status = child.wait().with_context(|| format!("Failed to wait for {}", program))?;
+        if !status.success() {
+            bail!("{} failed ({})", program, status);
+        }
+        return Ok(());
+    }
+    bail!("no clipboard tool found: install wl-clipboard (Wayland) or xclip or xsel (X11)")
+}
+
+/// Clipboard tools to try in order, the Wayland one first under Wayland
+#[cfg(all(feature = "clipboard", target_os = "linux"))]
+fn copy_commands(wayland: bool) -> Vec<(&'static str, &'static [&'static str])> {
+    let wayland_tool: (&str, &[&str]) = ("wl-copy", &[]);
+    let x11_tools: [(&str, &[&str]); 2] = [("xclip", &["-selection", "clipboard"]), ("xsel", &["--clipboard", "--input"])];
+    if wayland {
+        std::iter::once(wayland_tool).chain(x11_tools).collect()
+    } else {
+        x11_tools.to_vec()
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn copy(_text: &str) -> Result<()> {
+    anyhow::bail!("log-hound was built without the `clipboard` feature")
+}
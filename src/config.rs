@@ -1,8 +1,11 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// How many lines `docker logs --tail` fetches per wanted result, to leave room for filtering
+pub const DEFAULT_TAIL_MULTIPLIER: usize = 10;
 
 /// Configuration file structure for log-hound
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -27,13 +30,111 @@ pub struct Config {
     #[serde(default)]
     pub default_limit: Option<i32>,
 
+    /// Regions selected when the TUI starts (falls back to a built-in set when empty)
+    #[serde(default)]
+    pub tui_default_regions: Vec<String>,
+
+    /// Limits offered by the TUI (falls back to a built-in list when empty)
+    #[serde(default)]
+    pub tui_limits: Vec<i32>,
+
+    /// Relative time ranges offered by the TUI, e.g. "30d" (falls back to a built-in list when empty)
+    #[serde(default)]
+    pub tui_time_ranges: Vec<String>,
+
+    /// Stop following after this long without new lines, e.g. "30m" (CLI and TUI; off when unset)
+    #[serde(default)]
+    pub follow_idle_timeout: Option<String>,
+
+    /// History shown when following starts from the TUI's Results, e.g. "15m" (default 5m)
+    #[serde(default)]
+    pub follow_backfill: Option<String>,
+
+    /// strftime pattern for timestamps in human output and the TUI's results, e.g. "%H:%M:%S"
+    #[serde(default)]
+    pub time_format: Option<String>,
+
+    /// AWS profile to use per region (for log groups that live in other accounts)
+    #[serde(default)]
+    pub region_profiles: HashMap<String, String>,
+
+    /// Labels shown for regions in the columns view and the TUI, e.g. ap-northeast-1 = "Tokyo"
+    #[serde(default)]
+    pub region_aliases: HashMap<String, String>,
+
+    /// Noise excluded from every search (health checks, probes), before preset and CLI excludes
+    #[serde(default)]
+    pub global_exclude: Vec<String>,
+
+    /// Defaults for CloudWatch searches only ([cloudwatch])
+    #[serde(default)]
+    pub cloudwatch: CloudwatchDefaults,
+
+    /// Defaults for Kamal searches only ([kamal])
+    #[serde(default)]
+    pub kamal: KamalDefaults,
+
     /// Saved presets for quick access
     #[serde(default)]
     pub presets: HashMap<String, Preset>,
+
+    /// AWS accounts with their own profile, regions and log groups (search --account)
+    #[serde(default)]
+    pub accounts: HashMap<String, Account>,
+}
+
+/// Settings that only apply when searching CloudWatch
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CloudwatchDefaults {
+    /// Result limit, in place of the top-level default_limit
+    #[serde(default)]
+    pub default_limit: Option<i32>,
+
+    /// Excluded from CloudWatch searches, after the top-level global_exclude
+    #[serde(default)]
+    pub global_exclude: Vec<String>,
+}
+
+/// Settings that only apply when searching Kamal containers
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct KamalDefaults {
+    /// Result limit, in place of the top-level default_limit
+    #[serde(default)]
+    pub default_limit: Option<i32>,
+
+    /// Lines `docker logs --tail` fetches per wanted result (default 10)
+    #[serde(default)]
+    pub tail_multiplier: Option<usize>,
+}
+
+impl KamalDefaults {
+    pub fn tail_multiplier(&self) -> usize {
+        self.tail_multiplier.unwrap_or(DEFAULT_TAIL_MULTIPLIER)
+    }
+}
+
+/// An AWS account whose log groups are searched together
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Account {
+    /// AWS profile for this account
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Regions to search; each group without a region prefix is searched in all of them
+    #[serde(default)]
+    pub regions: Vec<String>,
+
+    /// Log groups to search
+    #[serde(default)]
+    pub groups: Vec<String>,
+
+    /// Description for this account
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 /// A saved preset configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Preset {
     /// Log groups to search (CloudWatch only)
     #[serde(default)]
@@ -66,6 +167,14 @@ pub struct Preset {
     /// Kamal deploy.yml file path (for source = "kamal")
     #[serde(default)]
     pub deploy_file: Option<String>,
+
+    /// AWS profile override (e.g. a preset for another account)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// AWS region override
+    #[serde(default)]
+    pub region: Option<String>,
 }
 
 impl Config {
@@ -77,15 +186,51 @@ impl Config {
             return Ok(Config::default());
         }
 
-        let contents = fs::read_to_string(&config_path)
+        Self::load_from(&config_path)
+    }
+
+    /// Load configuration from a specific file
+    pub fn load_from(config_path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(config_path)
             .with_context(|| format!("Failed to read config file: {:?}", config_path))?;
 
         let config: Config = toml::from_str(&contents)
             .with_context(|| format!("Failed to parse config file: {:?}", config_path))?;
+        config
+            .validate()
+            .with_context(|| format!("Invalid config file: {:?}", config_path))?;
 
         Ok(config)
     }
 
+    /// Check values serde can't: the TUI lists must hold positive limits and valid durations
+    fn validate(&self) -> Result<()> {
+        if let Some(limit) = self.tui_limits.iter().find(|&&limit| limit <= 0) {
+            bail!("tui_limits: {} is not a positive limit", limit);
+        }
+        for range in &self.tui_time_ranges {
+            crate::time::parse_duration(range).with_context(|| format!("tui_time_ranges: '{}'", range))?;
+        }
+        for (section, limit) in [("cloudwatch", self.cloudwatch.default_limit), ("kamal", self.kamal.default_limit)] {
+            if let Some(limit) = limit.filter(|&limit| limit <= 0) {
+                bail!("{}.default_limit: {} is not a positive limit", section, limit);
+            }
+        }
+        if self.kamal.tail_multiplier == Some(0) {
+            bail!("kamal.tail_multiplier: must be at least 1");
+        }
+        if let Some(format) = &self.time_format {
+            crate::time::validate_time_format(format).context("time_format")?;
+        }
+        if let Some(backfill) = &self.follow_backfill {
+            crate::time::parse_duration(backfill).with_context(|| format!("follow_backfill: '{}'", backfill))?;
+        }
+        if let Some(timeout) = &self.follow_idle_timeout {
+            crate::time::parse_timeout(timeout).with_context(|| format!("follow_idle_timeout: '{}'", timeout))?;
+        }
+        Ok(())
+    }
+
     /// Get the default configuration file path
     pub fn default_path() -> PathBuf {
         dirs::home_dir()
@@ -98,6 +243,11 @@ impl Config {
         self.presets.get(name)
     }
 
+    /// Get an account by name
+    pub fn get_account(&self, name: &str) -> Option<&Account> {
+        self.accounts.get(name)
+    }
+
     /// List all available presets
     pub fn list_presets(&self) -> Vec<(&String, &Preset)> {
         self.presets.iter().collect()
@@ -114,6 +264,22 @@ impl Config {
 # Default AWS region (optional)
 # default_region = "ap-northeast-1"
 
+# Regions selected when the TUI starts (optional; default_region is always included)
+# tui_default_regions = ["us-east-1", "eu-west-1"]
+
+# Limits and time ranges the TUI cycles through (optional; built-in lists otherwise)
+# tui_limits = [100, 1000, 10000, 50000]
+# tui_time_ranges = ["15m", "1h", "1d", "7d", "30d"]
+
+# Stop following when no new lines arrive for this long (optional; off by default)
+# follow_idle_timeout = "30m"
+
+# History loaded before streaming when following from the TUI's Results (optional; default 5m)
+# follow_backfill = "15m"
+
+# Timestamp format in results, as a strftime pattern (optional; --time-format overrides it)
+# time_format = "%H:%M:%S%.3f"
+
 # Default log groups when no -g is specified
 default_groups = []
 
@@ -123,6 +289,35 @@ default_time_range = "1h"
 # Default result limit
 default_limit = 100
 
+# Excluded from every search, CLI and TUI (skip with --no-global-exclude)
+# global_exclude = ["ELB-HealthChecker", "kube-probe"]
+
+# Defaults for one source only (optional); default_limit here wins over the one above
+# [cloudwatch]
+# default_limit = 100
+# global_exclude = ["ELB-HealthChecker"]   # Added to the global_exclude above
+#
+# [kamal]
+# default_limit = 500
+# tail_multiplier = 20   # docker logs --tail fetches limit × this many lines (default 10)
+
+# AWS profile per region, for log groups in other accounts (optional)
+# A single group can also pick its profile with "profile@region:group"
+# [region_profiles]
+# us-east-1 = "billing-prod"
+
+# Region labels in -o columns and the TUI, in place of abbreviations like AN1 (optional)
+# [region_aliases]
+# ap-northeast-1 = "Tokyo"
+# us-east-1 = "Virginia"
+
+# Accounts: a profile, regions and groups searched together (optional)
+# Use with: log-hound search --account <account_name> "ERROR"
+# [accounts.prod-us]
+# profile = "prod"
+# regions = ["us-east-1", "us-west-2"]
+# groups = ["app/production", "api/production"]
+
 # Presets for quick access
 # Use with: log-hound search -p <preset_name> "ERROR"
 
@@ -136,6 +331,8 @@ limit = 200
 description = "Staging environment"
 groups = ["app/staging", "api/staging"]
 exclude = ["health-check", "ping"]
+# profile = "staging"   # Overrides default_profile for this preset
+# region = "us-west-2"  # Overrides default_region for this preset
 
 [presets.all-regions]
 description = "Search across all regions"
@@ -0,0 +1,72 @@
+use anyhow::Result;
+use std::future::Future;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::time::Instant;
+
+/// A search-wide deadline (--timeout), shared by every log group or server searched
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Deadline {
+    at: Instant,
+    timeout: Duration,
+}
+
+/// A search was cut off by its deadline
+#[derive(Debug, Error)]
+#[error("timed out after {}s", .0.as_secs_f64())]
+pub struct TimedOut(pub Duration);
+
+impl Deadline {
+    /// A deadline `timeout` from now
+    pub fn after(timeout: Duration) -> Self {
+        Self { at: Instant::now() + timeout, timeout }
+    }
+}
+
+/// Run `future` until the deadline, if any; when it passes the future is dropped, which cancels
+/// whatever it was waiting on, and a `TimedOut` error is returned instead
+pub async fn within<T>(deadline: Option<Deadline>, future: impl Future<Output = Result<T>>) -> Result<T> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline.at, future)
+            .await
+            .unwrap_or_else(|_| Err(TimedOut(deadline.timeout).into())),
+        None => future.await,
+    }
+}
+
+/// Whether an error came from a deadline passing
+pub fn is_timeout(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<TimedOut>().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_slow_sources_time_out_with_partial_results() {
+        let source = |delay_ms: u64, lines: Vec<&'static str>| async move {
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            Ok::<_, anyhow::Error>(lines)
+        };
+
+        let deadline = Some(Deadline::after(Duration::from_millis(200)));
+        let started = std::time::Instant::now();
+        let results = futures::future::join_all([
+            within(deadline, source(10, vec!["fast"])),
+            within(deadline, source(10_000, vec!["slow"])),
+        ])
+        .await;
+
+        // The slow source doesn't hold up the fast one's results
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(results[0].as_ref().unwrap(), &vec!["fast"]);
+        let error = results[1].as_ref().unwrap_err();
+        assert!(is_timeout(error));
+        assert_eq!(error.to_string(), "timed out after 0.2s");
+
+        // No deadline: nothing is cut off
+        assert_eq!(within(None, source(10, vec!["x"])).await.unwrap(), vec!["x"]);
+        assert!(!is_timeout(&anyhow::anyhow!("boom")));
+    }
+}
@@ -0,0 +1,109 @@
+use std::io::IsTerminal;
+use tracing::level_filters::LevelFilter;
+use tracing::Subscriber;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::time::Uptime;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Setting this to anything turns on debug diagnostics, like `--verbose --verbose`
+const DEBUG_ENV: &str = "LOG_HOUND_DEBUG";
+
+/// Diagnostics level for a `--verbose` count: info, then debug (query/SSH spans), then trace
+/// Without --verbose, LOG_HOUND_DEBUG still enables debug
+pub fn level(verbosity: u8, debug_env: bool) -> LevelFilter {
+    match verbosity {
+        0 if debug_env => LevelFilter::DEBUG,
+        0 => LevelFilter::OFF,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Send the crate's diagnostics to stderr at the level `--verbose` asks for
+pub fn init(verbosity: u8) {
+    let level = level(verbosity, std::env::var_os(DEBUG_ENV).is_some());
+    if level != LevelFilter::OFF {
+        let _ = tracing::subscriber::set_global_default(subscriber(level, std::io::stderr, std::io::stderr().is_terminal()));
+    }
+}
+
+/// Writes one line per event, and one per span when it closes with how long it was busy:
+/// `  0.412s DEBUG query{group=app/prod}: close time.busy=388ms time.idle=24ms`
+pub fn subscriber<W>(level: LevelFilter, writer: W, ansi: bool) -> impl Subscriber + Send + Sync
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_timer(Uptime::default())
+        .with_target(false)
+        .with_ansi(ansi)
+        .with_writer(writer)
+        .finish()
+}
+
+/// A writer tests can read back after the subscriber is done with it
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct Captured(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+#[cfg(test)]
+impl Captured {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+#[cfg(test)]
+impl std::io::Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl<'w> MakeWriter<'w> for Captured {
+    type Writer = Captured;
+
+    fn make_writer(&'w self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbosity_levels() {
+        assert_eq!(level(0, false), LevelFilter::OFF);
+        assert_eq!(level(0, true), LevelFilter::DEBUG);
+        assert_eq!(level(1, false), LevelFilter::INFO);
+        assert_eq!(level(2, false), LevelFilter::DEBUG);
+        assert_eq!(level(3, true), LevelFilter::TRACE);
+    }
+
+    #[test]
+    fn test_events_carry_their_span_and_spans_report_timing() {
+        let captured = Captured::default();
+
+        tracing::subscriber::with_default(subscriber(level(2, false), captured.clone(), false), || {
+            let span = tracing::debug_span!("ssh_connect", server = %"web-1");
+            span.in_scope(|| tracing::debug!(attempt = 2, "retrying"));
+            tracing::trace!("too detailed");
+        });
+
+        let text = captured.text();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2, "{}", text);
+        assert!(lines[0].ends_with("DEBUG ssh_connect{server=web-1}: retrying attempt=2"), "{}", lines[0]);
+        assert!(lines[1].contains("DEBUG ssh_connect{server=web-1}: close time.busy="), "{}", lines[1]);
+    }
+}
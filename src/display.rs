@@ -0,0 +1,309 @@
+use crate::aws::LogEntry;
+use ratatui::style::Color;
+use regex::Regex;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// An ANSI SGR (color/style) sequence, e.g. `\x1b[1;31m`
+static SGR: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\x1b\[[0-9;:]*m").unwrap());
+
+/// Palette cycled through when coloring log groups
+pub const LOG_GROUP_COLORS: &[Color] = &[
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
+    Color::LightCyan,
+    Color::LightMagenta,
+    Color::LightYellow,
+    Color::LightGreen,
+    Color::LightBlue,
+];
+
+/// Color used for groups missing from a color map
+const DEFAULT_GROUP_COLOR: Color = Color::Blue;
+
+/// Human-readable byte count: 1536 -> "1.5 KB"
+pub fn format_bytes(bytes: f64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", value)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Log group key ("region:group") to display color
+pub type ColorMap = HashMap<String, Color>;
+
+/// Abbreviate an AWS region for compact display: ap-northeast-1 -> AN1
+pub fn shorten_region(region: &str) -> String {
+    let parts: Vec<&str> = region.split('-').collect();
+    if parts.len() < 3 {
+        return region.chars().take(6).collect();
+    }
+
+    // GovCloud regions have an extra segment: us-gov-west-1 -> GW1
+    let (area, direction) = match (parts[0], parts[1]) {
+        ("us", "gov") if parts.len() >= 4 => ("gov", parts[2]),
+        (area, direction) => (area, direction),
+    };
+
+    let prefix = match (area, direction) {
+        ("ap", "northeast") => Some("AN"),
+        ("ap", "southeast") => Some("AS"),
+        ("ap", "south") => Some("AO"),
+        ("ap", "east") => Some("AE"),
+        ("us", "east") => Some("UE"),
+        ("us", "west") => Some("UW"),
+        ("gov", "east") => Some("GE"),
+        ("gov", "west") => Some("GW"),
+        ("eu", "west") => Some("EW"),
+        ("eu", "central") => Some("EC"),
+        ("eu", "north") => Some("EN"),
+        ("eu", "south") => Some("ES"),
+        ("ca", "central") => Some("CC"),
+        ("ca", "west") => Some("CW"),
+        ("sa", "east") => Some("SE"),
+        ("af", "south") => Some("AF"),
+        ("me", "south") => Some("MS"),
+        ("me", "central") => Some("MC"),
+        ("il", "central") => Some("IC"),
+        ("mx", "central") => Some("MX"),
+        ("cn", "north") => Some("CN"),
+        ("cn", "northwest") => Some("CNW"),
+        _ => None,
+    };
+    // Unknown families: first letters of the first two segments
+    let prefix = prefix.map(str::to_string).unwrap_or_else(|| {
+        area.chars().take(1).chain(direction.chars().take(1)).collect::<String>().to_uppercase()
+    });
+    format!("{}{}", prefix, parts.last().unwrap_or(&""))
+}
+
+/// Compact label for a region: its configured alias (region_aliases), else the abbreviation
+pub fn region_label(region: &str, aliases: &HashMap<String, String>) -> String {
+    aliases.get(region).cloned().unwrap_or_else(|| shorten_region(region))
+}
+
+/// Abbreviate a log group from the initials of its last path segment: /aws/app/rails-web -> RW
+pub fn shorten_group(group: &str) -> String {
+    let name = group.rsplit('/').next().unwrap_or(group);
+    let abbrev: String = name
+        .split(['-', '_', '.'])
+        .filter(|s| !s.is_empty())
+        .map(|s| s.chars().next().unwrap_or(' '))
+        .collect::<String>()
+        .to_uppercase();
+
+    if abbrev.len() >= 2 {
+        abbrev
+    } else {
+        name.chars().take(3).collect::<String>().to_uppercase()
+    }
+}
+
+/// Longest log stream name shown before the middle is elided
+const STREAM_MAX_WIDTH: usize = 20;
+
+/// Shorten a log stream to its last path segment, eliding the middle of long names
+/// so both the prefix and the distinguishing tail (task id, instance id) stay visible:
+/// ecs/rails-web/9f1c2b3a4d5e6f708192a3b4c5d6e7f8 -> 9f1c2b3a4d…4c5d6e7f8
+pub fn shorten_stream(stream: &str) -> String {
+    let name = stream.rsplit('/').next().filter(|s| !s.is_empty()).unwrap_or(stream);
+    let chars: Vec<char> = name.chars().collect();
+    if chars.len() <= STREAM_MAX_WIDTH {
+        return name.to_string();
+    }
+
+    let head = STREAM_MAX_WIDTH / 2;
+    let tail = STREAM_MAX_WIDTH - head - 1;
+    let mut short: String = chars[..head].iter().collect();
+    short.push('…');
+    short.extend(&chars[chars.len() - tail..]);
+    short
+}
+
+/// Key identifying a log group across regions: "region:group" or just "group"
+pub fn group_key(region: Option<&str>, log_group: &str) -> String {
+    match region {
+        Some(r) => format!("{}:{}", r, log_group),
+        None => log_group.to_string(),
+    }
+}
+
+/// Assign palette colors to group keys in the given order
+pub fn build_color_map(keys: impl IntoIterator<Item = String>) -> ColorMap {
+    let mut map = ColorMap::new();
+    for key in keys {
+        let next = LOG_GROUP_COLORS[map.len() % LOG_GROUP_COLORS.len()];
+        map.entry(key).or_insert(next);
+    }
+    map
+}
+
+/// Look up the color for an entry's log group
+pub fn group_color(map: &ColorMap, entry: &LogEntry) -> Color {
+    map.get(&group_key(entry.region.as_deref(), &entry.log_group))
+        .copied()
+        .unwrap_or(DEFAULT_GROUP_COLOR)
+}
+
+/// Color for a detected log level (see `level::detect`); lines without one stay neutral
+pub fn level_color(level: Option<&str>) -> Color {
+    match level {
+        Some("FATAL") => Color::LightMagenta,
+        Some("ERROR") => Color::Red,
+        Some("WARN") => Color::Yellow,
+        Some("INFO") => Color::Green,
+        Some("DEBUG") => Color::Blue,
+        Some("TRACE") => Color::DarkGray,
+        _ => Color::Gray,
+    }
+}
+
+/// Wrap text in an OSC 8 terminal hyperlink; terminals without support show just the text
+pub fn hyperlink(text: &str, url: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// Drop the color codes apps write into their own log lines; other escape sequences are left for
+/// `sanitize` to show
+pub fn strip_sgr(text: &str) -> Cow<'_, str> {
+    SGR.replace_all(text, "")
+}
+
+/// Make log text safe to print to a terminal: control characters other than newline and tab
+/// (escape sequences, NUL, backspace, a lone \r...) show as their Unicode control picture, e.g.
+/// NUL as ␀ and ESC as ␛, and C1 controls as �; JSON output escapes them instead
+pub fn sanitize(text: &str) -> Cow<'_, str> {
+    let is_unsafe = |c: char, next: Option<char>| c.is_control() && !matches!((c, next), ('\n' | '\t', _) | ('\r', Some('\n')));
+    let nexts = text.chars().skip(1).map(Some).chain([None]);
+    if !text.chars().zip(nexts).any(|(c, next)| is_unsafe(c, next)) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut chars = text.chars().peekable();
+    let mut clean = String::with_capacity(text.len());
+    while let Some(c) = chars.next() {
+        clean.push(match c {
+            c if !is_unsafe(c, chars.peek().copied()) => c,
+            '\0'..='\x1f' => char::from_u32(0x2400 + c as u32).unwrap_or('\u{FFFD}'),
+            '\x7f' => '\u{2421}',
+            _ => '\u{FFFD}',
+        });
+    }
+    Cow::Owned(clean)
+}
+
+/// Convert a palette color for use with the `colored` crate in CLI output
+pub fn to_terminal_color(color: Color) -> colored::Color {
+    match color {
+        Color::Cyan => colored::Color::Cyan,
+        Color::Magenta => colored::Color::Magenta,
+        Color::Yellow => colored::Color::Yellow,
+        Color::Green => colored::Color::Green,
+        Color::Red => colored::Color::Red,
+        Color::LightCyan => colored::Color::BrightCyan,
+        Color::LightMagenta => colored::Color::BrightMagenta,
+        Color::LightYellow => colored::Color::BrightYellow,
+        Color::LightGreen => colored::Color::BrightGreen,
+        Color::LightBlue => colored::Color::BrightBlue,
+        Color::LightRed => colored::Color::BrightRed,
+        _ => colored::Color::Blue,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512.0), "512 B");
+        assert_eq!(format_bytes(1536.0), "1.5 KB");
+        assert_eq!(format_bytes(3.0 * 1024.0 * 1024.0 * 1024.0), "3.0 GB");
+    }
+
+    #[test]
+    fn test_level_color() {
+        use crate::level;
+
+        assert_eq!(level_color(level::detect("ERROR: upstream timeout")), Color::Red);
+        assert_eq!(level_color(level::detect(r#"{"level":"warning"}"#)), Color::Yellow);
+        assert_eq!(level_color(level::detect("level=info msg=ok")), Color::Green);
+        assert_eq!(level_color(level::detect("[DEBUG] cache miss")), Color::Blue);
+        assert_eq!(level_color(level::detect("TRACE enter")), Color::DarkGray);
+        assert_eq!(level_color(level::detect(r#"{"level":60}"#)), Color::LightMagenta);
+        assert_eq!(level_color(level::detect("GET /api 200")), Color::Gray);
+    }
+
+    #[test]
+    fn test_shorten_stream() {
+        assert_eq!(shorten_stream("web-1"), "web-1");
+        assert_eq!(shorten_stream("ecs/rails-web/i-0abc123def4567"), "i-0abc123def4567");
+        assert_eq!(shorten_stream("ecs/rails-web/9f1c2b3a4d5e6f708192a3b4c5d6e7f8"), "9f1c2b3a4d…4c5d6e7f8");
+        assert_eq!(shorten_stream("ecs/rails-web/9f1c2b3a4d5e6f708192a3b4c5d6e7f8").chars().count(), 20);
+    }
+
+    #[test]
+    fn test_shorten_region() {
+        assert_eq!(shorten_region("ap-northeast-1"), "AN1");
+        assert_eq!(shorten_region("ap-southeast-2"), "AS2");
+        assert_eq!(shorten_region("us-east-1"), "UE1");
+        assert_eq!(shorten_region("eu-central-1"), "EC1");
+        assert_eq!(shorten_region("local"), "local");
+    }
+
+    #[test]
+    fn test_region_aliases_override_abbreviation() {
+        let aliases = HashMap::from([("ap-northeast-1".to_string(), "Tokyo".to_string())]);
+        assert_eq!(region_label("ap-northeast-1", &aliases), "Tokyo");
+        assert_eq!(region_label("us-east-1", &aliases), "UE1");
+        assert_eq!(region_label("ap-northeast-1", &HashMap::new()), "AN1");
+    }
+
+    #[test]
+    fn test_shorten_newer_regions() {
+        assert_eq!(shorten_region("af-south-1"), "AF1");
+        assert_eq!(shorten_region("eu-south-2"), "ES2");
+        assert_eq!(shorten_region("me-south-1"), "MS1");
+        assert_eq!(shorten_region("me-central-1"), "MC1");
+        assert_eq!(shorten_region("il-central-1"), "IC1");
+        assert_eq!(shorten_region("ca-west-1"), "CW1");
+        assert_eq!(shorten_region("mx-central-1"), "MX1");
+        assert_eq!(shorten_region("us-gov-west-1"), "GW1");
+        assert_eq!(shorten_region("cn-northwest-1"), "CNW1");
+        // Deterministic fallback for families not in the table
+        assert_eq!(shorten_region("xy-middle-3"), "XM3");
+    }
+
+    #[test]
+    fn test_shorten_group() {
+        assert_eq!(shorten_group("/aws/app/rails-web"), "RW");
+        assert_eq!(shorten_group("/ecs/api_worker.jobs"), "AWJ");
+        assert_eq!(shorten_group("/aws/api"), "API");
+        assert_eq!(shorten_group("kamal:web1"), "KAM");
+    }
+
+    #[test]
+    fn test_build_color_map_keeps_first_assignment() {
+        let map = build_color_map(
+            ["us-east-1:app", "us-east-1:api", "us-east-1:app"]
+                .iter()
+                .map(|s| s.to_string()),
+        );
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["us-east-1:app"], LOG_GROUP_COLORS[0]);
+        assert_eq!(map["us-east-1:api"], LOG_GROUP_COLORS[1]);
+    }
+}
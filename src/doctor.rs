@@ -0,0 +1,212 @@
+use crate::aws;
+use crate::config::Config;
+use colored::Colorize;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long the CloudWatch connectivity check may take before it counts as failed
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Pass,
+    /// Something optional is missing, e.g. ssh when Kamal isn't used
+    Warn,
+    /// Searches will fail until this is fixed
+    Fail,
+}
+
+/// Outcome of one environment check
+#[derive(Debug)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: String) -> Self {
+        Self { name, status: Status::Pass, detail, hint: None }
+    }
+
+    fn warn(name: &'static str, detail: String, hint: &str) -> Self {
+        Self { name, status: Status::Warn, detail, hint: Some(hint.to_string()) }
+    }
+
+    fn fail(name: &'static str, detail: String, hint: &str) -> Self {
+        Self { name, status: Status::Fail, detail, hint: Some(hint.to_string()) }
+    }
+}
+
+/// Run every check for the given AWS profile and region
+pub async fn run(profile: Option<&str>, region: Option<&str>, endpoint_url: Option<&str>) -> Vec<Check> {
+    let mut checks = vec![check_config(&Config::default_path())];
+    checks.extend(check_aws(profile, region, endpoint_url).await);
+    checks.push(check_binary(
+        "ssh",
+        "Needed for --source kamal; install an OpenSSH client",
+    ));
+    checks.push(check_binary(
+        "docker",
+        "Only the Kamal servers need docker (log-hound runs it over ssh); needed locally to debug them",
+    ));
+    checks
+}
+
+/// Whether any check failed hard
+pub fn has_failures(checks: &[Check]) -> bool {
+    checks.iter().any(|c| c.status == Status::Fail)
+}
+
+/// Print one line per check, with a hint under anything that didn't pass
+pub fn write_report(out: &mut dyn Write, checks: &[Check]) -> io::Result<()> {
+    let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for check in checks {
+        let mark = match check.status {
+            Status::Pass => "✓".green(),
+            Status::Warn => "!".yellow(),
+            Status::Fail => "✗".red(),
+        };
+        writeln!(out, "  {} {:<w$}  {}", mark, check.name, check.detail, w = width)?;
+        if let Some(hint) = &check.hint {
+            writeln!(out, "    {} {}", "hint:".dimmed(), hint)?;
+        }
+    }
+    Ok(())
+}
+
+/// The config file is optional, but one that fails to parse is silently ignored by searches
+fn check_config(path: &Path) -> Check {
+    if !path.exists() {
+        return Check::warn(
+            "config",
+            format!("no config file at {}", path.display()),
+            "Optional; run `log-hound config init` to create one with presets",
+        );
+    }
+
+    match Config::load_from(path) {
+        Ok(config) => Check::pass(
+            "config",
+            format!(
+                "{} ({} presets, {} accounts)",
+                path.display(),
+                config.presets.len(),
+                config.accounts.len()
+            ),
+        ),
+        Err(e) => Check::fail("config", format!("{:#}", e), "Fix the file, or see `log-hound config init` for a sample"),
+    }
+}
+
+/// Credentials, then a cheap CloudWatch call in the configured region
+async fn check_aws(profile: Option<&str>, region: Option<&str>, endpoint_url: Option<&str>) -> Vec<Check> {
+    let sdk_config = aws::load_sdk_config(profile, region, endpoint_url).await;
+    let profile_name = profile.unwrap_or("default");
+
+    let credentials = match aws::check_credentials(&sdk_config, profile).await {
+        Ok(credentials) => credentials,
+        Err(e) => {
+            return vec![Check::fail(
+                "credentials",
+                format!("{:#}", e),
+                "Check --profile / AWS_PROFILE, or refresh your login (e.g. `aws sso login`)",
+            )];
+        }
+    };
+
+    let account = credentials
+        .account_id()
+        .map(|id| format!("account {}", id.as_str()))
+        .unwrap_or_else(|| "account unknown".to_string());
+    let Some(region_name) = sdk_config.region().map(|r| r.to_string()) else {
+        return vec![
+            Check::pass("credentials", format!("profile {}, {}", profile_name, account)),
+            Check::fail(
+                "cloudwatch",
+                "no region configured".to_string(),
+                "Pass --region, set AWS_REGION, or add default_region to the config file",
+            ),
+        ];
+    };
+
+    let credentials_check = Check::pass(
+        "credentials",
+        format!("profile {}, {}, region {}", profile_name, account, region_name),
+    );
+
+    let client = aws_sdk_cloudwatchlogs::Client::new(&sdk_config);
+    let request = client.describe_log_groups().limit(1).send();
+    let cloudwatch_check = match tokio::time::timeout(CONNECT_TIMEOUT, request).await {
+        Ok(Ok(_)) => Check::pass("cloudwatch", format!("reachable in {}", region_name)),
+        Ok(Err(e)) => Check::fail(
+            "cloudwatch",
+            aws_sdk_cloudwatchlogs::error::DisplayErrorContext(e).to_string(),
+            "The credentials need logs:DescribeLogGroups; check the region and network access",
+        ),
+        Err(_) => Check::fail(
+            "cloudwatch",
+            format!("no response from {} within {}s", region_name, CONNECT_TIMEOUT.as_secs()),
+            "Check network access (proxy, VPN) to the CloudWatch Logs endpoint",
+        ),
+    };
+
+    vec![credentials_check, cloudwatch_check]
+}
+
+fn check_binary(name: &'static str, hint: &str) -> Check {
+    match find_in_path(name) {
+        Some(path) => Check::pass(name, path.display().to_string()),
+        None => Check::warn(name, "not found on PATH".to_string(), hint),
+    }
+}
+
+fn find_in_path(binary: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(binary))
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_config() {
+        let dir = std::env::temp_dir().join(format!("log-hound-doctor-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let missing = check_config(&dir.join("missing.toml"));
+        assert_eq!(missing.status, Status::Warn);
+
+        let valid = dir.join("valid.toml");
+        std::fs::write(&valid, "[presets.prod]\ngroups = [\"app/prod\"]\n").unwrap();
+        let check = check_config(&valid);
+        assert_eq!(check.status, Status::Pass);
+        assert!(check.detail.contains("1 presets"));
+
+        let invalid = dir.join("invalid.toml");
+        std::fs::write(&invalid, "[presets.prod\n").unwrap();
+        let check = check_config(&invalid);
+        assert_eq!(check.status, Status::Fail);
+        assert!(has_failures(&[check]));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_check_binary_and_report() {
+        let missing = check_binary("log-hound-no-such-binary", "install it");
+        assert_eq!(missing.status, Status::Warn);
+        assert!(!has_failures(std::slice::from_ref(&missing)));
+
+        let mut out = Vec::new();
+        write_report(&mut out, &[Check::pass("config", "ok".to_string()), missing]).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("log-hound-no-such-binary  not found on PATH"));
+        assert!(text.contains("hint: install it"));
+    }
+}
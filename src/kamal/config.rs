@@ -1,13 +1,90 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use serde::Deserialize;
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Directories (relative to the project root) that may hold deploy*.yml, in lookup order:
+/// Kamal's `config/`, the newer `.kamal/` layout, and the root itself
+const DEPLOY_DIRS: &[&str] = &["config", ".kamal", "."];
+
+/// Deploy file used when none is given or found
+pub const DEFAULT_DEPLOY_FILE: &str = "config/deploy.yml";
+
+/// Find deploy*.yml files under a project root, as paths relative to it
+pub fn find_deploy_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for dir in DEPLOY_DIRS {
+        let Ok(entries) = std::fs::read_dir(root.join(dir)) else {
+            continue;
+        };
+        let mut found: Vec<PathBuf> = entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| name.starts_with("deploy") && name.ends_with(".yml"))
+            .map(|name| if *dir == "." { PathBuf::from(name) } else { Path::new(dir).join(name) })
+            .collect();
+        found.sort();
+        files.extend(found);
+    }
+    files
+}
+
+/// The base deploy.yml of a project: the first one found in the deploy directories
+pub fn default_deploy_file(root: &Path) -> PathBuf {
+    DEPLOY_DIRS
+        .iter()
+        .map(|dir| Path::new(dir).join("deploy.yml"))
+        .find(|candidate| root.join(candidate).is_file())
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_DEPLOY_FILE))
+}
+
+/// Deploy file for a Kamal destination (`--env production` → deploy.production.yml), like `kamal -d`
+/// Looked up in the deploy directories in order; the base deploy.yml is merged in when it's loaded
+pub fn destination_deploy_file(root: &Path, destination: &str) -> Result<PathBuf> {
+    if destination.is_empty() || !destination.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+        bail!("Invalid --env '{}': expected a destination name such as production", destination);
+    }
+
+    let file_name = format!("deploy.{}.yml", destination);
+    let candidates: Vec<PathBuf> = DEPLOY_DIRS
+        .iter()
+        .map(|dir| if *dir == "." { PathBuf::from(&file_name) } else { Path::new(dir).join(&file_name) })
+        .collect();
+    if let Some(found) = candidates.iter().find(|candidate| root.join(candidate).is_file()) {
+        return Ok(found.clone());
+    }
+
+    let list = |paths: &[PathBuf]| paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ");
+    let detected = find_deploy_files(root);
+    bail!(
+        "No deploy file for --env {} (looked for {}). Deploy files found: {}",
+        destination,
+        list(&candidates),
+        if detected.is_empty() { "none".to_string() } else { list(&detected) }
+    )
+}
+
+/// Base deploy.yml for a destination file: next to it first, then in the project's other deploy directories
+fn find_base_file(path: &Path) -> Option<PathBuf> {
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let in_deploy_dir = dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| DEPLOY_DIRS.contains(&name));
+    let root = if in_deploy_dir { dir.parent().unwrap_or(Path::new("")) } else { dir };
+
+    std::iter::once(dir.join("deploy.yml"))
+        .chain(DEPLOY_DIRS.iter().map(|d| root.join(d).join("deploy.yml")))
+        .find(|candidate| candidate.is_file())
+}
 
 /// Represents a parsed Kamal deploy.yml configuration
 #[derive(Debug, Clone)]
 pub struct KamalConfig {
     pub service: String,
     pub servers: Vec<String>,
+    /// Role of each server in a role-based config ("web" first when a host has several); empty for a plain list
+    pub roles: HashMap<String, String>,
     pub ssh_user: String,
     /// Destination name extracted from deploy.{destination}.yml filename
     pub destination: Option<String>,
@@ -75,12 +152,8 @@ impl KamalConfig {
         };
 
         let merged = if needs_base {
-            // Try to load base deploy.yml from same directory
-            let base_path = path.parent()
-                .map(|p| p.join("deploy.yml"))
-                .unwrap_or_else(|| Path::new("config/deploy.yml").to_path_buf());
-
-            if base_path.exists() {
+            // Try to load base deploy.yml from the same directory, then config/, .kamal/ or the root
+            if let Some(base_path) = find_base_file(path) {
                 let base_contents = std::fs::read_to_string(&base_path)
                     .with_context(|| format!("Failed to read base config: {:?}", base_path))?;
                 let base_config: KamalYaml = serde_yaml::from_str(&base_contents)
@@ -110,17 +183,25 @@ impl KamalConfig {
         let servers_config = raw.servers
             .ok_or_else(|| anyhow!("Missing 'servers' in Kamal config"))?;
 
-        // Extract all servers from the config
+        // Extract all servers from the config, remembering each host's role
+        let mut server_roles = HashMap::new();
+        let mut record_role = |role: &str, hosts: &[String]| {
+            for host in hosts {
+                server_roles.entry(host.clone()).or_insert_with(|| role.to_string());
+            }
+        };
         let servers = match servers_config {
             ServersConfig::Simple(hosts) => hosts,
             ServersConfig::RoleBasedSimple(roles) => {
                 // Kamal 2.x: Flatten all roles into a single list, prioritizing 'web' role
                 let mut all_servers = Vec::new();
                 if let Some(web_servers) = roles.get("web") {
+                    record_role("web", web_servers);
                     all_servers.extend(web_servers.clone());
                 }
                 for (role, hosts) in roles {
                     if role != "web" {
+                        record_role(&role, &hosts);
                         all_servers.extend(hosts);
                     }
                 }
@@ -130,10 +211,12 @@ impl KamalConfig {
                 // Kamal 1.x: Extract hosts from role configs
                 let mut all_servers = Vec::new();
                 if let Some(web_config) = roles.get("web") {
+                    record_role("web", &web_config.hosts);
                     all_servers.extend(web_config.hosts.clone());
                 }
                 for (role, config) in roles {
                     if role != "web" {
+                        record_role(&role, &config.hosts);
                         all_servers.extend(config.hosts.clone());
                     }
                 }
@@ -158,23 +241,33 @@ impl KamalConfig {
         Ok(KamalConfig {
             service,
             servers,
+            roles: server_roles,
             ssh_user,
             destination,
         })
     }
 
+    /// Role whose container runs on a server ("web" unless the config says otherwise)
+    pub fn role(&self, server: &str) -> &str {
+        self.roles.get(server).map(|r| r.as_str()).unwrap_or("web")
+    }
+
+    /// Log group label for entries from a server: "kamal:{server}", plus ":{role}" for role-based configs
+    pub fn group_label(&self, server: &str) -> String {
+        match self.roles.get(server) {
+            Some(role) => format!("kamal:{}:{}", server, role),
+            None => format!("kamal:{}", server),
+        }
+    }
+
     /// Parse Kamal configuration from YAML string (for testing)
+    #[cfg(test)]
     pub fn parse(yaml: &str) -> Result<Self> {
         let raw: KamalYaml = serde_yaml::from_str(yaml)
             .context("Failed to parse Kamal YAML")?;
         Self::from_yaml(raw, None)
     }
 
-    /// Get the container name pattern for docker logs
-    /// Kamal names containers as: {service}-{role}-{hash}
-    pub fn container_pattern(&self) -> String {
-        format!("{}*", self.service)
-    }
 }
 
 #[cfg(test)]
@@ -193,6 +286,8 @@ servers:
         assert_eq!(config.service, "my-app");
         assert_eq!(config.servers, vec!["host1.example.com", "host2.example.com"]);
         assert_eq!(config.ssh_user, "root");
+        assert_eq!(config.role("host1.example.com"), "web");
+        assert_eq!(config.group_label("host1.example.com"), "kamal:host1.example.com");
     }
 
     #[test]
@@ -212,6 +307,67 @@ ssh:
         assert!(config.servers.contains(&"web1.example.com".to_string()));
         assert!(config.servers.contains(&"job1.example.com".to_string()));
         assert_eq!(config.ssh_user, "deploy");
+        assert_eq!(config.role("job1.example.com"), "job");
+        assert_eq!(config.group_label("job1.example.com"), "kamal:job1.example.com:job");
+        assert_eq!(config.group_label("web1.example.com"), "kamal:web1.example.com:web");
+    }
+
+    #[test]
+    fn test_kamal_dir_layout_is_detected_and_loaded() {
+        let root = std::env::temp_dir().join(format!("log-hound-kamal-layout-{}", std::process::id()));
+        std::fs::create_dir_all(root.join(".kamal")).unwrap();
+        std::fs::write(root.join(".kamal/deploy.yml"), "service: my-app\nservers:\n  - base.example.com\n").unwrap();
+        std::fs::write(root.join(".kamal/deploy.staging.yml"), "servers:\n  - staging.example.com\n").unwrap();
+        std::fs::write(root.join(".kamal/secrets"), "KAMAL_REGISTRY_PASSWORD=x\n").unwrap();
+
+        assert_eq!(
+            find_deploy_files(&root),
+            vec![PathBuf::from(".kamal/deploy.staging.yml"), PathBuf::from(".kamal/deploy.yml")]
+        );
+        assert_eq!(default_deploy_file(&root), PathBuf::from(".kamal/deploy.yml"));
+
+        // The destination file takes service from the base file next to it
+        let config = KamalConfig::load(root.join(".kamal/deploy.staging.yml")).unwrap();
+        assert_eq!(config.service, "my-app");
+        assert_eq!(config.servers, vec!["staging.example.com"]);
+        assert_eq!(config.destination.as_deref(), Some("staging"));
+
+        // A base in config/ is found for a destination file at the root
+        std::fs::create_dir_all(root.join("config")).unwrap();
+        std::fs::rename(root.join(".kamal/deploy.yml"), root.join("config/deploy.yml")).unwrap();
+        std::fs::write(root.join("deploy.prod.yml"), "servers:\n  - prod.example.com\n").unwrap();
+        assert_eq!(KamalConfig::load(root.join("deploy.prod.yml")).unwrap().service, "my-app");
+        assert_eq!(KamalConfig::load(root.join(".kamal/deploy.staging.yml")).unwrap().service, "my-app");
+        assert_eq!(default_deploy_file(&root), PathBuf::from("config/deploy.yml"));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_destination_deploy_file() {
+        let root = std::env::temp_dir().join(format!("log-hound-kamal-env-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("config")).unwrap();
+        std::fs::write(root.join("config/deploy.yml"), "service: my-app\nssh:\n  user: deploy\nservers:\n  - base.example.com\n").unwrap();
+        std::fs::write(root.join("config/deploy.production.yml"), "servers:\n  - prod.example.com\n").unwrap();
+
+        let path = destination_deploy_file(&root, "production").unwrap();
+        assert_eq!(path, PathBuf::from("config/deploy.production.yml"));
+
+        // The destination's servers over the base's service and ssh user
+        let config = KamalConfig::load(root.join(&path)).unwrap();
+        assert_eq!(config.service, "my-app");
+        assert_eq!(config.ssh_user, "deploy");
+        assert_eq!(config.servers, vec!["prod.example.com"]);
+        assert_eq!(config.destination.as_deref(), Some("production"));
+
+        // An unknown destination lists what exists
+        let err = destination_deploy_file(&root, "staging").unwrap_err().to_string();
+        assert!(err.contains("config/deploy.staging.yml"), "{}", err);
+        assert!(err.contains("Deploy files found: config/deploy.production.yml, config/deploy.yml"), "{}", err);
+
+        assert!(destination_deploy_file(&root, "../production").is_err());
+
+        let _ = std::fs::remove_dir_all(root);
     }
 
     #[test]
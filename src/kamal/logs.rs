@@ -1,15 +1,47 @@
-use crate::aws::LogEntry;
+use crate::aws::{keep_window, LogEntry};
+use crate::cli::{MatchField, DEFAULT_LIMIT};
+use crate::config::DEFAULT_TAIL_MULTIPLIER;
+use crate::deadline::{self, Deadline};
 use crate::kamal::KamalConfig;
 use crate::output;
-use anyhow::{anyhow, Context, Result};
+use crate::progress::{FollowStatus, IdleTimer};
+use anyhow::{anyhow, bail, Context, Result};
 use chrono::{DateTime, NaiveDateTime, Utc};
+use futures::stream::{FuturesUnordered, Stream};
 use openssh::{KnownHosts, Session, SessionBuilder};
 use std::path::Path;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::Duration;
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::sync::mpsc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Child;
+use tokio::sync::{mpsc, watch};
+
+/// Connection attempts per server before giving up on transient SSH failures
+const SSH_CONNECT_ATTEMPTS: u32 = 3;
+
+/// Wait before retrying an SSH connection, multiplied by the attempt number
+const SSH_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Whether an SSH connection error may go away on retry
+/// Timeouts and refused/dropped connections are retried; auth, host key and DNS failures are not
+fn is_retryable_ssh_error(error: &openssh::Error) -> bool {
+    use std::io::ErrorKind;
+
+    match error {
+        openssh::Error::Connect(e) => {
+            let message = e.to_string();
+            match e.kind() {
+                ErrorKind::PermissionDenied => false,
+                _ if message.contains("Could not resolve") || message.contains("Host key verification failed") => false,
+                ErrorKind::TimedOut | ErrorKind::ConnectionRefused | ErrorKind::ConnectionAborted | ErrorKind::Other => true,
+                _ => false,
+            }
+        }
+        openssh::Error::Master(_) | openssh::Error::Disconnected => true,
+        _ => false,
+    }
+}
 
 /// Search parameters for Kamal logs
 #[derive(Debug, Clone)]
@@ -18,44 +50,261 @@ pub struct KamalSearchParams {
     pub exclude: Vec<String>,
     pub limit: usize,
     pub since: Option<String>,
-    pub follow: bool,
+    /// Keep the oldest `limit` entries in the window instead of the newest
+    pub oldest: bool,
+    /// Match patterns and excludes as whole words only
+    pub word: bool,
+    /// Select lines that don't contain the patterns (excludes still apply)
+    pub invert_match: bool,
+    /// Look for patterns in the message, the service and server, or either
+    pub match_field: MatchField,
+    /// Only lines starting with this (case-insensitive)
+    pub starts_with: Option<String>,
+    /// Only lines ending with this (case-insensitive)
+    pub ends_with: Option<String>,
+    /// When following, also emit this many lines after each match (excludes still apply)
+    pub follow_context: usize,
+    /// When following, stop once no lines have been emitted for this long
+    pub follow_idle_timeout: Option<Duration>,
+    /// Give up on servers still being searched when this passes (--timeout)
+    pub deadline: Option<Deadline>,
+    /// `docker logs --tail` fetches `limit` times this many lines, to leave room for filtering
+    pub tail_multiplier: usize,
+}
+
+/// The CLI's defaults, so callers only set what they change
+impl Default for KamalSearchParams {
+    fn default() -> Self {
+        Self {
+            patterns: Vec::new(),
+            exclude: Vec::new(),
+            limit: DEFAULT_LIMIT as usize,
+            since: None,
+            oldest: false,
+            word: false,
+            invert_match: false,
+            match_field: MatchField::default(),
+            starts_with: None,
+            ends_with: None,
+            follow_context: 0,
+            follow_idle_timeout: None,
+            deadline: None,
+            tail_multiplier: DEFAULT_TAIL_MULTIPLIER,
+        }
+    }
+}
+
+impl KamalSearchParams {
+    /// Whether a message contains all patterns (or, with invert_match, not all of them)
+    /// and none of the excludes (case-insensitive)
+    /// `source` names where the line came from (service and server), for --match-field stream/any
+    pub fn matches(&self, message: &str, source: &str) -> bool {
+        let message = message.to_lowercase();
+        self.selects(&message, &source.to_lowercase()) && !self.excludes(&message)
+    }
+
+    /// Whether a lowercased message is selected by the patterns, honoring invert_match,
+    /// and has the required prefix/suffix
+    fn selects(&self, message: &str, source: &str) -> bool {
+        let includes = self.patterns.iter().all(|p| self.found(message, source, p));
+        let selected = if self.invert_match && !self.patterns.is_empty() {
+            !includes
+        } else {
+            includes
+        };
+        selected && self.anchored(message)
+    }
+
+    /// Whether a lowercased message starts/ends with --starts-with/--ends-with (as a whole word with --word)
+    /// Trailing whitespace (the `\r` of ssh -tt output) is ignored for the suffix
+    fn anchored(&self, message: &str) -> bool {
+        let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let starts = self.starts_with.as_ref().is_none_or(|p| {
+            let p = p.to_lowercase();
+            message.starts_with(&p) && !(self.word && is_word(message[p.len()..].chars().next()))
+        });
+        let ends = self.ends_with.as_ref().is_none_or(|p| {
+            let (message, p) = (message.trim_end(), p.to_lowercase());
+            message.ends_with(&p) && !(self.word && is_word(message[..message.len() - p.len()].chars().next_back()))
+        });
+        starts && ends
+    }
+
+    /// Whether a pattern is in the field(s) --match-field looks at
+    fn found(&self, message: &str, source: &str, pattern: &str) -> bool {
+        match self.match_field {
+            MatchField::Message => self.contains(message, pattern),
+            MatchField::Stream => self.contains(source, pattern),
+            MatchField::Any => self.contains(message, pattern) || self.contains(source, pattern),
+        }
+    }
+
+    /// Whether a lowercased message contains any exclude
+    fn excludes(&self, message: &str) -> bool {
+        self.exclude.iter().any(|p| self.contains(message, p))
+    }
+
+    fn contains(&self, message: &str, pattern: &str) -> bool {
+        let pattern = pattern.to_lowercase();
+        if self.word {
+            contains_word(message, &pattern)
+        } else {
+            message.contains(&pattern)
+        }
+    }
+}
+
+/// How a follow ended
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Followed {
+    /// Lines emitted
+    pub lines: usize,
+    /// Stopped because nothing was emitted within `follow_idle_timeout`
+    pub idle: bool,
+}
+
+/// Decides which followed lines are emitted: every match, plus the next `follow_context` lines
+/// after it whether they match or not. Excluded lines are never emitted but still use up the context.
+#[derive(Debug, Default)]
+struct FollowContext {
+    remaining: usize,
+}
+
+impl FollowContext {
+    fn admit(&mut self, params: &KamalSearchParams, message: &str, source: &str) -> bool {
+        let message = message.to_lowercase();
+
+        if params.selects(&message, &source.to_lowercase()) {
+            // A match starts a fresh context window, even when the match itself is excluded
+            self.remaining = params.follow_context;
+            return !params.excludes(&message);
+        }
+
+        if self.remaining == 0 {
+            return false;
+        }
+        self.remaining -= 1;
+        !params.excludes(&message)
+    }
+}
+
+/// What --match-field stream looks at for a Kamal entry: its server label and service
+fn match_source(entry: &LogEntry) -> String {
+    format!("{} {}", entry.log_group, entry.log_stream.as_deref().unwrap_or_default())
+}
+
+/// Whether `needle` occurs in `haystack` with no word character directly before or after it
+fn contains_word(haystack: &str, needle: &str) -> bool {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    haystack.match_indices(needle).any(|(start, matched)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + matched.len()..].chars().next();
+        !before.is_some_and(is_word) && !after.is_some_and(is_word)
+    })
+}
+
+/// What was read from the output of one `docker logs`
+struct Collected {
+    entries: Vec<LogEntry>,
+    /// --oldest reached its limit before the output ended
+    filled: bool,
+    /// The last line written to stderr, which holds docker's error when it fails
+    last_stderr_line: String,
 }
 
 /// Searcher for Kamal-deployed Docker container logs
 pub struct KamalSearcher {
     config: KamalConfig,
+    /// Docker name filter (a regex) used instead of Kamal's `{service}-{role}-` naming
+    container_pattern: Option<String>,
+    /// Container ID (or name) used as is, skipping the `docker ps` lookup
+    container_id: Option<String>,
+    /// Search only the primary (first) server (--primary-only)
+    primary_only: bool,
 }
 
 impl KamalSearcher {
     /// Create a new KamalSearcher from a deploy.yml path
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let config = KamalConfig::load(path)?;
-        Ok(Self { config })
+        Ok(Self { config, container_pattern: None, container_id: None, primary_only: false })
     }
 
-    /// Create a new KamalSearcher from an existing config
-    pub fn new(config: KamalConfig) -> Self {
-        Self { config }
+    /// Match containers by this name regex instead of Kamal's naming (--container-pattern)
+    pub fn with_container_pattern(mut self, pattern: Option<String>) -> Self {
+        self.container_pattern = pattern;
+        self
+    }
+
+    /// Read logs from this container instead of looking one up (--container)
+    /// It may have exited, which allows post-mortem searches; only the first server is searched
+    pub fn with_container_id(mut self, id: Option<String>) -> Result<Self> {
+        if let Some(id) = &id {
+            validate_container_id(id)?;
+        }
+        self.container_id = id;
+        Ok(self)
+    }
+
+    /// Search only the primary (first) server, as following does, for a quick single-host check
+    pub fn with_primary_only(mut self, primary_only: bool) -> Self {
+        self.primary_only = primary_only;
+        self
+    }
+
+    /// Leave out servers (--exclude-server); fails if that leaves none to search
+    pub fn without_servers(mut self, excluded: &[String]) -> Result<Self> {
+        if excluded.is_empty() {
+            return Ok(self);
+        }
+
+        self.config.servers.retain(|server| !excluded.contains(server));
+        if self.config.servers.is_empty() {
+            bail!("All servers are excluded by --exclude-server");
+        }
+        Ok(self)
     }
 
-    /// Search logs from all configured servers
+    /// Search logs from all configured servers, or only the primary one (see `searched_servers`)
     pub async fn search_logs(
         &self,
         params: &KamalSearchParams,
     ) -> Vec<Result<Vec<LogEntry>>> {
         let futures: Vec<_> = self
-            .config
-            .servers
+            .searched_servers()
             .iter()
-            .map(|server| self.search_server_logs(server, params))
+            .map(|server| deadline::within(params.deadline, self.search_server_logs(server, params)))
             .collect();
 
         futures::future::join_all(futures).await
     }
 
+    /// Search all servers like `search_logs`, but yield each server's results (with its index in
+    /// `searched_servers`) as soon as that server finishes, for output that streams
+    pub fn search_logs_as_completed<'a>(
+        &'a self,
+        params: &'a KamalSearchParams,
+    ) -> impl Stream<Item = (usize, Result<Vec<LogEntry>>)> + 'a {
+        self.searched_servers()
+            .iter()
+            .enumerate()
+            .map(|(index, server)| async move {
+                (index, deadline::within(params.deadline, self.search_server_logs(server, params)).await)
+            })
+            .collect::<FuturesUnordered<_>>()
+    }
+
     /// Follow logs from the primary server (first in list)
-    /// Streams logs in real-time until interrupted
-    pub async fn follow_logs(&self, params: &KamalSearchParams) -> Result<()> {
+    /// Streams logs in real-time until the stream ends or the stop_flag is set, then closes the ssh process
+    /// Also stops after `follow_idle_timeout` without lines; `show_status` keeps a line-rate status under the output
+    pub async fn follow_logs(
+        &self,
+        params: &KamalSearchParams,
+        display_options: &output::DisplayOptions,
+        stop_flag: Arc<AtomicBool>,
+        show_status: bool,
+    ) -> Result<Followed> {
         use std::process::Stdio;
         use tokio::process::Command;
 
@@ -63,54 +312,40 @@ impl KamalSearcher {
             .ok_or_else(|| anyhow!("No servers configured"))?;
 
         // First, get the container ID via SSH
-        let session = self.connect_ssh(server).await?;
-        let container_id = self.find_container(&session).await?;
-        session.close().await?;
+        let container_id = self.follow_container(server).await?;
+        let docker_cmd = docker_logs_command(&container_id, params, true);
 
-        // Build docker logs -f command
-        let mut docker_cmd = format!("docker logs {} --timestamps -f", container_id);
-        if let Some(since) = &params.since {
-            docker_cmd.push_str(&format!(" --since {}", since));
-        }
-
-        if std::env::var("LOG_HOUND_DEBUG").is_ok() {
-            eprintln!("DEBUG: SSH command: ssh {}@{} {}", self.config.ssh_user, server, docker_cmd);
-        }
+        tracing::debug!(user = %self.config.ssh_user, %server, "ssh command: {}", docker_cmd);
 
         // Use tokio::process::Command with ssh directly for streaming
         let destination = format!("{}@{}", self.config.ssh_user, server);
-        let mut child = Command::new("ssh")
+        let mut command = Command::new("ssh");
+        command
             .arg("-tt")  // Force pseudo-terminal allocation for proper streaming
             .arg(&destination)
             .arg(&docker_cmd)
+            .stdin(Stdio::null())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .context("Failed to spawn ssh process")?;
+            .stderr(Stdio::piped());
 
-        // With -tt, docker logs output goes to stdout via the pseudo-terminal
-        let stdout = child.stdout.take()
-            .ok_or_else(|| anyhow!("Failed to capture stdout"))?;
-
-        let mut reader = BufReader::new(stdout).lines();
+        // Keep Ctrl+C away from ssh so we can stop following and close it ourselves
+        #[cfg(unix)]
+        command.process_group(0);
 
-        while let Ok(Some(line)) = reader.next_line().await {
-            if let Some(entry) = self.parse_log_line(&line, server) {
-                // Apply filters
-                let matches_include = params.patterns.is_empty()
-                    || params.patterns.iter()
-                        .all(|p| entry.message.to_lowercase().contains(&p.to_lowercase()));
+        let mut child = command.spawn().context("Failed to spawn ssh process")?;
 
-                let matches_exclude = params.exclude.iter()
-                    .any(|p| entry.message.to_lowercase().contains(&p.to_lowercase()));
-
-                if matches_include && !matches_exclude {
-                    output::print_entry(&entry);
-                }
+        let log_group = self.config.group_label(server);
+        let mut status = FollowStatus::new(show_status);
+        let followed = follow_child(&mut child, &log_group, &self.config.service, params, stop_flag, &mut status, |entry| {
+            let shown = display_options.shows(entry);
+            if shown {
+                output::print_entry(entry, display_options);
             }
-        }
-
-        Ok(())
+            shown
+        })
+        .await;
+        status.clear();
+        followed
     }
 
     /// Follow logs and send entries through a channel (for TUI integration)
@@ -129,15 +364,8 @@ impl KamalSearcher {
             .ok_or_else(|| anyhow!("No servers configured"))?;
 
         // First, get the container ID via SSH
-        let session = self.connect_ssh(server).await?;
-        let container_id = self.find_container(&session).await?;
-        session.close().await?;
-
-        // Build docker logs -f command
-        let mut docker_cmd = format!("docker logs {} --timestamps -f", container_id);
-        if let Some(since) = &params.since {
-            docker_cmd.push_str(&format!(" --since {}", since));
-        }
+        let container_id = self.follow_container(server).await?;
+        let docker_cmd = docker_logs_command(&container_id, params, true);
 
         // Use tokio::process::Command with ssh directly for streaming
         let destination = format!("{}@{}", self.config.ssh_user, server);
@@ -154,31 +382,21 @@ impl KamalSearcher {
             .ok_or_else(|| anyhow!("Failed to capture stdout"))?;
 
         let mut reader = BufReader::new(stdout).lines();
-        let server = server.to_string();
-        let patterns = params.patterns.clone();
-        let exclude = params.exclude.clone();
+        let log_group = self.config.group_label(server);
+        let params = params.clone();
         let service = self.config.service.clone();
 
         // Spawn task to read lines and send through channel
         tokio::spawn(async move {
+            let mut context = FollowContext::default();
             while !stop_flag.load(Ordering::Relaxed) {
                 tokio::select! {
                     line_result = reader.next_line() => {
                         match line_result {
                             Ok(Some(line)) => {
-                                if let Some(entry) = parse_log_line_static(&line, &server, &service) {
-                                    // Apply filters
-                                    let matches_include = patterns.is_empty()
-                                        || patterns.iter()
-                                            .all(|p| entry.message.to_lowercase().contains(&p.to_lowercase()));
-
-                                    let matches_exclude = exclude.iter()
-                                        .any(|p| entry.message.to_lowercase().contains(&p.to_lowercase()));
-
-                                    if matches_include && !matches_exclude {
-                                        if sender.send(entry).await.is_err() {
-                                            break; // Receiver dropped
-                                        }
+                                if let Some(entry) = parse_log_line_static(&line, &log_group, &service) {
+                                    if context.admit(&params, &entry.message, &match_source(&entry)) && sender.send(entry).await.is_err() {
+                                        break; // Receiver dropped
                                     }
                                 }
                             }
@@ -210,49 +428,97 @@ impl KamalSearcher {
         // Connect via SSH
         let session = self.connect_ssh(server).await?;
 
-        // Find the running container
-        let container_id = self.find_container(&session).await?;
-
-        // Fetch docker logs
-        let raw_logs = self.fetch_docker_logs(&session, &container_id, params).await?;
+        // Find the running container, unless one was given
+        let container_id = match &self.container_id {
+            Some(id) => id.clone(),
+            None => self.find_container(&session, server).await?,
+        };
 
-        // Parse logs into LogEntry format
-        let entries = self.parse_logs(&raw_logs, server, params)?;
+        // Fetch, parse and filter docker logs
+        let mut entries = self.fetch_docker_logs(&session, &container_id, server, params).await?;
 
         session.close().await?;
 
+        keep_window(&mut entries, params.limit, params.oldest);
+
         Ok(entries)
     }
 
+    /// When the container on the primary server (first in list) started: its last deploy, or
+    /// restart (--since-deploy)
+    pub async fn deployed_at(&self) -> Result<DateTime<Utc>> {
+        let server = self.config.servers.first().ok_or_else(|| anyhow!("No servers configured"))?;
+        let session = self.connect_ssh(server).await?;
+        let container_id = match &self.container_id {
+            Some(id) => id.clone(),
+            None => self.find_container(&session, server).await?,
+        };
+
+        let output = session
+            .command("bash")
+            .arg("-c")
+            .arg(docker_started_at_command(&container_id))
+            .output()
+            .await
+            .context("Failed to execute docker inspect")?;
+        session.close().await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("docker inspect failed: {}", stderr));
+        }
+        parse_started_at(&String::from_utf8_lossy(&output.stdout))
+    }
+
     /// Establish SSH connection to a server
+    /// Transient failures (timeouts, refused/dropped connections) are retried with backoff
+    #[tracing::instrument(name = "ssh_connect", level = "debug", skip_all, fields(%server))]
     async fn connect_ssh(&self, server: &str) -> Result<Session> {
         let destination = format!("{}@{}", self.config.ssh_user, server);
+        let mut attempt = 1;
+
+        loop {
+            let result = SessionBuilder::default()
+                .known_hosts_check(KnownHosts::Accept)
+                .connect_timeout(Duration::from_secs(10))
+                .connect(&destination)
+                .await;
+
+            match result {
+                Ok(session) => return Ok(session),
+                Err(e) if attempt < SSH_CONNECT_ATTEMPTS && is_retryable_ssh_error(&e) => {
+                    let backoff = SSH_RETRY_BACKOFF * attempt;
+                    tracing::debug!(
+                        attempt,
+                        ?backoff,
+                        "failed, retrying (of {}): {:#}",
+                        SSH_CONNECT_ATTEMPTS,
+                        anyhow::Error::from(e)
+                    );
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e).with_context(|| format!("Failed to SSH to {}", destination)),
+            }
+        }
+    }
 
-        let session = SessionBuilder::default()
-            .known_hosts_check(KnownHosts::Accept)
-            .connect_timeout(Duration::from_secs(10))
-            .connect(&destination)
-            .await
-            .with_context(|| format!("Failed to SSH to {}", destination))?;
+    /// Container to follow on a server: the given one, or the running one looked up over SSH
+    async fn follow_container(&self, server: &str) -> Result<String> {
+        if let Some(id) = &self.container_id {
+            return Ok(id.clone());
+        }
 
-        Ok(session)
+        let session = self.connect_ssh(server).await?;
+        let container_id = self.find_container(&session, server).await?;
+        session.close().await?;
+        Ok(container_id)
     }
 
     /// Find the running container ID for the service
-    async fn find_container(&self, session: &Session) -> Result<String> {
-        // Build filter pattern: service-web-{destination} if destination exists
-        // Kamal names containers as: {service}-{role}-{destination}-{hash}
-        let filter_pattern = if let Some(dest) = &self.config.destination {
-            format!("{}-web-{}", self.config.service, dest)
-        } else {
-            format!("{}-web", self.config.service)
-        };
-
-        // Find containers matching the service name pattern
-        let cmd = format!(
-            "docker ps --filter 'name={}' --format '{{{{.ID}}}}' | head -1",
-            filter_pattern
-        );
+    async fn find_container(&self, session: &Session, server: &str) -> Result<String> {
+        let filter_pattern = self.container_filter(server);
+        let cmd = docker_ps_command(&filter_pattern);
 
         let output = session
             .command("bash")
@@ -267,101 +533,156 @@ impl KamalSearcher {
             return Err(anyhow!("docker ps failed: {}", stderr));
         }
 
-        let container_id = String::from_utf8_lossy(&output.stdout)
-            .trim()
-            .to_string();
+        let service_desc = if let Some(dest) = &self.config.destination {
+            format!("{} (destination: {})", self.config.service, dest)
+        } else {
+            self.config.service.clone()
+        };
+        pick_container(&String::from_utf8_lossy(&output.stdout), &service_desc, &filter_pattern)
+    }
 
-        if container_id.is_empty() {
-            let service_desc = if let Some(dest) = &self.config.destination {
-                format!("{} (destination: {})", self.config.service, dest)
-            } else {
-                self.config.service.clone()
-            };
-            return Err(anyhow!(
-                "No running container found for service: {} (filter: {})",
-                service_desc,
-                filter_pattern
-            ));
+    /// Docker name filter for a server's container
+    /// Kamal names containers as: {service}-{role}-{destination}-{hash}, so the filter is anchored
+    /// to that prefix and "app" doesn't also match "app-worker-..."
+    fn container_filter(&self, server: &str) -> String {
+        if let Some(pattern) = &self.container_pattern {
+            return pattern.clone();
         }
 
-        Ok(container_id)
+        let role = self.config.role(server);
+        let prefix = match &self.config.destination {
+            Some(dest) => format!("{}-{}-{}-", self.config.service, role, dest),
+            None => format!("{}-{}-", self.config.service, role),
+        };
+        // Container names are [a-zA-Z0-9_.-], so '.' is the only regex metacharacter to escape
+        format!("^{}", prefix.replace('.', r"\."))
     }
 
-    /// Fetch docker logs from a container
+    /// Fetch docker logs from a container, keeping only entries that pass the filters
+    /// Lines are parsed as they arrive, so memory is bounded by the matches rather than the raw log size
     async fn fetch_docker_logs(
         &self,
         session: &Session,
         container_id: &str,
+        server: &str,
         params: &KamalSearchParams,
-    ) -> Result<String> {
-        // Build docker logs command
-        let mut cmd = format!("docker logs {} --timestamps", container_id);
-
-        // Add --since if specified
-        if let Some(since) = &params.since {
-            cmd.push_str(&format!(" --since {}", since));
-        }
-
-        // Add tail limit (fetch more than needed for filtering)
-        let fetch_limit = params.limit * 10; // Over-fetch to account for filtering
-        cmd.push_str(&format!(" --tail {}", fetch_limit.max(1000)));
+    ) -> Result<Vec<LogEntry>> {
+        let cmd = docker_logs_command(container_id, params, false);
 
-        // Execute and capture output
-        let output = session
+        let mut child = session
             .command("bash")
             .arg("-c")
             .arg(&cmd)
-            .output()
+            .stdout(openssh::Stdio::piped())
+            .stderr(openssh::Stdio::piped())
+            .spawn()
             .await
             .context("Failed to execute docker logs")?;
 
-        // Docker logs outputs to stderr for non-error logs too
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = child.stdout().take().ok_or_else(|| anyhow!("Failed to capture stdout"))?;
+        let stderr = child.stderr().take().ok_or_else(|| anyhow!("Failed to capture stderr"))?;
 
-        // Combine stdout and stderr (docker logs can output to both)
-        Ok(format!("{}{}", stdout, stderr))
+        let collected = self.collect_streams(stdout, stderr, server, params).await?;
+        if collected.filled {
+            // Closing the streams makes `docker logs` fail its next write and exit, so drop the
+            // connection to it rather than waiting for the rest of the log
+            let _ = child.disconnect().await;
+            return Ok(collected.entries);
+        }
+
+        let status = child.wait().await.context("Failed to execute docker logs")?;
+        if !status.success() {
+            // e.g. a --container that doesn't exist on this host: "No such container: ..."
+            bail!("docker logs {} failed on {} ({}): {}", container_id, server, status, collected.last_stderr_line);
+        }
+
+        Ok(collected.entries)
+    }
+
+    /// Read `docker logs` stdout and stderr concurrently (it writes log lines to both), keeping the
+    /// entries that match the search filters
+    /// With --oldest the output isn't capped by --tail, so reading stops once the two streams have
+    /// `limit` matches between them; the returned flag says so
+    async fn collect_streams<O, E>(&self, stdout: O, stderr: E, server: &str, params: &KamalSearchParams) -> Result<Collected>
+    where
+        O: AsyncRead + Unpin,
+        E: AsyncRead + Unpin,
+    {
+        let kept = AtomicUsize::new(0);
+        let (filled, _) = watch::channel(false);
+        let mut last_stderr_line = String::new();
+
+        let (mut entries, from_stderr) = tokio::try_join!(
+            self.collect_matching(stdout, server, params, &kept, &filled, None),
+            self.collect_matching(stderr, server, params, &kept, &filled, Some(&mut last_stderr_line)),
+        )?;
+        entries.extend(from_stderr);
+        let filled = *filled.borrow();
+        Ok(Collected { entries, filled, last_stderr_line })
     }
 
-    /// Parse raw docker logs into LogEntry format with filtering
-    fn parse_logs(
+    /// Read log lines from a stream, keeping the entries that match the search filters
+    /// `kept` counts --oldest matches across both streams, and `filled` is set once it reaches the limit
+    /// `last_line` receives the last non-empty line read
+    async fn collect_matching<R: AsyncRead + Unpin>(
         &self,
-        raw_logs: &str,
+        reader: R,
         server: &str,
         params: &KamalSearchParams,
+        kept: &AtomicUsize,
+        filled: &watch::Sender<bool>,
+        mut last_line: Option<&mut String>,
     ) -> Result<Vec<LogEntry>> {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
         let mut entries = Vec::new();
+        let mut other_filled = filled.subscribe();
+
+        loop {
+            buf.clear();
+            let read = tokio::select! {
+                read = reader.read_until(b'\n', &mut buf) => read?,
+                // The other stream collected the last match
+                _ = other_filled.wait_for(|filled| *filled) => break,
+            };
+            if read == 0 {
+                break;
+            }
+
+            // Invalid UTF-8 shows as U+FFFD; control characters are made visible when printed
+            let line = String::from_utf8_lossy(&buf);
+            let line = line.trim_end_matches(['\r', '\n']);
 
-        for line in raw_logs.lines() {
             // Skip empty lines
             if line.trim().is_empty() {
                 continue;
             }
+            if let Some(last_line) = last_line.as_deref_mut() {
+                last_line.clear();
+                last_line.push_str(line);
+            }
 
-            // Parse the log line
-            if let Some(entry) = self.parse_log_line(line, server) {
-                // Apply include filters (AND condition)
-                let matches_include = params.patterns.is_empty()
-                    || params
-                        .patterns
-                        .iter()
-                        .all(|p| entry.message.to_lowercase().contains(&p.to_lowercase()));
-
-                // Apply exclude filters
-                let matches_exclude = params
-                    .exclude
-                    .iter()
-                    .any(|p| entry.message.to_lowercase().contains(&p.to_lowercase()));
-
-                if matches_include && !matches_exclude {
-                    entries.push(entry);
-                }
+            let Some(entry) = self.parse_log_line(line, server) else {
+                continue;
+            };
+            if !params.matches(&entry.message, &match_source(&entry)) {
+                continue;
+            }
+            if !params.oldest {
+                entries.push(entry);
+                continue;
             }
-        }
 
-        // Sort by timestamp (newest first) and limit
-        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-        entries.truncate(params.limit);
+            // Without --tail the stream can be long; the first `limit` matches are the oldest
+            let slot = kept.fetch_add(1, Ordering::Relaxed);
+            if slot < params.limit {
+                entries.push(entry);
+            }
+            if slot + 1 >= params.limit {
+                filled.send_replace(true);
+                break;
+            }
+        }
 
         Ok(entries)
     }
@@ -379,9 +700,11 @@ impl KamalSearcher {
             return Some(LogEntry {
                 timestamp: Utc::now(),
                 message: line.to_string(),
-                log_group: format!("kamal:{}", server),
+                log_group: self.config.group_label(server),
                 log_stream: Some(self.config.service.clone()),
                 region: None,
+                also_in: Vec::new(),
+                ptr: None,
             });
         }
 
@@ -394,9 +717,11 @@ impl KamalSearcher {
         Some(LogEntry {
             timestamp,
             message,
-            log_group: format!("kamal:{}", server),
+            log_group: self.config.group_label(server),
             log_stream: Some(self.config.service.clone()),
             region: None,
+            also_in: Vec::new(),
+            ptr: None,
         })
     }
 
@@ -405,6 +730,22 @@ impl KamalSearcher {
         &self.config.servers
     }
 
+    /// Servers a search reads: all of them, or only the first with --primary-only or --container
+    /// (a container ID only exists on one host), as when following
+    pub fn searched_servers(&self) -> &[String] {
+        let servers = &self.config.servers;
+        if self.primary_only || self.container_id.is_some() {
+            &servers[..servers.len().min(1)]
+        } else {
+            servers
+        }
+    }
+
+    /// Log group label used for entries from a server
+    pub fn group_label(&self, server: &str) -> String {
+        self.config.group_label(server)
+    }
+
     /// Get service name
     pub fn service(&self) -> &str {
         &self.config.service
@@ -450,16 +791,161 @@ fn parse_docker_timestamp(s: &str) -> Option<DateTime<Utc>> {
     None
 }
 
+/// Read `docker logs -f` output from a child process, calling `on_entry` for each matching line
+/// (which returns whether it showed the entry, so only shown lines count and keep the follow alive) until the output ends, the stop_flag is set or `follow_idle_timeout` passes without a matching line,
+/// then kill the child so no remote session is left behind
+/// The status line is cleared around each entry and kept up to date with the line rate
+async fn follow_child(
+    child: &mut Child,
+    log_group: &str,
+    service: &str,
+    params: &KamalSearchParams,
+    stop_flag: Arc<AtomicBool>,
+    status: &mut FollowStatus,
+    mut on_entry: impl FnMut(&LogEntry) -> bool,
+) -> Result<Followed> {
+    // With -tt, docker logs output goes to stdout via the pseudo-terminal
+    let stdout = child.stdout.take()
+        .ok_or_else(|| anyhow!("Failed to capture stdout"))?;
+
+    let mut reader = BufReader::new(stdout).lines();
+    let mut count = 0;
+    let mut context = FollowContext::default();
+    let mut idle = IdleTimer::new(params.follow_idle_timeout);
+    let mut idle_stop = false;
+
+    while !stop_flag.load(Ordering::Relaxed) {
+        if idle.expired(Instant::now()) {
+            idle_stop = true;
+            break;
+        }
+        tokio::select! {
+            line_result = reader.next_line() => {
+                match line_result {
+                    Ok(Some(line)) => {
+                        if let Some(entry) = parse_log_line_static(&line, log_group, service) {
+                            if context.admit(params, &entry.message, &match_source(&entry)) {
+                                status.clear();
+                                if on_entry(&entry) {
+                                    status.record(1);
+                                    idle.activity(Instant::now());
+                                    count += 1;
+                                }
+                                status.draw();
+                            }
+                        }
+                    }
+                    Ok(None) | Err(_) => break, // EOF
+                }
+            }
+            _ = tokio::time::sleep(Duration::from_millis(100)) => {
+                // Check stop flag periodically
+                status.tick();
+            }
+        }
+    }
+
+    // Kill the child process when done (a no-op if it already exited)
+    let _ = child.kill().await;
+
+    Ok(Followed { lines: count, idle: idle_stop })
+}
+
+/// `docker logs` for a container, following it or fetching the search window
+fn docker_logs_command(container_id: &str, params: &KamalSearchParams, follow: bool) -> String {
+    let mut cmd = format!("docker logs {} --timestamps", container_id);
+    if follow {
+        cmd.push_str(" -f");
+    }
+
+    // Add --since if specified
+    if let Some(since) = &params.since {
+        cmd.push_str(&format!(" --since {}", since));
+    }
+
+    // Add tail limit (fetch more than needed for filtering)
+    // --tail cuts the start of the window, so skip it when keeping the oldest entries
+    if !follow && !params.oldest {
+        let fetch_limit = params.limit * params.tail_multiplier; // Over-fetch to account for filtering
+        cmd.push_str(&format!(" --tail {}", fetch_limit.max(1000)));
+    }
+    cmd
+}
+
+/// Loose check of a --container value: a (possibly short) ID or a container name
+/// It ends up in a remote shell command, so nothing beyond docker's name characters is allowed
+fn validate_container_id(id: &str) -> Result<()> {
+    let valid = id.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && id.len() <= 128
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    if !valid {
+        bail!("Invalid container '{}': expected a container ID (e.g. 3f2a9c1b7e4d) or name", id);
+    }
+    Ok(())
+}
+
+/// `docker ps` listing the ID and name of running containers whose name matches `filter`
+fn docker_ps_command(filter: &str) -> String {
+    format!(
+        "docker ps --filter 'name={}' --format '{{{{.ID}}}} {{{{.Names}}}}'",
+        filter.replace('\'', r"'\''")
+    )
+}
+
+/// `docker inspect` printing when a container started, e.g. `2026-01-31T12:34:56.789012345Z`
+fn docker_started_at_command(container_id: &str) -> String {
+    format!("docker inspect --format '{{{{.State.StartedAt}}}}' {}", container_id)
+}
+
+/// Parse a container's `.State.StartedAt`; containers that never started report the zero time
+fn parse_started_at(output: &str) -> Result<DateTime<Utc>> {
+    let output = output.trim();
+    match parse_docker_timestamp(output) {
+        Some(started) if started.timestamp() > 0 => Ok(started),
+        Some(_) => bail!("The container has never started"),
+        None => bail!("Unexpected container start time from docker inspect: '{}'", output),
+    }
+}
+
+/// Pick the single container from `docker ps` output, failing when none or several match
+fn pick_container(output: &str, service_desc: &str, filter: &str) -> Result<String> {
+    let containers: Vec<(&str, &str)> = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some((fields.next()?, fields.next().unwrap_or_default()))
+        })
+        .collect();
+
+    match containers.as_slice() {
+        [] => Err(anyhow!(
+            "No running container found for service: {} (filter: {})",
+            service_desc,
+            filter
+        )),
+        [(id, _)] => Ok(id.to_string()),
+        several => Err(anyhow!(
+            "{} running containers match service {} (filter: {}): {}; narrow it with --container-pattern",
+            several.len(),
+            service_desc,
+            filter,
+            several.iter().map(|(_, name)| *name).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
 /// Static version of parse_log_line for use in spawned tasks
-fn parse_log_line_static(line: &str, server: &str, service: &str) -> Option<LogEntry> {
+fn parse_log_line_static(line: &str, log_group: &str, service: &str) -> Option<LogEntry> {
     let parts: Vec<&str> = line.splitn(2, ' ').collect();
     if parts.len() < 2 {
         return Some(LogEntry {
             timestamp: Utc::now(),
             message: line.to_string(),
-            log_group: format!("kamal:{}", server),
+            log_group: log_group.to_string(),
             log_stream: Some(service.to_string()),
             region: None,
+            also_in: Vec::new(),
+            ptr: None,
         });
     }
 
@@ -470,15 +956,413 @@ fn parse_log_line_static(line: &str, server: &str, service: &str) -> Option<LogE
     Some(LogEntry {
         timestamp,
         message,
-        log_group: format!("kamal:{}", server),
+        log_group: log_group.to_string(),
         log_stream: Some(service.to_string()),
         region: None,
+        also_in: Vec::new(),
+        ptr: None,
     })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
+
+    /// A searcher for service `app` on these servers, all in the web role
+    fn searcher(servers: &[&str]) -> KamalSearcher {
+        KamalSearcher {
+            config: KamalConfig {
+                service: "app".to_string(),
+                servers: servers.iter().map(|s| s.to_string()).collect(),
+                roles: HashMap::new(),
+                ssh_user: "root".to_string(),
+                destination: None,
+            },
+            container_pattern: None,
+            container_id: None,
+            primary_only: false,
+        }
+    }
+
+    #[test]
+    fn test_params_match_patterns_and_excludes() {
+        let params = KamalSearchParams {
+            patterns: vec!["error".to_string(), "timeout".to_string()],
+            exclude: vec!["health".to_string()],
+            ..KamalSearchParams::default()
+        };
+
+        assert!(params.matches("ERROR: upstream Timeout after 30s", ""));
+        assert!(!params.matches("ERROR: bad request", ""));
+        assert!(!params.matches("ERROR: health check timeout", ""));
+    }
+
+    #[test]
+    fn test_params_match_whole_words() {
+        let params = KamalSearchParams {
+            patterns: vec!["id".to_string()],
+            word: true,
+            ..KamalSearchParams::default()
+        };
+
+        assert!(params.matches("lookup ID=5", ""));
+        assert!(params.matches("id", ""));
+        assert!(!params.matches("video width=640", ""));
+        assert!(!params.matches("user_id=5", ""));
+        assert!(params.matches("video and id=5", ""));
+    }
+
+    #[test]
+    fn test_params_anchored_match() {
+        let params = KamalSearchParams {
+            patterns: vec!["job".to_string()],
+            starts_with: Some("ERROR".to_string()),
+            ends_with: Some("failed".to_string()),
+            ..KamalSearchParams::default()
+        };
+
+        assert!(params.matches("error: job 42 failed\r", ""));
+        assert!(!params.matches("INFO error: job 42 failed", ""));
+        assert!(!params.matches("ERROR: job 42 failed, retrying", ""));
+        assert!(!params.matches("ERROR: worker failed", ""));
+
+        // --word: the anchored text is a whole word, as in Insights
+        let params = KamalSearchParams { word: true, patterns: vec![], ..params };
+        assert!(params.matches("error: job 42 failed", ""));
+        assert!(!params.matches("errors: job 42 failed", ""));
+        assert!(!params.matches("error: job 42 unfailed", ""));
+    }
+
+    #[test]
+    fn test_params_invert_match() {
+        let params = KamalSearchParams {
+            patterns: vec!["info".to_string(), "request".to_string()],
+            exclude: vec!["health".to_string()],
+            invert_match: true,
+            ..KamalSearchParams::default()
+        };
+
+        assert!(!params.matches("INFO request completed", ""));
+        assert!(params.matches("INFO cache warmed", ""));
+        assert!(params.matches("ERROR upstream timeout", ""));
+        // Excludes still apply to the inverted selection
+        assert!(!params.matches("ERROR health check failed", ""));
+    }
+
+    #[test]
+    fn test_params_match_field() {
+        let params = KamalSearchParams {
+            patterns: vec!["worker".to_string()],
+            exclude: vec!["health".to_string()],
+            ..KamalSearchParams::default()
+        };
+        let entry = |message: &str, server: &str| LogEntry {
+            timestamp: Utc::now(),
+            message: message.to_string(),
+            log_group: format!("kamal:{}", server),
+            log_stream: Some("app".to_string()),
+            region: None,
+            also_in: Vec::new(),
+            ptr: None,
+        };
+        let matches = |params: &KamalSearchParams, entry: LogEntry| params.matches(&entry.message, &match_source(&entry));
+
+        assert!(matches(&params, entry("worker started", "web-1")));
+        assert!(!matches(&params, entry("job done", "worker-1")));
+
+        let params = KamalSearchParams { match_field: MatchField::Stream, ..params };
+        assert!(!matches(&params, entry("worker started", "web-1")));
+        assert!(matches(&params, entry("job done", "Worker-1")));
+        // Excludes are still checked against the message
+        assert!(!matches(&params, entry("health ok", "worker-1")));
+
+        let params = KamalSearchParams { match_field: MatchField::Any, ..params };
+        assert!(matches(&params, entry("worker started", "web-1")));
+        assert!(matches(&params, entry("job done", "worker-1")));
+        assert!(!matches(&params, entry("job done", "web-1")));
+
+        // The service name counts as part of the source too
+        let params = KamalSearchParams { patterns: vec!["app".to_string()], match_field: MatchField::Stream, ..params };
+        assert!(matches(&params, entry("job done", "web-1")));
+    }
+
+    #[test]
+    fn test_follow_context_emits_lines_after_matches() {
+        let params = KamalSearchParams {
+            patterns: vec!["error".to_string()],
+            exclude: vec!["health".to_string()],
+            follow_context: 2,
+            ..KamalSearchParams::default()
+        };
+        let lines = [
+            "booting",
+            "ERROR: boom",
+            "  at app.rb:10",
+            "health check ok",
+            "unrelated",
+            "ERROR: again",
+            "  at app.rb:20",
+            "ERROR: third",
+            "  at app.rb:30",
+            "  at app.rb:31",
+            "  at app.rb:32",
+        ];
+
+        let mut context = FollowContext::default();
+        let emitted: Vec<&str> = lines.iter().copied().filter(|line| context.admit(&params, line, "")).collect();
+        assert_eq!(
+            emitted,
+            vec!["ERROR: boom", "  at app.rb:10", "ERROR: again", "  at app.rb:20", "ERROR: third", "  at app.rb:30", "  at app.rb:31"]
+        );
+
+        // Without context only matches are emitted
+        let params = KamalSearchParams { follow_context: 0, ..params };
+        let mut context = FollowContext::default();
+        assert_eq!(lines.iter().filter(|line| context.admit(&params, line, "")).count(), 3);
+    }
+
+    #[test]
+    fn test_without_servers_skips_excluded_hosts() {
+        let searcher = || searcher(&["web-1", "canary-1", "web-2"]);
+
+        let filtered = searcher().without_servers(&["canary-1".to_string()]).unwrap();
+        assert_eq!(filtered.servers(), ["web-1", "web-2"]);
+        assert_eq!(searcher().without_servers(&[]).unwrap().servers().len(), 3);
+
+        let all = ["web-1".to_string(), "canary-1".to_string(), "web-2".to_string()];
+        assert!(searcher().without_servers(&all).is_err());
+    }
+
+    #[test]
+    fn test_primary_only_searches_first_server() {
+        let searcher = || searcher(&["web-1", "web-2", "web-3"]);
+
+        assert_eq!(searcher().searched_servers(), ["web-1", "web-2", "web-3"]);
+        assert_eq!(searcher().with_primary_only(true).searched_servers(), ["web-1"]);
+        // The primary is the first server left after --exclude-server
+        let without_first = searcher().without_servers(&["web-1".to_string()]).unwrap().with_primary_only(true);
+        assert_eq!(without_first.searched_servers(), ["web-2"]);
+        // Listing the servers still shows the whole deployment
+        assert_eq!(searcher().with_primary_only(true).servers().len(), 3);
+    }
+
+    #[test]
+    fn test_entries_are_labelled_with_role() {
+        let mut searcher = searcher(&["web-1", "job-1"]);
+        searcher.config.roles = HashMap::from([
+            ("web-1".to_string(), "web".to_string()),
+            ("job-1".to_string(), "job".to_string()),
+        ]);
+
+        let line = "2026-01-31T12:34:56.789012345Z Processing job 42";
+        let entry = searcher.parse_log_line(line, "job-1").unwrap();
+        assert_eq!(entry.log_group, "kamal:job-1:job");
+        assert_eq!(searcher.group_label("web-1"), "kamal:web-1:web");
+    }
+
+    #[test]
+    fn test_container_filter_command() {
+        let searcher = |service: &str, destination: Option<&str>| {
+            let mut searcher = searcher(&["web-1", "job-1"]);
+            searcher.config.service = service.to_string();
+            searcher.config.destination = destination.map(str::to_string);
+            searcher.config.roles = HashMap::from([
+                ("web-1".to_string(), "web".to_string()),
+                ("job-1".to_string(), "job".to_string()),
+            ]);
+            searcher
+        };
+
+        assert_eq!(
+            docker_ps_command(&searcher("app", None).container_filter("web-1")),
+            "docker ps --filter 'name=^app-web-' --format '{{.ID}} {{.Names}}'"
+        );
+        assert_eq!(searcher("app", None).container_filter("job-1"), "^app-job-");
+        assert_eq!(searcher("app", Some("staging")).container_filter("web-1"), "^app-web-staging-");
+        assert_eq!(searcher("my.app", None).container_filter("web-1"), r"^my\.app-web-");
+        // Unknown hosts fall back to the web role
+        assert_eq!(searcher("app", None).container_filter("other"), "^app-web-");
+
+        let custom = searcher("app", None).with_container_pattern(Some("^legacy-app$".to_string()));
+        assert_eq!(custom.container_filter("job-1"), "^legacy-app$");
+        assert_eq!(docker_ps_command("it's"), r"docker ps --filter 'name=it'\''s' --format '{{.ID}} {{.Names}}'");
+    }
+
+    #[test]
+    fn test_docker_logs_command_targets_given_container() {
+        let searcher = searcher(&["web-1"]).with_container_id(Some("3f2a9c1b7e4d".to_string())).unwrap();
+        let id = searcher.container_id.as_deref().unwrap();
+
+        let params = KamalSearchParams { since: Some("1h".to_string()), ..KamalSearchParams::default() };
+        assert_eq!(
+            docker_logs_command(id, &params, false),
+            "docker logs 3f2a9c1b7e4d --timestamps --since 1h --tail 1000"
+        );
+        assert_eq!(docker_logs_command(id, &params, true), "docker logs 3f2a9c1b7e4d --timestamps -f --since 1h");
+        let params = KamalSearchParams { limit: 500, tail_multiplier: 4, ..params };
+        assert!(docker_logs_command(id, &params, false).ends_with("--tail 2000"));
+
+        assert!(validate_container_id("app-web-1f2e.1").is_ok());
+        assert!(validate_container_id("abc; rm -rf /").is_err());
+        assert!(validate_container_id("-abc").is_err());
+        assert!(validate_container_id("").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_oldest_stops_at_limit_across_both_streams() {
+        let searcher = searcher(&["web-1"]);
+        let lines = |prefix: &str| {
+            (0..5)
+                .map(|i| format!("2026-01-31T12:34:5{}.000000000Z {} error {}\n", i, prefix, i))
+                .collect::<String>()
+        };
+        let params = KamalSearchParams { patterns: vec!["error".to_string()], limit: 3, oldest: true, ..KamalSearchParams::default() };
+
+        // stderr never ends, like a long `docker logs` still writing: reading stops anyway
+        let stdout = lines("out");
+        let (stderr, _writer) = tokio::io::duplex(64);
+        let collected = tokio::time::timeout(
+            Duration::from_secs(5),
+            searcher.collect_streams(stdout.as_bytes(), stderr, "web-1", &params),
+        )
+        .await
+        .expect("reading should stop once the limit is reached")
+        .unwrap();
+        assert!(collected.filled);
+        let messages: Vec<&str> = collected.entries.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["out error 0", "out error 1", "out error 2"]);
+
+        // Matches from both streams count toward one limit
+        let (stdout, stderr) = (lines("out"), lines("err"));
+        let collected = searcher.collect_streams(stdout.as_bytes(), stderr.as_bytes(), "web-1", &params).await.unwrap();
+        assert!(collected.filled);
+        assert_eq!(collected.entries.len(), 3);
+
+        // Newest-first searches are capped by --tail and read everything
+        let params = KamalSearchParams { oldest: false, ..params };
+        let collected = searcher.collect_streams(stdout.as_bytes(), stderr.as_bytes(), "web-1", &params).await.unwrap();
+        assert!(!collected.filled);
+        assert_eq!(collected.entries.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_docker_error_is_kept_from_stderr() {
+        let params = KamalSearchParams { patterns: vec!["error".to_string()], ..KamalSearchParams::default() };
+        let stdout = "2026-01-31T12:34:50.000000000Z error on stdout\n";
+        let stderr = "2026-01-31T12:34:51.000000000Z log line on stderr\nError response from daemon: No such container: 3f2a9c1b7e4d\n";
+
+        let collected = searcher(&["web-1"]).collect_streams(stdout.as_bytes(), stderr.as_bytes(), "web-1", &params).await.unwrap();
+        assert_eq!(collected.last_stderr_line, "Error response from daemon: No such container: 3f2a9c1b7e4d");
+    }
+
+    #[test]
+    fn test_pick_container() {
+        assert_eq!(pick_container("abc123 app-web-1f2e\n", "app", "^app-web-").unwrap(), "abc123");
+        assert!(pick_container("", "app", "^app-web-").unwrap_err().to_string().contains("No running container"));
+
+        let several = pick_container("abc123 app-web-1f2e\ndef456 app-web-9a8b\n", "app", "^app-web-").unwrap_err();
+        assert!(several.to_string().contains("2 running containers"));
+        assert!(several.to_string().contains("app-web-1f2e, app-web-9a8b"));
+    }
+
+    #[test]
+    fn test_retryable_ssh_errors() {
+        use std::io::{Error, ErrorKind};
+
+        let connect = |kind, message: &str| openssh::Error::Connect(Error::new(kind, message.to_string()));
+
+        assert!(is_retryable_ssh_error(&connect(ErrorKind::TimedOut, "connect to host web-1 port 22: Connection timed out")));
+        assert!(is_retryable_ssh_error(&connect(ErrorKind::ConnectionRefused, "connect to host web-1 port 22: Connection refused")));
+        assert!(is_retryable_ssh_error(&connect(ErrorKind::Other, "connect to host web-1 port 22: Network is unreachable")));
+        assert!(is_retryable_ssh_error(&openssh::Error::Disconnected));
+
+        assert!(!is_retryable_ssh_error(&connect(ErrorKind::PermissionDenied, "deploy@web-1: Permission denied (publickey).")));
+        assert!(!is_retryable_ssh_error(&connect(ErrorKind::Other, "Could not resolve hostname web-1: Name or service not known")));
+        assert!(!is_retryable_ssh_error(&connect(ErrorKind::ConnectionAborted, "Host key verification failed.")));
+        assert!(!is_retryable_ssh_error(&openssh::Error::InvalidCommand));
+    }
+
+    #[tokio::test]
+    async fn test_follow_child_stops_and_kills_child() {
+        use std::process::Stdio;
+        use tokio::process::Command;
+
+        let params = KamalSearchParams {
+            patterns: vec!["tick".to_string()],
+            ..KamalSearchParams::default()
+        };
+
+        // A fake `docker logs -f` that never ends on its own
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg("while true; do echo '2026-01-31T12:34:56.789012345Z tick'; echo '2026-01-31T12:34:56.789012345Z noise'; sleep 0.05; done")
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let flag = stop_flag.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            flag.store(true, Ordering::Relaxed);
+        });
+
+        let mut messages = Vec::new();
+        let followed = tokio::time::timeout(
+            Duration::from_secs(5),
+            follow_child(&mut child, "web-1", "app", &params, stop_flag, &mut FollowStatus::new(false), |e| {
+                messages.push(e.message.clone());
+                true
+            }),
+        )
+        .await
+        .expect("follow_child should stop once the flag is set")
+        .unwrap();
+
+        assert!(followed.lines > 0);
+        assert!(!followed.idle);
+        assert_eq!(followed.lines, messages.len());
+        assert!(messages.iter().all(|m| m == "tick"));
+
+        // The child has been killed and reaped: it has an exit status, and not a successful one
+        let status = child.try_wait().unwrap().expect("the child should have exited");
+        assert!(!status.success());
+
+        // Only non-matching lines: the idle timeout stops the follow without the flag
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg("while true; do echo '2026-01-31T12:34:56.789012345Z noise'; sleep 0.05; done")
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let params = KamalSearchParams { follow_idle_timeout: Some(Duration::from_millis(300)), ..params };
+        let followed = tokio::time::timeout(
+            Duration::from_secs(5),
+            follow_child(&mut child, "web-1", "app", &params, Arc::new(AtomicBool::new(false)), &mut FollowStatus::new(false), |_| true),
+        )
+        .await
+        .expect("follow_child should stop once idle")
+        .unwrap();
+        assert_eq!(followed, Followed { lines: 0, idle: true });
+        assert!(child.try_wait().unwrap().is_some());
+
+        // Matching lines the display filters drop (--where and the like) don't count either
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg("while true; do echo '2026-01-31T12:34:56.789012345Z tick'; sleep 0.05; done")
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let followed = tokio::time::timeout(
+            Duration::from_secs(5),
+            follow_child(&mut child, "web-1", "app", &params, Arc::new(AtomicBool::new(false)), &mut FollowStatus::new(false), |_| false),
+        )
+        .await
+        .expect("follow_child should stop once idle")
+        .unwrap();
+        assert_eq!(followed, Followed { lines: 0, idle: true });
+    }
 
     #[test]
     fn test_parse_docker_timestamp() {
@@ -490,4 +1374,25 @@ mod tests {
         let ts = parse_docker_timestamp("2026-01-31T12:34:56Z");
         assert!(ts.is_some());
     }
+
+    #[test]
+    fn test_since_deploy_window_from_started_at() {
+        use chrono::TimeZone;
+
+        assert_eq!(
+            docker_started_at_command("3f2a9c1b7e4d"),
+            "docker inspect --format '{{.State.StartedAt}}' 3f2a9c1b7e4d"
+        );
+
+        // docker inspect prints nanoseconds and a trailing newline
+        let started = parse_started_at("2026-01-31T12:34:56.789012345Z\n").unwrap();
+        assert_eq!(started, Utc.with_ymd_and_hms(2026, 1, 31, 12, 34, 56).unwrap() + chrono::Duration::nanoseconds(789012345));
+        let range = crate::time::TimeRange::since(started);
+        assert_eq!(range.start, started);
+        assert!(range.end > started);
+
+        // Created but never started, or not a time at all
+        assert!(parse_started_at("0001-01-01T00:00:00Z").unwrap_err().to_string().contains("never started"));
+        assert!(parse_started_at("Error: No such object").is_err());
+    }
 }
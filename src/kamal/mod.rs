@@ -1,5 +1,5 @@
 mod config;
 pub mod logs;
 
-pub use config::KamalConfig;
+pub use config::{default_deploy_file, destination_deploy_file, find_deploy_files, KamalConfig, DEFAULT_DEPLOY_FILE};
 pub use logs::{KamalSearcher, KamalSearchParams};
@@ -0,0 +1,98 @@
+use regex::Regex;
+use serde_json::Value;
+use std::sync::LazyLock;
+
+/// Level keys in structured (JSON) logs, in lookup order
+const JSON_LEVEL_KEYS: &[&str] = &["level", "severity", "log.level", "lvl", "levelname"];
+
+/// `level=warn` / `severity: "error"` in key-value or logfmt lines
+static LEVEL_FIELD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)\b(?:level|severity|lvl)\s*[=:]\s*"?([a-z]+)"#).unwrap());
+
+/// Upper-case level words, as printed by most text loggers ("ERROR", "[WARN]", "W, [...] WARN --")
+static LEVEL_WORD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(FATAL|CRITICAL|ERROR|ERR|WARNING|WARN|INFO|DEBUG|TRACE)\b").unwrap());
+
+/// Normalized severity of a log line: FATAL, ERROR, WARN, INFO, DEBUG or TRACE
+/// JSON messages use their level field (pino/bunyan numbers too); text uses `level=` or the
+/// first upper-case level word. None when nothing looks like a level
+pub fn detect(message: &str) -> Option<&'static str> {
+    let trimmed = message.trim();
+    if trimmed.starts_with('{') {
+        if let Ok(json) = serde_json::from_str::<Value>(trimmed) {
+            if let Some(level) = json_level(&json) {
+                return Some(level);
+            }
+        }
+    }
+
+    if let Some(level) = LEVEL_FIELD.captures(message).and_then(|c| normalize(&c[1])) {
+        return Some(level);
+    }
+    LEVEL_WORD.captures(message).and_then(|c| normalize(&c[1]))
+}
+
+fn json_level(json: &Value) -> Option<&'static str> {
+    JSON_LEVEL_KEYS.iter().find_map(|key| {
+        let value = json.get(key).or_else(|| key.split_once('.').and_then(|(a, b)| json.get(a)?.get(b)))?;
+        match value {
+            Value::String(s) => normalize(s),
+            // pino/bunyan: 10 trace, 20 debug, 30 info, 40 warn, 50 error, 60 fatal
+            Value::Number(n) => match n.as_u64()? {
+                0..=19 => Some("TRACE"),
+                20..=29 => Some("DEBUG"),
+                30..=39 => Some("INFO"),
+                40..=49 => Some("WARN"),
+                50..=59 => Some("ERROR"),
+                _ => Some("FATAL"),
+            },
+            _ => None,
+        }
+    })
+}
+
+/// Map the spellings loggers use onto one set of names
+fn normalize(level: &str) -> Option<&'static str> {
+    match level.to_ascii_lowercase().as_str() {
+        "fatal" | "critical" | "crit" | "emerg" | "emergency" | "alert" | "panic" => Some("FATAL"),
+        "error" | "err" => Some("ERROR"),
+        "warn" | "warning" => Some("WARN"),
+        "info" | "notice" | "information" => Some("INFO"),
+        "debug" => Some("DEBUG"),
+        "trace" | "verbose" => Some("TRACE"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_text_levels() {
+        assert_eq!(detect("ERROR: upstream timeout"), Some("ERROR"));
+        assert_eq!(detect("2026-01-31 12:00:00 [WARN] disk at 91%"), Some("WARN"));
+        assert_eq!(detect("I, [2026-01-31T12:00:00 #1]  INFO -- : Started GET /"), Some("INFO"));
+        assert_eq!(detect("time=12:00 level=debug msg=\"cache miss\""), Some("DEBUG"));
+        // level= wins over words in the message
+        assert_eq!(detect("level=info msg=\"retrying after ERROR\""), Some("INFO"));
+    }
+
+    #[test]
+    fn test_detect_json_levels() {
+        assert_eq!(detect(r#"{"level":"warning","msg":"slow"}"#), Some("WARN"));
+        assert_eq!(detect(r#"{"severity":"ERROR","message":"boom"}"#), Some("ERROR"));
+        assert_eq!(detect(r#"{"log":{"level":"info"}}"#), Some("INFO"));
+        assert_eq!(detect(r#"{"level":50,"msg":"pino error"}"#), Some("ERROR"));
+        assert_eq!(detect(r#"{"level":30}"#), Some("INFO"));
+    }
+
+    #[test]
+    fn test_detect_no_level() {
+        assert_eq!(detect("GET /api 200 12ms"), None);
+        // Lower-case words in prose aren't levels
+        assert_eq!(detect("no error found while parsing"), None);
+        assert_eq!(detect(r#"{"msg":"hello"}"#), None);
+        assert_eq!(detect(""), None);
+    }
+}
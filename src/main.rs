@@ -1,139 +1,164 @@
 mod aws;
+mod batch;
 mod cli;
+mod clipboard;
 mod config;
+mod deadline;
+mod diagnostics;
+mod display;
+mod doctor;
 mod kamal;
+mod level;
 mod output;
+mod progress;
+mod sink;
+mod state;
 mod time;
 mod tui;
+mod where_clause;
 
-use anyhow::Result;
-use aws::SearchParams;
+use anyhow::{bail, Context, Result};
+use aws::{LogEntry, SearchParams};
+use chrono::SecondsFormat;
 use clap::Parser;
-use cli::{Cli, Commands, ConfigAction, LogSource, OutputMode};
+use futures::StreamExt;
+use cli::{Cli, Commands, ConfigAction, LogSource, OutputMode, PagerMode, ResolveError, ResolvedSearch};
 use colored::Colorize;
 use config::Config;
 use kamal::KamalSearchParams;
+use sink::OutputSink;
+use state::SinceLast;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::io::{IsTerminal, Write};
+use std::sync::Arc;
+use std::time::Instant;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    diagnostics::init(cli.verbose);
     let config = Config::load().unwrap_or_default();
 
     match cli.command {
-        Commands::Search {
-            patterns,
-            groups,
-            deploy_file,
-            preset,
-            exclude,
-            last,
-            start,
-            end,
-            output,
-            limit,
-            follow,
-        } => {
-            // Resolve preset if specified
-            let (resolved_groups, resolved_patterns, resolved_exclude, resolved_last, resolved_limit, resolved_deploy, resolved_source) =
-                if let Some(preset_name) = &preset {
-                    match config.get_preset(preset_name) {
-                        Some(p) => {
-                            let mut final_patterns = p.patterns.clone();
-                            final_patterns.extend(patterns);
-
-                            let mut final_exclude = p.exclude.clone();
-                            final_exclude.extend(exclude);
-
-                            let final_groups = if groups.is_empty() {
-                                p.groups.clone()
-                            } else {
-                                groups
-                            };
-
-                            let final_last = p.time_range.clone().unwrap_or(last);
-                            let final_limit = p.limit.unwrap_or(limit);
-
-                            // Kamal presets can specify deploy file
-                            let final_deploy = deploy_file.or(p.deploy_file.clone());
-                            let final_source = if p.source.as_deref() == Some("kamal") {
-                                LogSource::Kamal
-                            } else {
-                                cli.source.clone()
-                            };
-
-                            (final_groups, final_patterns, final_exclude, final_last, final_limit, final_deploy, final_source)
-                        }
-                        None => {
-                            eprintln!("{} Preset '{}' not found", "Error:".red(), preset_name);
-                            eprintln!("Available presets: {:?}", config.presets.keys().collect::<Vec<_>>());
-                            return Ok(());
+        Commands::Search(ref args) => {
+            let search = match cli::resolve_search(&cli, args, &config) {
+                Ok(search) => search,
+                Err(e) => {
+                    if args.output.is_json() {
+                        output::display_json_error(&e.to_string(), None, &args.output);
+                    } else {
+                        eprintln!("{} {}", "Error:".red(), e);
+                        match &e {
+                            ResolveError::UnknownPreset { available, .. } => {
+                                eprintln!("Available presets: {:?}", available);
+                            }
+                            ResolveError::UnknownAccount { available, .. } => {
+                                eprintln!("Available accounts: {:?}", available);
+                            }
+                            _ => {}
                         }
                     }
-                } else {
-                    // Use config defaults if no groups specified
-                    let final_groups = if groups.is_empty() {
-                        config.default_groups.clone()
-                    } else {
-                        groups
-                    };
-                    (final_groups, patterns, exclude, last, limit, deploy_file, cli.source.clone())
-                };
+                    return Ok(());
+                }
+            };
+
+            // Explain on stderr so JSON on stdout stays parseable
+            if args.explain || args.dry_run {
+                cli::write_explain(&mut std::io::stderr(), &search)?;
+            }
+            if args.dry_run {
+                return Ok(());
+            }
 
-            match resolved_source {
+            match search.source {
                 LogSource::Cloudwatch => {
                     let searcher = aws::MultiRegionSearcher::new(
-                        cli.profile.clone().or(config.default_profile.clone()),
-                        cli.region.clone().or(config.default_region.clone()),
-                    );
-
-                    run_cloudwatch_search(
-                        &searcher,
-                        resolved_patterns,
-                        resolved_groups,
-                        resolved_exclude,
-                        resolved_last,
-                        start,
-                        end,
-                        output,
-                        resolved_limit,
+                        search.profile.clone(),
+                        search.region.clone(),
                     )
-                    .await?;
+                    .with_region_profiles(config.region_profiles.clone())
+                    .with_endpoint_url(cli.endpoint_url.clone());
+
+                    run_cloudwatch_search(&searcher, &search).await?;
                 }
                 LogSource::Kamal => {
-                    let deploy_path = resolved_deploy.unwrap_or_else(|| "config/deploy.yml".to_string());
-
-                    run_kamal_search(
-                        &deploy_path,
-                        resolved_patterns,
-                        resolved_exclude,
-                        resolved_last,
-                        output,
-                        resolved_limit as usize,
-                        follow,
-                    )
-                    .await?;
+                    run_kamal_search(&search).await?;
                 }
             }
         }
+        Commands::Batch(ref args) => {
+            run_batch(&cli, args, &config).await?;
+        }
+        Commands::Export(args) => {
+            run_export(
+                &args,
+                cli.profile.as_deref().or(config.default_profile.as_deref()),
+                cli.region.as_deref().or(config.default_region.as_deref()),
+                cli.endpoint_url.as_deref(),
+            )
+            .await?;
+        }
+        Commands::FetchContext { ptr, json } => {
+            let client = aws::create_client(
+                cli.profile.as_deref().or(config.default_profile.as_deref()),
+                cli.region.as_deref().or(config.default_region.as_deref()),
+                cli.endpoint_url.as_deref(),
+            )
+            .await?;
+            let searcher = aws::LogSearcher::new(client);
+            fetch_context(&searcher, &ptr, json).await?;
+        }
         Commands::Groups { prefix } => {
             let client = aws::create_client(
                 cli.profile.as_deref().or(config.default_profile.as_deref()),
                 cli.region.as_deref().or(config.default_region.as_deref()),
+                cli.endpoint_url.as_deref(),
             )
             .await?;
             let searcher = aws::LogSearcher::new(client);
             list_groups(&searcher, prefix).await?;
         }
-        Commands::Tui => {
+        Commands::Profiles => {
+            let active = cli.profile.as_deref().or(config.default_profile.as_deref()).unwrap_or("default");
+            list_profiles(&aws::load_profiles()?, active);
+        }
+        Commands::Tui { follow_backfill } => {
+            follow_backfill.as_deref().map(time::parse_duration).transpose()?;
+
+            // The profile last picked in the TUI wins over the config default
             let searcher = aws::MultiRegionSearcher::new(
-                cli.profile.clone().or(config.default_profile.clone()),
+                cli.profile
+                    .clone()
+                    .or(state::TuiState::load().profile)
+                    .or(config.default_profile.clone()),
                 cli.region.clone().or(config.default_region.clone()),
-            );
+            )
+            .with_region_profiles(config.region_profiles.clone())
+            .with_endpoint_url(cli.endpoint_url.clone());
+
+            // --region pre-selects that region in the TUI like default_region does
+            let config = Config {
+                default_region: cli.region.clone().or(config.default_region),
+                follow_backfill: follow_backfill.or(config.follow_backfill),
+                ..config
+            };
             tui::run_tui(searcher, config).await?;
         }
         Commands::Config { action } => {
             handle_config_command(action, &config)?;
         }
+        Commands::Doctor => {
+            let checks = doctor::run(
+                cli.profile.as_deref().or(config.default_profile.as_deref()),
+                cli.region.as_deref().or(config.default_region.as_deref()),
+                cli.endpoint_url.as_deref(),
+            )
+            .await;
+            doctor::write_report(&mut std::io::stdout(), &checks)?;
+            if doctor::has_failures(&checks) {
+                std::process::exit(1);
+            }
+        }
     }
 
     Ok(())
@@ -191,6 +216,12 @@ fn handle_config_command(action: ConfigAction, config: &Config) -> Result<()> {
                     if !preset.exclude.is_empty() {
                         println!("    Exclude: {}", preset.exclude.join(", ").dimmed());
                     }
+                    if let Some(ref profile) = preset.profile {
+                        println!("    Profile: {}", profile.dimmed());
+                    }
+                    if let Some(ref region) = preset.region {
+                        println!("    Region: {}", region.dimmed());
+                    }
                     println!();
                 }
             }
@@ -201,249 +232,727 @@ fn handle_config_command(action: ConfigAction, config: &Config) -> Result<()> {
 
 async fn run_cloudwatch_search(
     searcher: &aws::MultiRegionSearcher,
-    patterns: Vec<String>,
-    groups: Vec<String>,
-    exclude: Vec<String>,
-    last: String,
-    start: Option<String>,
-    end: Option<String>,
-    output_mode: OutputMode,
-    limit: i32,
+    search: &ResolvedSearch,
 ) -> Result<()> {
+    let started = Instant::now();
+    let deadline = search_deadline(search)?;
+    let patterns = &search.patterns;
+    let exclude = &search.exclude;
+    let groups = &search.groups;
+    let output_mode = &search.output;
+
+    // Resume after the previous run's newest entry if requested
+    let mut since_last = if search.since_last {
+        Some(SinceLast::load(search)?)
+    } else {
+        None
+    };
+
     // Determine time range
-    let time_range = if let Some(start_str) = start {
-        time::TimeRange::from_explicit(&start_str, end.as_deref())?
+    let time_range = if let Some(previous) = since_last.as_ref().and_then(|s| s.previous) {
+        time::TimeRange::since(previous)
     } else {
-        time::TimeRange::from_relative(&last)?
+        time::TimeRange::resolve(search.start.as_deref(), search.end.as_deref(), &search.last)?
     };
 
     // Create search params
-    let params = SearchParams::new(patterns.clone(), exclude.clone(), limit);
-
-    // Format patterns for display (skip for JSON output)
-    if output_mode != OutputMode::Json {
-        let pattern_display = if patterns.is_empty() {
-            "*".to_string()
-        } else if patterns.len() == 1 {
-            format!("'{}'", patterns[0])
-        } else {
-            patterns
-                .iter()
-                .map(|p| format!("'{}'", p))
-                .collect::<Vec<_>>()
-                .join(" AND ")
-        };
+    let params = SearchParams::new(patterns.clone(), exclude.clone(), search.limit)
+        .with_oldest(search.oldest)
+        .with_stream(search.stream.clone())
+        .with_query(search.query.clone())
+        .with_word(search.word)
+        .with_invert_match(search.invert_match)
+        .with_match_field(search.match_field)
+        .with_starts_with(search.starts_with.clone())
+        .with_ends_with(search.ends_with.clone())
+        .with_split_windows(search.split_windows)
+        .with_deadline(deadline);
 
-        let exclude_display = if exclude.is_empty() {
-            String::new()
-        } else {
-            format!(
-                " {} {}",
-                "NOT".red(),
-                exclude
-                    .iter()
-                    .map(|p| format!("'{}'", p))
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            )
-        };
+    write_banner(&mut std::io::stderr(), search, Banner::LogGroups { groups, time_range })?;
 
-        println!(
-            "{} {}{}  from {} to {}",
-            "Searching".cyan(),
-            pattern_display.yellow(),
-            exclude_display,
-            time_range.start.format("%Y-%m-%d %H:%M:%S"),
-            time_range.end.format("%Y-%m-%d %H:%M:%S"),
-        );
-
-        if groups.is_empty() {
+    // Querying past a group's retention silently returns nothing, so say so up front
+    if search.retention_check {
+        let now = chrono::Utc::now();
+        // Skipped if it doesn't finish in time
+        let retention = deadline::within(deadline, async { Ok(searcher.retention_days(groups).await) }).await;
+        for (group, retention) in groups.iter().zip(retention.unwrap_or_default()) {
+            let Some(days) = retention.filter(|&days| aws::predates_retention(time_range.start, now, Some(days))) else {
+                continue;
+            };
             eprintln!(
-                "{}",
-                "No log groups specified. Use --groups or configure defaults.".red()
+                "{} {} keeps logs for {} days; nothing before {} can be found",
+                "Warning:".yellow(),
+                group.cyan(),
+                days,
+                (now - chrono::Duration::days(days as i64)).format("%Y-%m-%d %H:%M"),
             );
-            return Ok(());
         }
+    }
 
-        println!("Log groups: {}\n", groups.join(", ").dimmed());
+    if search.explain_query && !confirm_scan_estimate(searcher, search, &params, time_range).await? {
+        return Ok(());
     }
 
-    if groups.is_empty() {
-        if output_mode == OutputMode::Json {
-            println!("{{\"error\": \"No log groups specified\"}}");
+    let display_options = display_options(search, groups, time_range)?.with_console_links(console_links(search, time_range, &params));
+
+    // --first/--last-only need every result before printing any
+    if matches!(output_mode, OutputMode::Streaming | OutputMode::JsonStream) && display_options.bounds.is_none() {
+        let mut stats = aws::QueryStats::default();
+        let searches = searcher
+            .search_log_groups_as_completed(groups, &params, time_range.start, time_range.end)
+            .map(|(index, result)| {
+                let entries = result.map(|(entries, group_stats)| {
+                    stats.add(&group_stats);
+                    entries
+                });
+                (index, entries)
+            });
+        let streamed = stream_results(searches, groups, search, &display_options, &mut since_last).await?;
+        if warn_timed_out(streamed.timed_out, groups.len(), "log group") {
+            searcher.stop_abandoned_queries().await;
         }
-        return Ok(());
+        print_summary(search, streamed.shown, groups.len(), "log group", streamed.failed, started);
+        print_stats(search, &stats);
+        return commit_since_last(since_last, streamed.failed > 0);
+    }
+
+    // Search all log groups concurrently, with a spinner on an interactive terminal
+    let spinner = progress::Spinner::start(
+        format!("Searching {} log group{}...", groups.len(), if groups.len() == 1 { "" } else { "s" }),
+        !output_mode.is_json() && !search.quiet,
+    );
+    let aws::SearchResults { groups: results, stats } = searcher
+        .search_log_groups(groups, &params, time_range.start, time_range.end)
+        .await;
+    spinner.finish();
+    if warn_timed_out(count_timed_out(&results), results.len(), "log group") {
+        searcher.stop_abandoned_queries().await;
+    }
+
+    // Missing credentials fail every group the same way; report it once instead of as "no results",
+    // and fail the run so scripts see a non-zero exit code
+    if let Some(e) = results
+        .iter()
+        .filter_map(|r| r.as_ref().err())
+        .find(|e| aws::error_kind(e) == aws::AwsErrorKind::Credentials)
+    {
+        if output_mode.is_json() {
+            // In place of the results, so --output-file consumers see it too
+            let mut sink = open_sink(search)?;
+            output::write_json_error(&mut sink, &format!("{:#}", e), Some(aws::AwsErrorKind::Credentials.as_str()), output_mode)?;
+            sink.finish()?;
+        }
+        bail!("{:#}\nCheck --profile, or refresh your login (e.g. `aws sso login`).", e);
     }
 
-    // Search all log groups concurrently
     let mut all_entries = Vec::new();
+    let mut failed = 0;
 
-    match output_mode {
-        OutputMode::Streaming => {
-            // For streaming, search sequentially to show results as they come
-            let results = searcher
-                .search_log_groups(&groups, &params, time_range.start, time_range.end)
-                .await;
-
-            for (group, result) in groups.iter().zip(results) {
-                println!("{} {}...", "Querying".dimmed(), group.cyan());
-                match result {
-                    Ok(entries) => {
-                        for entry in entries {
-                            output::print_entry(&entry);
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("{} {}: {}", "Error".red(), group, e);
-                    }
+    // For interleaved/grouped/json, collect all results first
+    for (group, result) in groups.iter().zip(results) {
+        match result {
+            Ok(entries) => all_entries.extend(track_since_last(entries, &mut since_last)),
+            Err(e) => {
+                failed += 1;
+                if !output_mode.is_json() {
+                    eprintln!("{} {}: {}", "Error".red(), group, e);
                 }
             }
         }
-        _ => {
-            // For interleaved/grouped/json, collect all results first
-            let results = searcher
-                .search_log_groups(&groups, &params, time_range.start, time_range.end)
-                .await;
-
-            for (group, result) in groups.iter().zip(results) {
-                match result {
-                    Ok(entries) => all_entries.extend(entries),
-                    Err(e) => {
-                        if output_mode != OutputMode::Json {
-                            eprintln!("{} {}: {}", "Error".red(), group, e);
-                        }
-                    }
-                }
+    }
+
+    // Only now, so errors and progress don't draw over a pager
+    let mut sink = open_sink(search)?;
+    let shown = output::display_results(all_entries, &display_options, &mut sink)?;
+    sink.finish()?;
+    print_summary(search, shown, groups.len(), "log group", failed, started);
+    print_stats(search, &stats);
+    commit_since_last(since_last, failed > 0)
+}
+
+/// `batch`: run every search in a batch file through the usual search path, each writing to its own
+/// file; without --output-dir those are JSON files in a scratch directory, combined onto stdout
+async fn run_batch(cli: &Cli, args: &cli::BatchArgs, config: &Config) -> Result<()> {
+    let text = std::fs::read_to_string(&args.file).with_context(|| format!("Failed to read batch file: {}", args.file))?;
+    let searches = batch::resolve_batch(cli, config, &batch::parse_batch(&text)?)?;
+
+    let combined = args.output_dir.is_none();
+    let dir = match &args.output_dir {
+        Some(dir) => std::path::PathBuf::from(dir),
+        None => std::env::temp_dir().join(format!("log-hound-batch-{}", std::process::id())),
+    };
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let jobs = searches
+        .into_iter()
+        .map(|(name, search)| batch::prepare(name, search, &dir, combined))
+        .collect();
+
+    let outcomes = batch::run_all(jobs, args.concurrency as usize, |search| async move {
+        match search.source {
+            LogSource::Cloudwatch => {
+                let searcher = aws::MultiRegionSearcher::new(search.profile.clone(), search.region.clone())
+                    .with_region_profiles(config.region_profiles.clone())
+                    .with_endpoint_url(cli.endpoint_url.clone());
+                run_cloudwatch_search(&searcher, &search).await
             }
+            LogSource::Kamal => run_kamal_search(&search).await,
+        }
+    })
+    .await;
+    // A search that returns without writing its file has nothing to show either
+    let outcomes: Vec<_> = outcomes
+        .into_iter()
+        .map(|(job, outcome)| {
+            let outcome = outcome.and_then(|()| match job.path.exists() {
+                true => Ok(()),
+                false => bail!("the search wrote no results"),
+            });
+            (job, outcome)
+        })
+        .collect();
 
-            output::display_results(all_entries, &output_mode);
+    let mut failed = outcomes.iter().filter(|(_, outcome)| outcome.is_err()).count();
+    for (job, outcome) in &outcomes {
+        match outcome {
+            Ok(()) if combined => {}
+            Ok(()) => eprintln!("{} {} → {}", "Wrote".green(), job.name, job.path.display()),
+            Err(e) => eprintln!("{} {}: {:#}", "Error".red(), job.name, e),
         }
     }
+    if combined {
+        let document = batch::combine(&outcomes);
+        // Searches can also report errors (e.g. missing credentials) as their JSON output
+        failed = document.as_array().map_or(0, |sections| sections.iter().filter(|s| s.get("error").is_some()).count());
+        println!("{}", serde_json::to_string_pretty(&document)?);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 
+    if failed > 0 {
+        bail!("{} of {} searches failed", failed, outcomes.len());
+    }
     Ok(())
 }
 
-async fn run_kamal_search(
-    deploy_path: &str,
-    patterns: Vec<String>,
-    exclude: Vec<String>,
-    last: String,
-    output_mode: OutputMode,
-    limit: usize,
-    follow: bool,
-) -> Result<()> {
+/// --explain-query: run a limit-1 probe over the end of the range, print the data the whole range
+/// would scan at the same rate, and ask whether to go ahead. False means don't search
+async fn confirm_scan_estimate(
+    searcher: &aws::MultiRegionSearcher,
+    search: &ResolvedSearch,
+    params: &SearchParams,
+    time_range: time::TimeRange,
+) -> Result<bool> {
+    let probed = time_range.tail(chrono::Duration::minutes(aws::PROBE_WINDOW_MINUTES));
+    let probe_params = SearchParams { limit: 1, split_windows: false, ..params.clone() };
+    let results = searcher.search_log_groups(&search.groups, &probe_params, probed.start, probed.end).await;
+    if let Some(e) = results.groups.iter().find_map(|result| result.as_ref().err()) {
+        bail!("Probe query failed: {}", e);
+    }
+
+    let estimate = results.stats.extrapolate(&probed, &time_range);
+    eprintln!(
+        "{} {} scanned in the last {} min; the full range would scan about {} (~${:.4})",
+        "Estimate:".cyan(),
+        display::format_bytes(results.stats.bytes_scanned),
+        (probed.end - probed.start).num_minutes(),
+        display::format_bytes(estimate.bytes_scanned).yellow(),
+        estimate.estimated_cost_usd(),
+    );
+
+    if search.yes {
+        return Ok(true);
+    }
+    if !std::io::stdin().is_terminal() {
+        bail!("--explain-query asks before searching; pass --yes to search without a terminal");
+    }
+    eprint!("Run the search? [y/N] ");
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+async fn run_kamal_search(search: &ResolvedSearch) -> Result<()> {
     use kamal::KamalSearcher;
 
+    let started = Instant::now();
+    let deadline = search_deadline(search)?;
+    let patterns = &search.patterns;
+    let exclude = &search.exclude;
+    let last = &search.last;
+    let output_mode = &search.output;
+    let follow = search.follow;
+    // --env picks deploy.{env}.yml; without it or -d, use the project's deploy.yml from config/, .kamal/ or the root
+    let deploy_path = match (&search.env, &search.deploy_file) {
+        (Some(env), _) => kamal::destination_deploy_file(std::path::Path::new("."), env)?,
+        (None, Some(path)) => std::path::PathBuf::from(path),
+        (None, None) => kamal::default_deploy_file(std::path::Path::new(".")),
+    };
+
     // Load Kamal configuration
-    let searcher = KamalSearcher::from_file(deploy_path)?;
-
-    // Convert time range to Docker --since format
-    let since = time::to_docker_since(&last)?;
-
-    // Format patterns for display (skip for JSON output)
-    if output_mode != OutputMode::Json {
-        let pattern_display = if patterns.is_empty() {
-            "*".to_string()
-        } else if patterns.len() == 1 {
-            format!("'{}'", patterns[0])
-        } else {
-            patterns
-                .iter()
-                .map(|p| format!("'{}'", p))
-                .collect::<Vec<_>>()
-                .join(" AND ")
-        };
+    let searcher = KamalSearcher::from_file(&deploy_path)?
+        .without_servers(&search.exclude_servers)?
+        .with_container_pattern(search.container_pattern.clone())
+        .with_container_id(search.container.clone())?
+        .with_primary_only(search.primary_only);
 
-        let exclude_display = if exclude.is_empty() {
-            String::new()
-        } else {
-            format!(
-                " {} {}",
-                "NOT".red(),
-                exclude
-                    .iter()
-                    .map(|p| format!("'{}'", p))
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            )
-        };
+    // Resume after the previous run's newest entry if requested
+    let mut since_last = if search.since_last && !follow {
+        Some(SinceLast::load(search)?)
+    } else {
+        None
+    };
 
-        if follow {
-            println!(
-                "{} {}{} on {}",
-                "Following".cyan().bold(),
-                pattern_display.yellow(),
-                exclude_display,
-                searcher.servers().first().unwrap_or(&"unknown".to_string()).green(),
-            );
-            println!("Service: {} | Press Ctrl+C to stop\n", searcher.service().green());
-        } else {
-            println!(
-                "{} {}{} (last {})",
-                "Searching".cyan(),
-                pattern_display.yellow(),
-                exclude_display,
-                last.cyan(),
-            );
-            println!(
-                "Service: {} | Servers: {}\n",
-                searcher.service().green(),
-                searcher.servers().join(", ").dimmed()
-            );
+    // Convert time range to Docker --since format, keeping the window it covers for JSON output
+    let (since, time_range) = match since_last.as_ref().and_then(|s| s.previous) {
+        Some(previous) => (previous.to_rfc3339_opts(SecondsFormat::Nanos, true), time::TimeRange::since(previous)),
+        None if search.since_deploy => {
+            let deployed = deadline::within(deadline, searcher.deployed_at()).await?;
+            (deployed.to_rfc3339_opts(SecondsFormat::Nanos, true), time::TimeRange::since(deployed))
         }
-    }
+        None => (time::to_docker_since(last)?, time::TimeRange::from_relative(last)?),
+    };
+
+    let banner = if follow {
+        Banner::Following { service: searcher.service(), server: searcher.servers().first().map_or("unknown", String::as_str) }
+    } else {
+        let window = match search.since_deploy {
+            true => format!("since deploy at {}", time_range.start.format("%Y-%m-%d %H:%M:%S")),
+            false => format!("last {}", last),
+        };
+        Banner::Servers { service: searcher.service(), servers: searcher.searched_servers(), window }
+    };
+    write_banner(&mut std::io::stderr(), search, banner)?;
 
     // Create search params
     let params = KamalSearchParams {
-        patterns,
-        exclude,
-        limit,
+        patterns: patterns.clone(),
+        exclude: exclude.clone(),
+        limit: search.limit as usize,
         since: Some(since),
-        follow,
+        oldest: search.oldest,
+        word: search.word,
+        invert_match: search.invert_match,
+        match_field: search.match_field,
+        starts_with: search.starts_with.clone(),
+        ends_with: search.ends_with.clone(),
+        follow_context: search.follow_context,
+        follow_idle_timeout: search.follow_idle_timeout.as_deref().map(time::parse_timeout).transpose()?,
+        deadline,
+        tail_multiplier: search.tail_multiplier,
     };
 
-    // Follow mode - stream logs in real-time
+    let server_keys: Vec<String> = searcher.servers().iter().map(|s| searcher.group_label(s)).collect();
+    let display_options = display_options(search, &server_keys, time_range)?;
+
+    // Follow mode - stream logs in real-time until Ctrl+C
     if follow {
-        return searcher.follow_logs(&params).await;
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let ctrl_c_flag = stop_flag.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                ctrl_c_flag.store(true, Ordering::Relaxed);
+            }
+        });
+
+        let show_status = !output_mode.is_json() && !search.quiet;
+        let followed = searcher.follow_logs(&params, &display_options, stop_flag, show_status).await?;
+        if !output_mode.is_json() && !search.quiet {
+            eprintln!();
+            if followed.idle {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "Stopped following: no new lines for {}",
+                        search.follow_idle_timeout.as_deref().unwrap_or_default()
+                    )
+                    .yellow()
+                );
+            }
+            eprintln!("{}", format!("Followed {} lines", followed.lines).dimmed());
+        }
+        return Ok(());
     }
 
-    // Search all servers
-    let mut all_entries = Vec::new();
 
-    match output_mode {
-        OutputMode::Streaming => {
-            let results = searcher.search_logs(&params).await;
-            for (server, result) in searcher.servers().iter().zip(results) {
-                println!("{} {}...", "Querying".dimmed(), server.cyan());
-                match result {
-                    Ok(entries) => {
-                        for entry in entries {
-                            output::print_entry(&entry);
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("{} {}: {}", "Error".red(), server, e);
-                    }
+    // --first/--last-only need every result before printing any
+    let servers = searcher.searched_servers();
+    if matches!(output_mode, OutputMode::Streaming | OutputMode::JsonStream) && display_options.bounds.is_none() {
+        let searches = searcher.search_logs_as_completed(&params);
+        let streamed = stream_results(searches, servers, search, &display_options, &mut since_last).await?;
+        warn_timed_out(streamed.timed_out, servers.len(), "server");
+        print_summary(search, streamed.shown, servers.len(), "server", streamed.failed, started);
+        return commit_since_last(since_last, streamed.failed > 0);
+    }
+
+    // Search all servers, with a spinner on an interactive terminal
+    let spinner = progress::Spinner::start(
+        format!("Searching {} server{}...", servers.len(), if servers.len() == 1 { "" } else { "s" }),
+        !output_mode.is_json() && !search.quiet,
+    );
+    let results = searcher.search_logs(&params).await;
+    spinner.finish();
+    warn_timed_out(count_timed_out(&results), results.len(), "server");
+
+    let mut all_entries = Vec::new();
+    let mut failed = 0;
+    for (server, result) in servers.iter().zip(results) {
+        match result {
+            Ok(entries) => all_entries.extend(track_since_last(entries, &mut since_last)),
+            Err(e) => {
+                failed += 1;
+                if !output_mode.is_json() {
+                    eprintln!("{} {}: {}", "Error".red(), server, e);
                 }
             }
         }
-        _ => {
-            let results = searcher.search_logs(&params).await;
-            for (server, result) in searcher.servers().iter().zip(results) {
-                match result {
-                    Ok(entries) => all_entries.extend(entries),
-                    Err(e) => {
-                        if output_mode != OutputMode::Json {
-                            eprintln!("{} {}: {}", "Error".red(), server, e);
-                        }
-                    }
+    }
+
+    // Only now, so errors and progress don't draw over a pager
+    let mut sink = open_sink(search)?;
+    let shown = output::display_results(all_entries, &display_options, &mut sink)?;
+    sink.finish()?;
+    print_summary(search, shown, servers.len(), "server", failed, started);
+    commit_since_last(since_last, failed > 0)
+}
+
+/// The --timeout deadline, counted from the start of the search
+fn search_deadline(search: &ResolvedSearch) -> Result<Option<deadline::Deadline>> {
+    let timeout = search.timeout.as_deref().map(time::parse_timeout).transpose()?;
+    Ok(timeout.map(deadline::Deadline::after))
+}
+
+/// How many sources --timeout cut off
+fn count_timed_out<T>(results: &[Result<T>]) -> usize {
+    results.iter().filter(|r| r.as_ref().is_err_and(deadline::is_timeout)).count()
+}
+
+/// Warn on stderr (even with JSON output) when --timeout cut some sources off, since their results
+/// are missing; returns whether any were
+fn warn_timed_out(timed_out: usize, sources: usize, source_kind: &str) -> bool {
+    if timed_out > 0 {
+        eprintln!(
+            "{} --timeout passed with {} of {} {}s unfinished; showing partial results",
+            "Warning:".yellow(),
+            timed_out,
+            sources,
+            source_kind,
+        );
+    }
+    timed_out > 0
+}
+
+/// What `stream_results` wrote
+struct Streamed {
+    shown: usize,
+    failed: usize,
+    timed_out: usize,
+}
+
+/// Streaming and JSON stream output: write each source's entries as soon as its search finishes,
+/// rather than after the slowest one. `searches` yields results by their index in `sources`
+async fn stream_results(
+    searches: impl futures::Stream<Item = (usize, Result<Vec<LogEntry>>)>,
+    sources: &[String],
+    search: &ResolvedSearch,
+    display_options: &output::DisplayOptions,
+    since_last: &mut Option<SinceLast>,
+) -> Result<Streamed> {
+    let mut searches = std::pin::pin!(searches);
+    let mut sink = open_sink(search)?;
+    // Opened with the first result, so a credentials error can take the document's place
+    let json_stream = search.output == OutputMode::JsonStream;
+    let mut json: Option<output::JsonStreamWriter> = None;
+
+    let mut streamed = Streamed { shown: 0, failed: 0, timed_out: 0 };
+    while let Some((index, result)) = searches.next().await {
+        let entries = match result {
+            Ok(entries) => entries,
+            // Missing credentials fail every source the same way; report it once and fail the run
+            Err(e) if aws::error_kind(&e) == aws::AwsErrorKind::Credentials => {
+                if let Some(json) = json.take() {
+                    // Entries already written still end up in a valid document
+                    json.finish(&mut sink)?;
+                } else if json_stream {
+                    output::write_json_error(&mut sink, &format!("{:#}", e), Some(aws::AwsErrorKind::Credentials.as_str()), &search.output)?;
                 }
+                sink.finish()?;
+                bail!("{:#}\nCheck --profile, or refresh your login (e.g. `aws sso login`).", e);
             }
+            Err(e) => {
+                streamed.failed += 1;
+                streamed.timed_out += usize::from(deadline::is_timeout(&e));
+                eprintln!("{} {}: {}", "Error".red(), sources[index], e);
+                continue;
+            }
+        };
+        if json_stream && json.is_none() {
+            json = Some(output::JsonStreamWriter::begin(&mut sink, display_options.time_range)?);
+        }
+        for entry in track_since_last(entries, since_last) {
+            if !display_options.shows(&entry) {
+                continue;
+            }
+            match &mut json {
+                Some(json) => json.write(&mut sink, &entry)?,
+                None => output::write_entry(&mut sink, &entry, display_options)?,
+            }
+            streamed.shown += 1;
+        }
+        // So each source's entries show up now rather than when the buffer fills
+        sink.flush()?;
+    }
+
+    if json_stream {
+        let json = match json {
+            Some(json) => json,
+            None => output::JsonStreamWriter::begin(&mut sink, display_options.time_range)?,
+        };
+        json.finish(&mut sink)?;
+    }
+    sink.finish()?;
+    Ok(streamed)
+}
+
+/// Where a search looks, for the lines announcing it
+enum Banner<'a> {
+    /// CloudWatch log groups over a time range
+    LogGroups { groups: &'a [String], time_range: time::TimeRange },
+    /// Kamal servers over the last duration or since the deploy
+    Servers { service: &'a str, servers: &'a [String], window: String },
+    /// The Kamal server whose container is followed
+    Following { service: &'a str, server: &'a str },
+}
+
+/// Header on stderr before human-readable results: the patterns and excludes, then where and when
+/// the search looks. Nothing with --quiet or JSON output
+fn write_banner(out: &mut dyn Write, search: &ResolvedSearch, banner: Banner) -> std::io::Result<()> {
+    if search.output.is_json() || search.quiet {
+        return Ok(());
+    }
+
+    let patterns = &search.patterns;
+    let pattern_display = if patterns.is_empty() {
+        "*".to_string()
+    } else if patterns.len() == 1 {
+        format!("'{}'", patterns[0])
+    } else {
+        patterns
+            .iter()
+            .map(|p| format!("'{}'", p))
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    };
+    let pattern_display = if search.invert_match && !patterns.is_empty() {
+        format!("NOT ({})", pattern_display)
+    } else {
+        pattern_display
+    };
+
+    let exclude_display = if search.exclude.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " {} {}",
+            "NOT".red(),
+            search
+                .exclude
+                .iter()
+                .map(|p| format!("'{}'", p))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
 
-            output::display_results(all_entries, &output_mode);
+    match banner {
+        Banner::LogGroups { groups, time_range } => {
+            writeln!(
+                out,
+                "{} {}{}  from {} to {}",
+                "Searching".cyan(),
+                pattern_display.yellow(),
+                exclude_display,
+                time_range.start.format("%Y-%m-%d %H:%M:%S"),
+                time_range.end.format("%Y-%m-%d %H:%M:%S"),
+            )?;
+            writeln!(out, "Log groups: {}\n", groups.join(", ").dimmed())
+        }
+        Banner::Servers { service, servers, window } => {
+            writeln!(out, "{} {}{} ({})", "Searching".cyan(), pattern_display.yellow(), exclude_display, window.cyan())?;
+            writeln!(out, "Service: {} | Servers: {}\n", service.green(), servers.join(", ").dimmed())
         }
+        Banner::Following { service, server } => {
+            writeln!(out, "{} {}{} on {}", "Following".cyan().bold(), pattern_display.yellow(), exclude_display, server.green())?;
+            writeln!(out, "Service: {} | Press Ctrl+C to stop\n", service.green())
+        }
+    }
+}
+
+/// Footer on stderr after human-readable results: counts, failures and how long it took
+fn print_summary(search: &ResolvedSearch, results: usize, sources: usize, source_kind: &'static str, failed: usize, started: Instant) {
+    if search.quiet || search.output.is_json() {
+        return;
+    }
+    let summary = output::SearchSummary { results, sources, source_kind, failed, elapsed: started.elapsed() };
+    let _ = output::write_summary(&mut std::io::stderr(), &summary);
+}
+
+/// `--stats`: data scanned and what it cost, on stderr so JSON on stdout stays parseable
+fn print_stats(search: &ResolvedSearch, stats: &aws::QueryStats) {
+    if !search.stats {
+        return;
+    }
+    eprintln!(
+        "{} {} scanned, {} of {} records matched (~${:.4})",
+        "Stats:".dimmed(),
+        display::format_bytes(stats.bytes_scanned),
+        stats.records_matched,
+        stats.records_scanned,
+        stats.estimated_cost_usd(),
+    );
+}
+
+/// How results are filtered and shown, with `keys` (log groups or servers) each getting a color
+fn display_options(search: &ResolvedSearch, keys: &[String], time_range: time::TimeRange) -> Result<output::DisplayOptions> {
+    Ok(output::DisplayOptions::new(search.output.clone())
+        .with_group_colors(keys)
+        .with_correlator(search.correlate.as_deref())?
+        .with_where(&search.where_clauses)?
+        .with_exclude_where(&search.exclude_where_clauses)?
+        .with_timestamp_bounds(search.min_timestamp.as_deref(), search.max_timestamp.as_deref())?
+        .with_exclude_ranges(&search.exclude_ranges)?
+        .with_time_range(time_range)
+        .with_limit_total(search.limit_total)
+        .with_sample(search.sample)
+        .with_page(search.page)
+        .with_time_format(search.time_format.as_deref())?
+        .with_bounds(search.first, search.last_only)
+        .with_pretty_json(search.pretty_json)
+        .with_show_stream(search.show_stream)
+        .with_region_aliases(search.region_aliases.clone())
+        .with_flatten_newlines(search.flatten_newlines)
+        .with_group_by(search.group_by)
+        .with_json_nested(search.json_nested)
+        .with_delimiter(search.delimiter.as_deref())?
+        .with_merge_duplicates(search.merge_duplicates)
+        .with_dedupe_window(search.dedupe_window.as_deref())?
+        .with_stream_contains(search.stream_contains.clone())
+        .with_exclude_streams(search.exclude_streams.clone())
+        .with_quiet(search.quiet)
+        .with_oldest(search.oldest))
+}
+
+/// Console query for `--hyperlinks`, only when results go to a terminal that can show the links
+fn console_links(search: &ResolvedSearch, time_range: time::TimeRange, params: &SearchParams) -> Option<aws::ConsoleQuery> {
+    let to_terminal = search.output_file.is_none() && !search.copy && std::io::stdout().is_terminal();
+    if !search.hyperlinks || !to_terminal {
+        return None;
+    }
+
+    let default_region = search
+        .region
+        .clone()
+        .or_else(|| std::env::var("AWS_REGION").ok())
+        .or_else(|| std::env::var("AWS_DEFAULT_REGION").ok());
+    Some(aws::ConsoleQuery { default_region, time_range, query: params.insights_query() })
+}
+
+/// Open the results sink for `--output-file`, `--copy` or `--pager` (stdout otherwise)
+fn open_sink(search: &ResolvedSearch) -> Result<OutputSink> {
+    let sink = if search.copy {
+        OutputSink::clipboard()
+    } else if search.output_file.is_none()
+        // Following never ends, so only page it when asked to explicitly
+        && (!search.follow || search.pager == PagerMode::Always)
+        && OutputSink::wants_pager(search.pager, &search.output, std::io::stdout().is_terminal())
+    {
+        OutputSink::pager()
+    } else {
+        OutputSink::open(search.output_file.as_deref(), search.gzip)?
+    };
+    if !sink.is_terminal_output() {
+        // No ANSI color codes in files or on the clipboard
+        colored::control::set_override(false);
+    }
+    Ok(sink)
+}
+
+/// Drop entries already seen by the previous `--since-last` run and record the rest
+fn track_since_last(mut entries: Vec<LogEntry>, since_last: &mut Option<SinceLast>) -> Vec<LogEntry> {
+    if let Some(tracker) = since_last {
+        entries.retain(|e| tracker.is_new(e));
+        for entry in &entries {
+            tracker.observe(entry);
+        }
+    }
+    entries
+}
+
+/// Save the `--since-last` checkpoint, skipping it when any source failed
+/// so the next run doesn't miss lines from the failed source
+fn commit_since_last(since_last: Option<SinceLast>, failed: bool) -> Result<()> {
+    match since_last {
+        Some(tracker) if !failed => tracker.commit(),
+        _ => Ok(()),
+    }
+}
+
+/// Start an S3 export task for one log group and, unless --no-wait, poll it to completion
+/// A region (or profile@region) prefix on the group wins over the global profile and region
+async fn run_export(
+    args: &cli::ExportArgs,
+    profile: Option<&str>,
+    region: Option<&str>,
+    endpoint_url: Option<&str>,
+) -> Result<()> {
+    let group = aws::RegionalLogGroup::parse(&args.group);
+    let destination = aws::S3Destination::parse(&args.destination)?;
+    let time_range = time::TimeRange::resolve(args.start.as_deref(), args.end.as_deref(), &args.last)?;
+
+    let client = aws::create_client(
+        group.profile.as_deref().or(profile),
+        group.region.as_deref().or(region),
+        endpoint_url,
+    )
+    .await?;
+
+    eprintln!(
+        "{} {} ({} → {}) to {}",
+        "Exporting".cyan(),
+        group.log_group.green(),
+        time_range.start.format("%Y-%m-%d %H:%M UTC"),
+        time_range.end.format("%Y-%m-%d %H:%M UTC"),
+        destination.to_string().cyan(),
+    );
+    let task_id = aws::start_export(&client, &group.log_group, &time_range, &destination, args.stream_prefix.as_deref()).await?;
+    eprintln!("Export task: {}", task_id.cyan());
+
+    if !args.no_wait {
+        aws::wait_for_export(&client, &task_id, |status| eprintln!("  {}", status.to_lowercase().dimmed())).await?;
+        eprintln!("{}", "Export complete".green());
+    }
+    // The location on stdout, for scripts; objects appear there as the task runs
+    println!("{}", destination.task_url(&task_id));
+    Ok(())
+}
+
+async fn fetch_context(searcher: &aws::LogSearcher, ptr: &str, json: bool) -> Result<()> {
+    let record = searcher.get_log_record(ptr).await?;
+    if record.is_empty() {
+        bail!("No log record found for that pointer (was it searched in another region?)");
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&record)?);
+        return Ok(());
     }
 
+    // Fields sorted by name, with the message last since it's usually the longest
+    let width = record.keys().map(|k| k.len()).max().unwrap_or(0);
+    for (name, value) in record.iter().filter(|(name, _)| *name != "@message") {
+        println!("{}  {}", format!("{:width$}", name).cyan(), value);
+    }
+    if let Some(message) = record.get("@message") {
+        println!("{}  {}", format!("{:width$}", "@message").cyan(), message);
+    }
     Ok(())
 }
 
@@ -463,3 +972,57 @@ async fn list_groups(searcher: &aws::LogSearcher, prefix: Option<String>) -> Res
 
     Ok(())
 }
+
+/// Print AWS profiles with their regions, marking the one searches would use
+fn list_profiles(profiles: &[aws::AwsProfile], active: &str) {
+    if profiles.is_empty() {
+        println!("{}", "No AWS profiles found in ~/.aws/config or ~/.aws/credentials.".yellow());
+        return;
+    }
+
+    println!("{} AWS profiles:\n", profiles.len().to_string().cyan());
+    let width = profiles.iter().map(|p| p.name.len()).max().unwrap_or(0);
+    for profile in profiles {
+        let marker = if profile.name == active { "*".green().to_string() } else { " ".to_string() };
+        let files: Vec<&str> = [(profile.in_config, "config"), (profile.in_credentials, "credentials")]
+            .into_iter()
+            .filter_map(|(found, file)| found.then_some(file))
+            .collect();
+        println!(
+            "{} {:<width$}  {:<14}  {}",
+            marker,
+            profile.name,
+            profile.region.as_deref().unwrap_or("-"),
+            files.join(", ").dimmed(),
+            width = width
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_banner_is_skipped_when_quiet() {
+        colored::control::set_override(false);
+        let groups = vec!["app/prod".to_string(), "api/prod".to_string()];
+        let start = Utc.with_ymd_and_hms(2026, 1, 23, 5, 0, 0).unwrap();
+        let time_range = time::TimeRange { start, end: start + chrono::Duration::hours(1) };
+        let render = |search: &ResolvedSearch| {
+            let mut out = Vec::new();
+            write_banner(&mut out, search, Banner::LogGroups { groups: &groups, time_range }).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let search = ResolvedSearch { patterns: vec!["ERROR".to_string()], exclude: vec!["health".to_string()], ..ResolvedSearch::default() };
+        assert_eq!(
+            render(&search),
+            "Searching 'ERROR' NOT 'health'  from 2026-01-23 05:00:00 to 2026-01-23 06:00:00\nLog groups: app/prod, api/prod\n\n"
+        );
+
+        assert_eq!(render(&ResolvedSearch { quiet: true, ..search.clone() }), "");
+        assert_eq!(render(&ResolvedSearch { output: OutputMode::Json, ..search }), "");
+    }
+}
@@ -1,110 +1,1219 @@
-use crate::aws::LogEntry;
-use crate::cli::OutputMode;
+use crate::aws::{keep_window, ConsoleQuery, LogEntry};
+use crate::cli::{GroupBy, OutputMode};
+use crate::display::{self, region_label, shorten_group, shorten_stream, ColorMap};
+use crate::level;
+use crate::time::{parse_datetime, parse_duration, validate_time_format, TimeRange};
+use crate::where_clause::WhereClause;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
 use colored::Colorize;
-use serde::Serialize;
+use regex::Regex;
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
 
 /// JSON output wrapper for structured results
 #[derive(Serialize)]
 struct JsonOutput {
     total: usize,
+    /// Set when --limit-total or --sample cut the results
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+    /// The window actually searched, so results of a relative --last stay reproducible
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_range: Option<TimeRange>,
+    #[serde(serialize_with = "with_ids")]
     results: Vec<LogEntry>,
 }
 
-/// Format and display log entries based on the selected output mode
-pub fn display_results(entries: Vec<LogEntry>, mode: &OutputMode) {
+/// JSON output with results nested by region, then log group (--json-nested)
+#[derive(Serialize)]
+struct NestedJsonOutput {
+    total: usize,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_range: Option<TimeRange>,
+    #[serde(serialize_with = "nested_with_ids")]
+    regions: BTreeMap<String, BTreeMap<String, Vec<LogEntry>>>,
+}
+
+/// A log entry as written in JSON output, led by a stable id for deduplicating across runs
+/// and the normalized level detected in the message, when there is one
+#[derive(Serialize)]
+struct JsonEntry<'a> {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    level: Option<&'static str>,
+    #[serde(flatten)]
+    entry: &'a LogEntry,
+}
+
+impl<'a> JsonEntry<'a> {
+    fn new(entry: &'a LogEntry) -> Self {
+        Self { id: entry_id(entry), level: level::detect(&entry.message), entry }
+    }
+}
+
+/// Stable id of an entry: FNV-1a over (timestamp, log group, region, message), as 16 hex digits
+/// The same line fetched by different runs (or builds) gets the same id
+fn entry_id(entry: &LogEntry) -> String {
+    let timestamp = entry.timestamp.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true);
+    let fields = [
+        timestamp.as_str(),
+        entry.log_group.as_str(),
+        entry.region.as_deref().unwrap_or_default(),
+        entry.message.as_str(),
+    ];
+
+    // 0xff never occurs in UTF-8, so it separates fields unambiguously
+    let bytes: Vec<u8> = fields.iter().flat_map(|field| field.bytes().chain(std::iter::once(0xff))).collect();
+    format!("{:016x}", crate::state::stable_hash(&bytes))
+}
+
+/// Serialize entries with their ids, computed here so LogEntry itself stays light
+fn with_ids<S: Serializer>(entries: &[LogEntry], serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_seq(entries.iter().map(JsonEntry::new))
+}
+
+fn nested_with_ids<S: Serializer>(
+    regions: &BTreeMap<String, BTreeMap<String, Vec<LogEntry>>>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_map(regions.iter().map(|(region, groups)| {
+        let groups: BTreeMap<&String, Vec<JsonEntry>> = groups
+            .iter()
+            .map(|(group, entries)| (group, entries.iter().map(JsonEntry::new).collect()))
+            .collect();
+        (region, groups)
+    }))
+}
+
+/// Region key for entries without a region in nested JSON and --group-by region
+const NO_REGION: &str = "default";
+
+/// Section for entries without a log stream in --group-by stream
+const NO_STREAM: &str = "(no stream)";
+
+/// JSON output with results nested under each correlation id
+#[derive(Serialize)]
+struct CorrelatedJsonOutput {
+    total: usize,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_range: Option<TimeRange>,
+    requests: Vec<CorrelatedGroup>,
+}
+
+/// Entries sharing one correlation id, ordered by time
+#[derive(Debug, Serialize)]
+struct CorrelatedGroup {
+    /// None for entries without an id
+    id: Option<String>,
+    #[serde(serialize_with = "with_ids")]
+    results: Vec<LogEntry>,
+}
+
+/// Extracts a request/trace id from log messages
+#[derive(Debug, Clone)]
+pub struct Correlator {
+    regex: Regex,
+}
+
+impl Correlator {
+    /// Build from a field name (`request_id` matches `request_id=abc`, `"request_id":"abc"`, ...)
+    /// or a regex whose `id` named group, first capture group or whole match is the id
+    pub fn new(spec: &str) -> Result<Self> {
+        static FIELD_NAME: std::sync::LazyLock<Regex> =
+            std::sync::LazyLock::new(|| Regex::new(r"^[A-Za-z_][A-Za-z0-9_.-]*$").unwrap());
+
+        let pattern = if FIELD_NAME.is_match(spec) {
+            format!(
+                r#"(?i)["']?\b{}["']?\s*[:=]\s*["']?([^\s"',;}}\]]+)"#,
+                regex::escape(spec)
+            )
+        } else {
+            spec.to_string()
+        };
+
+        let regex = Regex::new(&pattern)
+            .with_context(|| format!("Invalid --correlate pattern '{}'", spec))?;
+        Ok(Self { regex })
+    }
+
+    /// Extract the correlation id from a message, if present
+    pub fn extract(&self, message: &str) -> Option<String> {
+        let caps = self.regex.captures(message)?;
+        caps.name("id")
+            .or_else(|| caps.get(1))
+            .or_else(|| caps.get(0))
+            .map(|m| m.as_str().to_string())
+    }
+}
+
+/// Group entries by correlation id, ordered by each id's first entry
+/// Entries without an id are collected into a final group
+fn correlate(mut entries: Vec<LogEntry>, correlator: &Correlator) -> Vec<CorrelatedGroup> {
+    entries.sort_by_key(|e| e.timestamp);
+
+    let mut groups: Vec<CorrelatedGroup> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut uncorrelated = Vec::new();
+
+    for entry in entries {
+        let Some(id) = correlator.extract(&entry.message) else {
+            uncorrelated.push(entry);
+            continue;
+        };
+        let i = *index.entry(id.clone()).or_insert_with(|| {
+            groups.push(CorrelatedGroup { id: Some(id), results: Vec::new() });
+            groups.len() - 1
+        });
+        groups[i].results.push(entry);
+    }
+
+    if !uncorrelated.is_empty() {
+        groups.push(CorrelatedGroup { id: None, results: uncorrelated });
+    }
+
+    groups
+}
+
+/// Timestamp format in human output unless --time-format is given
+pub const DEFAULT_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
+/// Field separator in CSV output unless --delimiter is given
+pub const DEFAULT_DELIMITER: char = ',';
+
+/// Columns of CSV output, in order
+const CSV_HEADER: [&str; 6] = ["timestamp", "region", "log_group", "log_stream", "level", "message"];
+
+/// Options controlling how results are rendered
+#[derive(Debug, Clone, Default)]
+pub struct DisplayOptions {
+    pub mode: OutputMode,
+    /// Colors for log group prefixes in human output
+    pub colors: ColorMap,
+    /// Correlation id extractor for correlated and JSON output
+    pub correlator: Option<Correlator>,
+    /// Cap on total results after merge/sort (--limit-total)
+    pub limit_total: Option<usize>,
+    /// Indent JSON messages in human output (--pretty-json)
+    pub pretty_json: bool,
+    /// --limit-total keeps the oldest entries instead of the newest (--oldest)
+    pub oldest: bool,
+    /// Include the log stream in human output (--show-stream)
+    pub show_stream: bool,
+    /// Drop entries whose log stream doesn't contain this substring (--stream-contains)
+    pub stream_contains: Option<String>,
+    /// Drop entries whose log stream contains any of these substrings (--exclude-stream)
+    pub exclude_streams: Vec<String>,
+    /// Keep only JSON messages whose fields satisfy every comparison (--where)
+    pub where_clauses: Vec<WhereClause>,
+    /// Drop JSON messages whose fields satisfy any of these comparisons (--exclude-where)
+    pub exclude_where_clauses: Vec<WhereClause>,
+    /// Drop entries before this time (--min-timestamp)
+    pub min_timestamp: Option<DateTime<Utc>>,
+    /// Drop entries after this time (--max-timestamp)
+    pub max_timestamp: Option<DateTime<Utc>>,
+    /// Drop entries inside any of these windows (--exclude-range)
+    pub exclude_ranges: Vec<TimeRange>,
+    /// Print nothing besides results, not even "No matching logs found." on stderr (--quiet)
+    pub quiet: bool,
+    /// Print each message on one line in human output (--flatten-newlines)
+    pub flatten_newlines: bool,
+    /// Section key for grouped output (--group-by)
+    pub group_by: GroupBy,
+    /// Nest JSON results by region and log group (--json-nested)
+    pub json_nested: bool,
+    /// Collapse identical lines found in several log groups (--merge-duplicates-across-groups)
+    pub merge_duplicates: bool,
+    /// Drop repeats of a message within this long of its last occurrence (--dedupe-window)
+    pub dedupe_window: Option<chrono::Duration>,
+    /// Keep an evenly drawn sample of this many results (--sample)
+    pub sample: Option<usize>,
+    /// Show one page of the sorted results (--page/--page-size)
+    pub page: Option<Page>,
+    /// The window searched, reported in JSON output
+    pub time_range: Option<TimeRange>,
+    /// Link log group prefixes to this query in the CloudWatch console (--hyperlinks)
+    pub console_links: Option<ConsoleQuery>,
+    /// strftime pattern for timestamps in human output (--time-format)
+    pub time_format: Option<String>,
+    /// Region labels in the columns view, in place of abbreviations (config region_aliases)
+    pub region_aliases: HashMap<String, String>,
+    /// Show only the earliest/latest entry of each section (--first/--last-only)
+    pub bounds: Option<Bounds>,
+    /// Field separator for CSV output (--delimiter); DEFAULT_DELIMITER when unset
+    pub delimiter: Option<char>,
+}
+
+/// Which ends of each section's results --first/--last-only keep
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub first: bool,
+    pub last: bool,
+}
+
+/// A page of sorted results: `number` is 1-based
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Page {
+    pub number: usize,
+    pub size: usize,
+}
+
+impl Page {
+    /// Number of pages for `total` results (at least 1, so an empty result is "page 1/1")
+    fn count(&self, total: usize) -> usize {
+        total.div_ceil(self.size).max(1)
+    }
+
+    /// Index range of this page within `total` results; empty past the last page
+    fn range(&self, total: usize) -> std::ops::Range<usize> {
+        let start = (self.number - 1).saturating_mul(self.size).min(total);
+        start..(start + self.size).min(total)
+    }
+}
+
+impl DisplayOptions {
+    pub fn new(mode: OutputMode) -> Self {
+        Self {
+            mode,
+            ..Self::default()
+        }
+    }
+
+    /// Assign group colors by sorted group key, matching the TUI's listing order
+    pub fn with_group_colors(mut self, keys: &[String]) -> Self {
+        let mut keys = keys.to_vec();
+        keys.sort();
+        self.colors = display::build_color_map(keys);
+        self
+    }
+
+    /// Group results by the id extracted with a `--correlate` field name or regex
+    pub fn with_correlator(mut self, spec: Option<&str>) -> Result<Self> {
+        self.correlator = spec.map(Correlator::new).transpose()?;
+        Ok(self)
+    }
+
+    /// Keep only JSON messages satisfying every `--where` comparison
+    pub fn with_where(mut self, specs: &[String]) -> Result<Self> {
+        self.where_clauses = specs.iter().map(|spec| WhereClause::parse(spec)).collect::<Result<_>>()?;
+        Ok(self)
+    }
+
+    /// Drop JSON messages satisfying any `--exclude-where` comparison
+    pub fn with_exclude_where(mut self, specs: &[String]) -> Result<Self> {
+        self.exclude_where_clauses =
+            specs.iter().map(|spec| WhereClause::parse_flag("--exclude-where", spec)).collect::<Result<_>>()?;
+        Ok(self)
+    }
+
+    /// Clip results to `[min, max]` (either may be open), parsed like --start/--end
+    pub fn with_timestamp_bounds(mut self, min: Option<&str>, max: Option<&str>) -> Result<Self> {
+        self.min_timestamp = min.map(parse_datetime).transpose().context("Invalid --min-timestamp")?;
+        self.max_timestamp = max.map(parse_datetime).transpose().context("Invalid --max-timestamp")?;
+        Ok(self)
+    }
+
+    /// Drop results inside any `<start>..<end>` window
+    pub fn with_exclude_ranges(mut self, specs: &[String]) -> Result<Self> {
+        self.exclude_ranges = specs.iter().map(|spec| TimeRange::parse_span(spec)).collect::<Result<_>>()?;
+        Ok(self)
+    }
+
+    pub fn with_sample(mut self, sample: Option<usize>) -> Self {
+        self.sample = sample;
+        self
+    }
+
+    pub fn with_page(mut self, page: Option<Page>) -> Self {
+        self.page = page;
+        self
+    }
+
+    pub fn with_time_range(mut self, time_range: TimeRange) -> Self {
+        self.time_range = Some(time_range);
+        self
+    }
+
+    pub fn with_console_links(mut self, console_links: Option<ConsoleQuery>) -> Self {
+        self.console_links = console_links;
+        self
+    }
+
+    pub fn with_limit_total(mut self, limit_total: Option<usize>) -> Self {
+        self.limit_total = limit_total;
+        self
+    }
+
+    /// Reduce results to the earliest (`first`) and/or latest (`last`) entry of each section
+    pub fn with_bounds(mut self, first: bool, last: bool) -> Self {
+        self.bounds = (first || last).then_some(Bounds { first, last });
+        self
+    }
+
+    /// Render timestamps in human output with this strftime pattern instead of DEFAULT_TIME_FORMAT
+    pub fn with_time_format(mut self, format: Option<&str>) -> Result<Self> {
+        if let Some(format) = format {
+            validate_time_format(format)?;
+        }
+        self.time_format = format.map(str::to_string);
+        Ok(self)
+    }
+
+    /// An entry's timestamp as shown in human output
+    fn timestamp(&self, entry: &LogEntry) -> String {
+        entry.timestamp.format(self.time_format.as_deref().unwrap_or(DEFAULT_TIME_FORMAT)).to_string()
+    }
+
+    /// Separate CSV fields with a `--delimiter` character; `\t` (or `tab`) stands for a tab
+    pub fn with_delimiter(mut self, spec: Option<&str>) -> Result<Self> {
+        self.delimiter = spec.map(parse_delimiter).transpose()?;
+        Ok(self)
+    }
+
+    pub fn with_pretty_json(mut self, pretty_json: bool) -> Self {
+        self.pretty_json = pretty_json;
+        self
+    }
+
+    pub fn with_oldest(mut self, oldest: bool) -> Self {
+        self.oldest = oldest;
+        self
+    }
+
+    pub fn with_region_aliases(mut self, region_aliases: HashMap<String, String>) -> Self {
+        self.region_aliases = region_aliases;
+        self
+    }
+
+    pub fn with_show_stream(mut self, show_stream: bool) -> Self {
+        self.show_stream = show_stream;
+        self
+    }
+
+    pub fn with_stream_contains(mut self, stream_contains: Option<String>) -> Self {
+        self.stream_contains = stream_contains;
+        self
+    }
+
+    pub fn with_exclude_streams(mut self, exclude_streams: Vec<String>) -> Self {
+        self.exclude_streams = exclude_streams;
+        self
+    }
+
+    pub fn with_quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    pub fn with_flatten_newlines(mut self, flatten_newlines: bool) -> Self {
+        self.flatten_newlines = flatten_newlines;
+        self
+    }
+
+    pub fn with_group_by(mut self, group_by: GroupBy) -> Self {
+        self.group_by = group_by;
+        self
+    }
+
+    pub fn with_json_nested(mut self, json_nested: bool) -> Self {
+        self.json_nested = json_nested;
+        self
+    }
+
+    pub fn with_merge_duplicates(mut self, merge_duplicates: bool) -> Self {
+        self.merge_duplicates = merge_duplicates;
+        self
+    }
+
+    /// Collapse repeats of a message that follow its last occurrence within a `--dedupe-window` duration
+    pub fn with_dedupe_window(mut self, spec: Option<&str>) -> Result<Self> {
+        self.dedupe_window = spec.map(parse_duration).transpose().context("Invalid --dedupe-window")?;
+        Ok(self)
+    }
+
+    /// Whether an entry passes the post-fetch filters (--stream-contains, --exclude-stream, --where)
+    /// Entries without a log stream never match a stream filter and are never excluded
+    pub fn shows(&self, entry: &LogEntry) -> bool {
+        let stream = entry.log_stream.as_deref();
+        let included = match &self.stream_contains {
+            Some(substr) => stream.is_some_and(|s| s.contains(substr.as_str())),
+            None => true,
+        };
+        let excluded = stream.is_some_and(|s| self.exclude_streams.iter().any(|x| s.contains(x.as_str())));
+
+        let in_bounds = self.min_timestamp.is_none_or(|min| entry.timestamp >= min)
+            && self.max_timestamp.is_none_or(|max| entry.timestamp <= max)
+            && !self.exclude_ranges.iter().any(|range| range.contains(entry.timestamp));
+
+        included
+            && !excluded
+            && in_bounds
+            && self.where_clauses.iter().all(|clause| clause.matches(&entry.message))
+            && !self.exclude_where_clauses.iter().any(|clause| clause.matches(&entry.message))
+    }
+}
+
+/// Matches an object key at the start of a line of pretty-printed JSON
+static JSON_KEY: std::sync::LazyLock<Regex> =
+    std::sync::LazyLock::new(|| Regex::new(r#"^(\s*)("(?:[^"\\]|\\.)*")(:.*)$"#).unwrap());
+
+/// Reformat a message that is a single JSON object as an indented block with colored keys
+/// Returns None for anything else, which is printed untouched
+fn pretty_json_message(message: &str) -> Option<String> {
+    let trimmed = message.trim();
+    if !trimmed.starts_with('{') {
+        return None;
+    }
+
+    let value: serde_json::Value = serde_json::from_str(trimmed).ok()?;
+    if !value.is_object() {
+        return None;
+    }
+
+    let pretty = serde_json::to_string_pretty(&value).ok()?;
+    let lines: Vec<String> = pretty
+        .lines()
+        .map(|line| match JSON_KEY.captures(line) {
+            Some(caps) => format!("  {}{}{}", &caps[1], caps[2].cyan(), &caps[3]),
+            None => format!("  {}", line),
+        })
+        .collect();
+
+    Some(lines.join("\n"))
+}
+
+/// Shown in place of line breaks with --flatten-newlines
+const NEWLINE_SEPARATOR: &str = " ⏎ ";
+
+/// Replace line breaks (\r\n, \n, \r) so a message prints on a single line
+fn flatten_newlines(message: &str) -> String {
+    message
+        .trim_end_matches(['\r', '\n'])
+        .replace("\r\n", "\n")
+        .replace(['\r', '\n'], NEWLINE_SEPARATOR)
+}
+
+/// Sort by timestamp and keep only `limit_total` entries (the newest, or the oldest with `oldest`)
+/// Returns whether any entries were dropped
+fn apply_limit_total(entries: &mut Vec<LogEntry>, limit_total: Option<usize>, oldest: bool) -> bool {
+    match limit_total {
+        Some(limit) => keep_window(entries, limit, oldest),
+        None => {
+            entries.sort_by_key(|e| e.timestamp);
+            false
+        }
+    }
+}
+
+/// Seed for --sample, fixed so rerunning a search over the same results draws the same sample
+const SAMPLE_SEED: u64 = 0x5eed_1095_4a3d_0001;
+
+/// Keep `size` entries chosen uniformly at random, in their original order
+/// Selection sampling (Knuth's algorithm S) driven by SplitMix64, so a seed always picks the same entries
+fn sample(entries: Vec<LogEntry>, size: usize, seed: u64) -> Vec<LogEntry> {
+    let total = entries.len();
+    if size >= total {
+        return entries;
+    }
+
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+
+    let mut kept = Vec::with_capacity(size);
+    for (seen, entry) in entries.into_iter().enumerate() {
+        let needed = (size - kept.len()) as u64;
+        let remaining = (total - seen) as u64;
+        if next() % remaining < needed {
+            kept.push(entry);
+        }
+    }
+    kept
+}
+
+/// Collapse entries with the same timestamp and message into the first one, recording the other
+/// log groups in `also_in`. Expects entries sorted by timestamp
+fn merge_duplicates(entries: Vec<LogEntry>) -> Vec<LogEntry> {
+    let mut merged: Vec<LogEntry> = Vec::with_capacity(entries.len());
+    // Start of the run of merged entries sharing the current timestamp
+    let mut run_start = 0;
+
+    for entry in entries {
+        if merged.last().is_none_or(|last| last.timestamp != entry.timestamp) {
+            run_start = merged.len();
+        }
+
+        match merged[run_start..].iter_mut().find(|m| m.message == entry.message) {
+            Some(existing) => {
+                if existing.log_group != entry.log_group && !existing.also_in.contains(&entry.log_group) {
+                    existing.also_in.push(entry.log_group);
+                }
+            }
+            None => merged.push(entry),
+        }
+    }
+
+    merged
+}
+
+/// Drop entries whose message last appeared no more than `window` earlier, so a burst of repeats
+/// shows as its first line and a message recurring after a quiet spell shows again
+/// Every occurrence extends the burst, dropped or not. Expects entries sorted by timestamp
+fn dedupe_within(entries: Vec<LogEntry>, window: chrono::Duration) -> Vec<LogEntry> {
+    let mut last_seen: HashMap<String, DateTime<Utc>> = HashMap::new();
+    entries
+        .into_iter()
+        .filter(|entry| {
+            let repeat = last_seen
+                .insert(entry.message.clone(), entry.timestamp)
+                .is_some_and(|previous| entry.timestamp - previous <= window);
+            !repeat
+        })
+        .collect()
+}
+
+/// Format and write log entries based on the selected output mode
+/// Returns the number of results written
+pub fn display_results(mut entries: Vec<LogEntry>, options: &DisplayOptions, out: &mut dyn Write) -> io::Result<usize> {
+    let mode = &options.mode;
+    entries.retain(|e| options.shows(e));
+    if let Some(bounds) = options.bounds.filter(|_| !entries.is_empty()) {
+        return display_bounds(&entries, bounds, options, out);
+    }
+    let found = entries.len();
+    let limited = apply_limit_total(&mut entries, options.limit_total, options.oldest);
+    if options.merge_duplicates {
+        entries = merge_duplicates(entries);
+    }
+    if let Some(window) = options.dedupe_window {
+        entries = dedupe_within(entries, window);
+    }
+
+    let before_sample = entries.len();
+    if let Some(size) = options.sample {
+        entries = sample(entries, size, SAMPLE_SEED);
+    }
+    let sampled = entries.len() < before_sample;
+    let truncated = limited || sampled;
+
+    // Entries are sorted by now, so pages are stable between runs over the same range
+    let paged = entries.len();
+    if let Some(page) = options.page {
+        let range = page.range(paged);
+        entries.truncate(range.end);
+        entries.drain(..range.start);
+    }
+
     if entries.is_empty() {
-        if *mode == OutputMode::Json {
-            println!("{}", serde_json::to_string_pretty(&JsonOutput {
+        if mode.is_json() {
+            write_json(out, &JsonOutput {
                 total: 0,
+                truncated,
+                time_range: options.time_range,
                 results: vec![],
-            }).unwrap_or_default());
-        } else {
-            println!("{}", "No matching logs found.".yellow());
+            }, mode)?;
+        } else if !options.quiet && !mode.is_bare() {
+            // On stderr, like the summary, so stdout only ever carries results
+            match options.page.filter(|_| paged > 0) {
+                Some(page) => eprintln!(
+                    "{}",
+                    format!("No results on page {} (there are {})", page.number, page.count(paged)).yellow()
+                ),
+                None => eprintln!("{}", "No matching logs found.".yellow()),
+            }
         }
-        return;
+        return Ok(0);
     }
 
+    let shown = entries.len();
     match mode {
-        OutputMode::Interleaved => display_interleaved(entries),
-        OutputMode::Grouped => display_grouped(entries),
-        OutputMode::Json => display_json(entries),
+        OutputMode::Interleaved => display_interleaved(entries, options, out)?,
+        OutputMode::Grouped => display_grouped(entries, options, out)?,
+        OutputMode::Columns => display_columns(entries, options, out)?,
+        OutputMode::Correlated => match &options.correlator {
+            Some(correlator) => display_correlated(entries, correlator, options, out)?,
+            None => display_interleaved(entries, options, out)?,
+        },
+        OutputMode::Json | OutputMode::JsonCompact | OutputMode::JsonStream => match &options.correlator {
+            Some(correlator) => display_correlated_json(entries, correlator, truncated, options, out)?,
+            None if options.json_nested => display_nested_json(entries, truncated, options, out)?,
+            None => display_json(entries, truncated, options, out)?,
+        },
+        OutputMode::Raw => display_raw(entries, options, out)?,
+        OutputMode::Csv => display_csv(entries, options, out)?,
         OutputMode::Streaming => {
             // Streaming mode displays as results arrive (handled differently)
             // When called here, just display interleaved as fallback
-            display_interleaved(entries)
+            display_interleaved(entries, options, out)?
         }
     }
+
+    // Footers go to stderr with the summary, so stdout only ever carries results
+    if sampled && !mode.is_bare() && !options.quiet {
+        eprintln!("\n{}", format!("Showing a sample of {} of {} results (--sample)", shown, before_sample).yellow());
+    }
+
+    if limited && !mode.is_bare() && !options.quiet {
+        eprintln!(
+            "\n{}",
+            format!(
+                "Showing the {} {} of {} results (--limit-total)",
+                if options.oldest { "oldest" } else { "newest" },
+                options.limit_total.unwrap_or(0),
+                found
+            )
+            .yellow()
+        );
+    }
+
+    if let Some(page) = options.page.filter(|_| !mode.is_bare() && !options.quiet) {
+        let range = page.range(paged);
+        let pages = page.count(paged);
+        let next = if page.number < pages { format!("; next: --page {}", page.number + 1) } else { String::new() };
+        eprintln!(
+            "\n{}",
+            format!("Page {}/{} (results {}-{} of {}){}", page.number, pages, range.start + 1, range.end, paged, next).dimmed()
+        );
+    }
+
+    Ok(shown)
+}
+
+/// What a finished search did, for the footer of human-readable output
+#[derive(Debug)]
+pub struct SearchSummary {
+    pub results: usize,
+    /// Log groups or servers queried
+    pub sources: usize,
+    /// "log group" or "server"
+    pub source_kind: &'static str,
+    /// Sources whose query failed
+    pub failed: usize,
+    pub elapsed: std::time::Duration,
+}
+
+/// Write a one-line summary of a finished search (to stderr, after the results)
+pub fn write_summary(out: &mut dyn Write, summary: &SearchSummary) -> io::Result<()> {
+    let plural = |n: usize, word: &str| format!("{} {}{}", n, word, if n == 1 { "" } else { "s" });
+    let failed = if summary.failed > 0 {
+        format!(" ({})", format!("{} failed", summary.failed).red())
+    } else {
+        String::new()
+    };
+
+    writeln!(
+        out,
+        "\n{} {} from {}{} in {:.1}s",
+        "──".dimmed(),
+        plural(summary.results, "result"),
+        plural(summary.sources, summary.source_kind),
+        failed,
+        summary.elapsed.as_secs_f64()
+    )
 }
 
-fn display_interleaved(mut entries: Vec<LogEntry>) {
+fn display_interleaved(mut entries: Vec<LogEntry>, options: &DisplayOptions, out: &mut dyn Write) -> io::Result<()> {
     // Sort all entries by timestamp
-    entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    entries.sort_by_key(|e| e.timestamp);
+
+    if !options.quiet {
+        eprintln!("{} {} results:\n", "Found".green(), entries.len().to_string().cyan());
+    }
+
+    for entry in entries {
+        write_entry(out, &entry, options)?;
+    }
+    Ok(())
+}
 
-    println!(
-        "{} {} results:\n",
-        "Found".green(),
-        entries.len().to_string().cyan()
-    );
+/// Messages alone, oldest first, one per line unless a message spans several (see --flatten-newlines)
+fn display_raw(mut entries: Vec<LogEntry>, options: &DisplayOptions, out: &mut dyn Write) -> io::Result<()> {
+    entries.sort_by_key(|e| e.timestamp);
 
     for entry in entries {
-        print_entry(&entry);
+        write_entry(out, &entry, options)?;
+    }
+    Ok(())
+}
+
+/// Parse a --delimiter: a single character other than a quote or line break, or `\t`/`tab`
+fn parse_delimiter(spec: &str) -> Result<char> {
+    if spec == "\\t" || spec.eq_ignore_ascii_case("tab") {
+        return Ok('\t');
+    }
+    let mut chars = spec.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if !matches!(c, '"' | '\n' | '\r') => Ok(c),
+        _ => bail!("Invalid --delimiter '{}': expected a single character other than a quote or line break, or \\t", spec),
+    }
+}
+
+/// A CSV field, quoted when it contains the delimiter, a quote or a line break (quotes are doubled)
+fn csv_field(value: &str, delimiter: char) -> String {
+    if value.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_csv_row(out: &mut dyn Write, fields: &[&str], delimiter: char) -> io::Result<()> {
+    let row: Vec<String> = fields.iter().map(|field| csv_field(field, delimiter)).collect();
+    writeln!(out, "{}", row.join(&delimiter.to_string()))
+}
+
+fn display_csv(mut entries: Vec<LogEntry>, options: &DisplayOptions, out: &mut dyn Write) -> io::Result<()> {
+    entries.sort_by_key(|e| e.timestamp);
+
+    let delimiter = options.delimiter.unwrap_or(DEFAULT_DELIMITER);
+    write_csv_row(out, &CSV_HEADER, delimiter)?;
+    for entry in &entries {
+        // Merged duplicates list every group, like human output
+        let groups = std::iter::once(&entry.log_group).chain(&entry.also_in).cloned().collect::<Vec<_>>().join(",");
+        let timestamp = entry.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true);
+        let fields = [
+            timestamp.as_str(),
+            entry.region.as_deref().unwrap_or(""),
+            groups.as_str(),
+            entry.log_stream.as_deref().unwrap_or(""),
+            level::detect(&entry.message).unwrap_or(""),
+            entry.message.as_str(),
+        ];
+        write_csv_row(out, &fields, delimiter)?;
+    }
+    Ok(())
+}
+
+fn display_columns(mut entries: Vec<LogEntry>, options: &DisplayOptions, out: &mut dyn Write) -> io::Result<()> {
+    entries.sort_by_key(|e| e.timestamp);
+
+    if !options.quiet {
+        eprintln!("{} {} results:\n", "Found".green(), entries.len().to_string().cyan());
+    }
+
+    let rows: Vec<ColumnRow> = entries.iter().map(|e| ColumnRow::new(e, options)).collect();
+    let widths = ColumnWidths::of(&rows);
+
+    for (row, entry) in rows.iter().zip(&entries) {
+        writeln!(out, "{}", row.format(&widths, &message_text(entry, options)))?;
+    }
+    Ok(())
+}
+
+/// Prefix columns for one entry in the aligned columns view
+struct ColumnRow {
+    timestamp: String,
+    region: String,
+    group: String,
+    /// Empty unless --show-stream
+    stream: String,
+}
+
+impl ColumnRow {
+    fn new(entry: &LogEntry, options: &DisplayOptions) -> Self {
+        Self {
+            timestamp: options.timestamp(entry),
+            region: entry.region.as_deref().map(|r| region_label(r, &options.region_aliases)).unwrap_or_default(),
+            group: shorten_group(&entry.log_group),
+            stream: match &entry.log_stream {
+                Some(stream) if options.show_stream => shorten_stream(stream),
+                _ => String::new(),
+            },
+        }
+    }
+
+    /// Pad each column to the shared width, then colorize (padding must not count escape codes)
+    fn format(&self, widths: &ColumnWidths, message: &str) -> String {
+        let mut line = format!("{:<w$}", self.timestamp, w = widths.timestamp)
+            .dimmed()
+            .to_string();
+        if widths.region > 0 {
+            line.push(' ');
+            line.push_str(&format!("{:<w$}", self.region, w = widths.region).cyan().to_string());
+        }
+        line.push(' ');
+        line.push_str(&format!("{:<w$}", self.group, w = widths.group).blue().to_string());
+        if widths.stream > 0 {
+            line.push(' ');
+            line.push_str(&format!("{:<w$}", self.stream, w = widths.stream).dimmed().to_string());
+        }
+        line.push(' ');
+        line.push_str(message);
+        line
     }
 }
 
-fn display_grouped(entries: Vec<LogEntry>) {
-    use std::collections::HashMap;
+/// Maximum width of each prefix column across a result set
+#[derive(Debug, PartialEq)]
+struct ColumnWidths {
+    timestamp: usize,
+    region: usize,
+    group: usize,
+    stream: usize,
+}
+
+impl ColumnWidths {
+    fn of(rows: &[ColumnRow]) -> Self {
+        let width = |f: fn(&ColumnRow) -> &str| rows.iter().map(|r| f(r).chars().count()).max().unwrap_or(0);
+        Self {
+            timestamp: width(|r| &r.timestamp),
+            region: width(|r| &r.region),
+            group: width(|r| &r.group),
+            stream: width(|r| &r.stream),
+        }
+    }
+}
+
+/// Section an entry belongs to in grouped output
+fn section_key(entry: &LogEntry, group_by: GroupBy) -> String {
+    match group_by {
+        GroupBy::Group => entry.log_group.clone(),
+        GroupBy::Region => entry.region.clone().unwrap_or_else(|| NO_REGION.to_string()),
+        GroupBy::Stream => entry.log_stream.clone().unwrap_or_else(|| NO_STREAM.to_string()),
+    }
+}
 
-    let mut by_group: HashMap<String, Vec<LogEntry>> = HashMap::new();
+/// Sections sorted by key, so output is stable between runs
+fn display_grouped(entries: Vec<LogEntry>, options: &DisplayOptions, out: &mut dyn Write) -> io::Result<()> {
+    let mut by_group: BTreeMap<String, Vec<LogEntry>> = BTreeMap::new();
 
     for entry in entries {
         by_group
-            .entry(entry.log_group.clone())
+            .entry(section_key(&entry, options.group_by))
             .or_default()
             .push(entry);
     }
 
     for (group_name, mut group_entries) in by_group {
-        println!(
+        writeln!(
+            out,
             "\n{} {} ({} results)",
             "━━━".blue(),
             group_name.cyan().bold(),
             group_entries.len()
-        );
-        println!();
+        )?;
+        writeln!(out)?;
 
-        group_entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        group_entries.sort_by_key(|e| e.timestamp);
 
         for entry in group_entries {
-            print_entry(&entry);
+            write_entry(out, &entry, options)?;
+        }
+    }
+    Ok(())
+}
+
+/// The earliest and latest of some entries, and how many there were
+#[derive(Debug, Clone)]
+struct Extent<'a> {
+    total: usize,
+    first: &'a LogEntry,
+    last: &'a LogEntry,
+}
+
+impl<'a> Extent<'a> {
+    /// None when there are no entries; on equal timestamps the first listed is "first", the last listed "last"
+    fn of(entries: impl Iterator<Item = &'a LogEntry> + Clone) -> Option<Self> {
+        Some(Self {
+            total: entries.clone().count(),
+            first: entries.clone().min_by_key(|e| e.timestamp)?,
+            last: entries.max_by_key(|e| e.timestamp)?,
+        })
+    }
+
+    /// The ends --first/--last-only asked for, labelled
+    fn ends(&self, bounds: Bounds) -> Vec<(&'static str, &'a LogEntry)> {
+        let mut ends = Vec::new();
+        if bounds.first {
+            ends.push(("first", self.first));
+        }
+        if bounds.last {
+            ends.push(("last", self.last));
+        }
+        ends
+    }
+}
+
+/// Extent of all entries, and of each section (log group unless --group-by says otherwise)
+fn extents(entries: &[LogEntry], group_by: GroupBy) -> (Option<Extent<'_>>, BTreeMap<String, Extent<'_>>) {
+    let mut sections: BTreeMap<String, Vec<&LogEntry>> = BTreeMap::new();
+    for entry in entries {
+        sections.entry(section_key(entry, group_by)).or_default().push(entry);
+    }
+    let sections = sections
+        .into_iter()
+        .filter_map(|(key, entries)| Some((key, Extent::of(entries.into_iter())?)))
+        .collect();
+    (Extent::of(entries.iter()), sections)
+}
+
+/// An extent in JSON, without the end that wasn't asked for
+#[derive(Serialize)]
+struct ExtentJson<'a> {
+    total: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first: Option<JsonEntry<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last: Option<JsonEntry<'a>>,
+}
+
+impl<'a> ExtentJson<'a> {
+    fn new(extent: &Extent<'a>, bounds: Bounds) -> Self {
+        Self {
+            total: extent.total,
+            first: bounds.first.then(|| JsonEntry::new(extent.first)),
+            last: bounds.last.then(|| JsonEntry::new(extent.last)),
         }
     }
 }
 
-fn display_json(mut entries: Vec<LogEntry>) {
+/// --first/--last-only in JSON: the overall extent, then one per section under "groups"
+#[derive(Serialize)]
+struct BoundsJsonOutput<'a> {
+    #[serde(flatten)]
+    overall: ExtentJson<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_range: Option<TimeRange>,
+    groups: BTreeMap<String, ExtentJson<'a>>,
+}
+
+/// Only the earliest/latest entry of each section, then of everything when there are several sections
+/// Returns the number of entries written
+fn display_bounds(entries: &[LogEntry], bounds: Bounds, options: &DisplayOptions, out: &mut dyn Write) -> io::Result<usize> {
+    let (Some(overall), sections) = extents(entries, options.group_by) else {
+        return Ok(0);
+    };
+    if options.mode.is_json() {
+        let output = BoundsJsonOutput {
+            overall: ExtentJson::new(&overall, bounds),
+            time_range: options.time_range,
+            groups: sections.iter().map(|(key, extent)| (key.clone(), ExtentJson::new(extent, bounds))).collect(),
+        };
+        write_json(out, &output, &options.mode)?;
+        return Ok(sections.values().map(|extent| extent.ends(bounds).len()).sum());
+    }
+
+    // Raw and CSV get just the rows, each once, without section headings or the overall ends
+    if options.mode.is_bare() {
+        let mut rows: Vec<LogEntry> = Vec::new();
+        for extent in sections.values() {
+            let mut ends = extent.ends(bounds);
+            ends.dedup_by(|(_, a), (_, b)| std::ptr::eq(*a, *b));
+            rows.extend(ends.into_iter().map(|(_, entry)| entry.clone()));
+        }
+        let written = rows.len();
+        match options.mode {
+            OutputMode::Csv => display_csv(rows, options, out)?,
+            _ => display_raw(rows, options, out)?,
+        }
+        return Ok(written);
+    }
+
+    let mut written = 0;
+    let several = sections.len() > 1;
+    let overall = several.then(|| ("all".to_string(), overall));
+    for (key, extent) in sections.into_iter().chain(overall) {
+        writeln!(out, "\n{} {} ({} results)", "━━━".blue(), key.cyan().bold(), extent.total)?;
+        for (label, entry) in extent.ends(bounds) {
+            write!(out, "{:<5} ", label.green())?;
+            write_entry(out, entry, options)?;
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+fn display_json(mut entries: Vec<LogEntry>, truncated: bool, options: &DisplayOptions, out: &mut dyn Write) -> io::Result<()> {
     // Sort by timestamp for consistent output
-    entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    entries.sort_by_key(|e| e.timestamp);
 
     let output = JsonOutput {
         total: entries.len(),
+        truncated,
+        time_range: options.time_range,
         results: entries,
     };
 
-    match serde_json::to_string_pretty(&output) {
-        Ok(json) => println!("{}", json),
-        Err(e) => eprintln!("Error serializing to JSON: {}", e),
+    write_json(out, &output, &options.mode)
+}
+
+fn display_nested_json(mut entries: Vec<LogEntry>, truncated: bool, options: &DisplayOptions, out: &mut dyn Write) -> io::Result<()> {
+    entries.sort_by_key(|e| e.timestamp);
+
+    let total = entries.len();
+    let mut regions: BTreeMap<String, BTreeMap<String, Vec<LogEntry>>> = BTreeMap::new();
+    for entry in entries {
+        let region = entry.region.clone().unwrap_or_else(|| NO_REGION.to_string());
+        regions
+            .entry(region)
+            .or_default()
+            .entry(entry.log_group.clone())
+            .or_default()
+            .push(entry);
+    }
+
+    let output = NestedJsonOutput { total, truncated, time_range: options.time_range, regions };
+    write_json(out, &output, &options.mode)
+}
+
+fn display_correlated(
+    entries: Vec<LogEntry>,
+    correlator: &Correlator,
+    options: &DisplayOptions,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let total = entries.len();
+    let groups = correlate(entries, correlator);
+
+    if !options.quiet {
+        eprintln!(
+            "{} {} results in {} requests:",
+            "Found".green(),
+            total.to_string().cyan(),
+            groups.iter().filter(|g| g.id.is_some()).count().to_string().cyan()
+        );
+    }
+
+    for group in groups {
+        let label = match &group.id {
+            Some(id) => id.cyan().bold(),
+            None => "(no id)".dimmed(),
+        };
+        let span = match (group.results.first(), group.results.last()) {
+            (Some(first), Some(last)) => (last.timestamp - first.timestamp).num_milliseconds(),
+            _ => 0,
+        };
+
+        writeln!(
+            out,
+            "\n{} {} ({} results, {}ms)\n",
+            "━━━".blue(),
+            label,
+            group.results.len(),
+            span
+        )?;
+
+        for entry in &group.results {
+            write_entry(out, entry, options)?;
+        }
+    }
+    Ok(())
+}
+
+fn display_correlated_json(
+    entries: Vec<LogEntry>,
+    correlator: &Correlator,
+    truncated: bool,
+    options: &DisplayOptions,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let output = CorrelatedJsonOutput {
+        total: entries.len(),
+        truncated,
+        time_range: options.time_range,
+        requests: correlate(entries, correlator),
+    };
+
+    write_json(out, &output, &options.mode)
+}
+
+/// Print an error object in the JSON output format, tagged with a `kind` when one is known
+pub fn display_json_error(message: &str, kind: Option<&str>, mode: &OutputMode) {
+    if let Err(e) = write_json_error(&mut io::stdout(), message, kind, mode) {
+        eprintln!("Error writing JSON: {}", e);
     }
 }
 
-/// Print a single log entry with formatting
-pub fn print_entry(entry: &LogEntry) {
-    let timestamp = entry.timestamp.format("%Y-%m-%d %H:%M:%S%.3f");
+/// Write an error object in the JSON output format where results would have gone
+pub fn write_json_error(out: &mut dyn Write, message: &str, kind: Option<&str>, mode: &OutputMode) -> io::Result<()> {
+    let mut error = serde_json::json!({ "error": message });
+    if let Some(kind) = kind {
+        error["kind"] = serde_json::Value::from(kind);
+    }
+    write_json(out, &error, mode)
+}
 
-    // Truncate log group to last segment for cleaner output
-    let short_group = entry
-        .log_group
-        .rsplit('/')
-        .next()
-        .unwrap_or(&entry.log_group);
+/// Serialize a value as pretty or single-line JSON depending on the output mode
+fn to_json<T: Serialize>(value: &T, mode: &OutputMode) -> serde_json::Result<String> {
+    if matches!(mode, OutputMode::JsonCompact | OutputMode::JsonStream) {
+        serde_json::to_string(value)
+    } else {
+        serde_json::to_string_pretty(value)
+    }
+}
+
+fn write_json<T: Serialize>(out: &mut dyn Write, value: &T, mode: &OutputMode) -> io::Result<()> {
+    let json = to_json(value, mode).map_err(io::Error::other)?;
+    writeln!(out, "{}", json)
+}
+
+/// Writes the same document as single-line JSON output, one entry at a time as results arrive
+/// (-o json-stream): `{"results":[` first, then each entry, then the closing `],"total":N}`
+pub struct JsonStreamWriter {
+    total: usize,
+}
+
+impl JsonStreamWriter {
+    /// Open the document, leading with the searched window when known
+    pub fn begin(out: &mut dyn Write, time_range: Option<TimeRange>) -> io::Result<Self> {
+        write!(out, "{{")?;
+        if let Some(time_range) = time_range {
+            write!(out, "\"time_range\":{},", serde_json::to_string(&time_range).map_err(io::Error::other)?)?;
+        }
+        write!(out, "\"results\":[")?;
+        Ok(Self { total: 0 })
+    }
+
+    pub fn write(&mut self, out: &mut dyn Write, entry: &LogEntry) -> io::Result<()> {
+        if self.total > 0 {
+            write!(out, ",")?;
+        }
+        serde_json::to_writer(&mut *out, &JsonEntry::new(entry)).map_err(io::Error::other)?;
+        self.total += 1;
+        Ok(())
+    }
+
+    /// Close the array and add the total; returns how many entries were written
+    pub fn finish(self, out: &mut dyn Write) -> io::Result<usize> {
+        writeln!(out, "],\"total\":{}}}", self.total)?;
+        Ok(self.total)
+    }
+}
+
+/// Print a single log entry with formatting to stdout
+pub fn print_entry(entry: &LogEntry, options: &DisplayOptions) {
+    let _ = write_entry(&mut io::stdout(), entry, options);
+}
+
+/// Write a single log entry with formatting
+pub fn write_entry(out: &mut dyn Write, entry: &LogEntry, options: &DisplayOptions) -> io::Result<()> {
+    // Raw output is for other tools, so messages go out exactly as logged
+    if options.mode == OutputMode::Raw {
+        return if options.flatten_newlines {
+            writeln!(out, "{}", flatten_newlines(&entry.message))
+        } else {
+            writeln!(out, "{}", entry.message)
+        };
+    }
+
+    let timestamp = options.timestamp(entry);
+
+    // Truncate log group to last segment for cleaner output; merged duplicates list every group
+    let short_group = std::iter::once(&entry.log_group)
+        .chain(&entry.also_in)
+        .map(|group| group.rsplit('/').next().unwrap_or(group))
+        .collect::<Vec<_>>()
+        .join(",");
 
     // Include region if available
     let group_display = if let Some(ref region) = entry.region {
@@ -113,17 +1222,819 @@ pub fn print_entry(entry: &LogEntry) {
         format!("[{}]", short_group)
     };
 
-    println!(
-        "{} {} {}",
-        timestamp.to_string().dimmed(),
-        group_display.blue(),
-        entry.message
-    );
+    let group_display = group_display
+        .color(display::to_terminal_color(display::group_color(&options.colors, entry)))
+        .to_string();
+    let group_display = match options
+        .console_links
+        .as_ref()
+        .and_then(|links| links.url(entry.region.as_deref(), &entry.log_group))
+    {
+        Some(url) => display::hyperlink(&group_display, &url),
+        None => group_display,
+    };
+
+    // Stream after the group when requested (--show-stream)
+    let stream_display = match &entry.log_stream {
+        Some(stream) if options.show_stream => format!(" {}", format!("[{}]", shorten_stream(stream)).dimmed()),
+        _ => String::new(),
+    };
+
+    let pretty = options.pretty_json.then(|| pretty_json_message(&entry.message)).flatten();
+
+    writeln!(
+        out,
+        "{} {}{} {}",
+        timestamp.dimmed(),
+        group_display,
+        stream_display,
+        match pretty {
+            Some(ref block) => format!("\n{}", block),
+            None => message_text(entry, options),
+        }
+    )
 }
 
-/// Print a single entry in JSON format (for streaming mode)
-pub fn print_entry_json(entry: &LogEntry) {
-    if let Ok(json) = serde_json::to_string(entry) {
-        println!("{}", json);
+/// An entry's message as printed in human output: on one line with --flatten-newlines, without the
+/// app's own color codes, and with other control characters made visible so log content can't
+/// drive the terminal
+fn message_text(entry: &LogEntry, options: &DisplayOptions) -> String {
+    let message = display::strip_sgr(&entry.message);
+    if options.flatten_newlines {
+        display::sanitize(&flatten_newlines(&message)).into_owned()
+    } else {
+        display::sanitize(&message).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn entry(region: Option<&str>, group: &str, message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: Utc.with_ymd_and_hms(2026, 1, 23, 5, 36, 5).unwrap(),
+            message: message.to_string(),
+            log_group: group.to_string(),
+            log_stream: None,
+            region: region.map(|r| r.to_string()),
+            also_in: Vec::new(),
+            ptr: None,
+        }
+    }
+
+    /// An app/prod entry at 05:{minute}:00 on the day `entry` uses
+    fn entry_at(minute: u32, message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: Utc.with_ymd_and_hms(2026, 1, 23, 5, minute, 0).unwrap(),
+            ..entry(None, "app/prod", message)
+        }
+    }
+
+    #[test]
+    fn test_stream_contains_filters_mixed_streams() {
+        let with_stream = |stream: Option<&str>, message: &str| LogEntry {
+            log_stream: stream.map(|s| s.to_string()),
+            ..entry(None, "/aws/app/rails-web", message)
+        };
+        let entries = vec![
+            with_stream(Some("ecs/rails-web/web-1"), "from web-1"),
+            with_stream(Some("ecs/rails-worker/worker-1"), "from worker"),
+            with_stream(Some("ecs/rails-web/web-2"), "from web-2"),
+            with_stream(None, "no stream"),
+        ];
+
+        let options = DisplayOptions::new(OutputMode::JsonCompact).with_stream_contains(Some("rails-web/".to_string()));
+        let messages: Vec<&str> = entries.iter().filter(|e| options.shows(e)).map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["from web-1", "from web-2"]);
+        assert!(entries.iter().all(|e| DisplayOptions::default().shows(e)));
+
+        // Applied by display_results before the results are counted
+        let mut out = Vec::new();
+        let options = options.with_stream_contains(Some("worker-1".to_string()));
+        display_results(entries, &options, &mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["total"], 1);
+        assert_eq!(json["results"][0]["message"], "from worker");
+    }
+
+    #[test]
+    fn test_stdout_has_only_result_lines() {
+        colored::control::set_override(false);
+        // "No matching logs found." and "Found N results" go to stderr, with or without --quiet
+        for quiet in [false, true] {
+            let options = DisplayOptions::new(OutputMode::Interleaved).with_quiet(quiet);
+
+            let mut out = Vec::new();
+            display_results(vec![], &options, &mut out).unwrap();
+            assert!(out.is_empty());
+
+            let entries = vec![entry(None, "app/prod", "first"), entry(None, "app/prod", "second")];
+            let mut out = Vec::new();
+            display_results(entries, &options, &mut out).unwrap();
+            let text = String::from_utf8(out).unwrap();
+            let lines: Vec<&str> = text.lines().filter(|l| !l.is_empty()).collect();
+            assert_eq!(lines.len(), 2, "quiet: {}", quiet);
+            assert!(lines.iter().all(|l| l.contains("first") || l.contains("second")));
+        }
+    }
+
+    #[test]
+    fn test_custom_time_format() {
+        colored::control::set_override(false);
+        let render = |format: Option<&str>| {
+            let options = DisplayOptions::new(OutputMode::Interleaved).with_time_format(format).unwrap();
+            let mut out = Vec::new();
+            write_entry(&mut out, &entry(None, "app/prod", "boom"), &options).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!(render(None), "2026-01-23 05:36:05.000 [prod] boom\n");
+        assert_eq!(render(Some("%d/%m %H:%M")), "23/01 05:36 [prod] boom\n");
+        assert_eq!(render(Some("%s")), "1769146565 [prod] boom\n");
+        assert!(DisplayOptions::default().with_time_format(Some("%Y-%Q")).is_err());
+    }
+
+    #[test]
+    fn test_first_and_last_per_group() {
+        colored::control::set_override(false);
+        let at = |group: &str, second: u32, message: &str| LogEntry {
+            timestamp: Utc.with_ymd_and_hms(2026, 1, 23, 5, 0, second).unwrap(),
+            ..entry(None, group, message)
+        };
+        let entries = vec![
+            at("app/web", 30, "web middle"),
+            at("app/api", 20, "api only"),
+            at("app/web", 10, "web start"),
+            at("app/web", 50, "web end"),
+        ];
+
+        let (overall, sections) = extents(&entries, GroupBy::Group);
+        let overall = overall.unwrap();
+        assert_eq!((overall.total, overall.first.message.as_str(), overall.last.message.as_str()), (4, "web start", "web end"));
+        let summary: Vec<_> = sections
+            .iter()
+            .map(|(key, e)| (key.as_str(), e.total, e.first.message.as_str(), e.last.message.as_str()))
+            .collect();
+        assert_eq!(summary, vec![("app/api", 1, "api only", "api only"), ("app/web", 3, "web start", "web end")]);
+
+        let options = DisplayOptions::new(OutputMode::JsonCompact).with_bounds(true, true);
+        let mut out = Vec::new();
+        assert_eq!(display_results(entries.clone(), &options, &mut out).unwrap(), 4);
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["total"], 4);
+        assert_eq!(json["first"]["message"], "web start");
+        assert_eq!(json["groups"]["app/web"]["total"], 3);
+        assert_eq!(json["groups"]["app/web"]["first"]["message"], "web start");
+        assert_eq!(json["groups"]["app/web"]["last"]["message"], "web end");
+        assert_eq!(json["groups"]["app/api"]["last"]["message"], "api only");
+
+        // --last-only: just the latest of each group, then of everything
+        let options = DisplayOptions::new(OutputMode::Interleaved).with_bounds(false, true);
+        let mut out = Vec::new();
+        assert_eq!(display_results(entries.clone(), &options, &mut out).unwrap(), 3);
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().filter(|l| l.starts_with("last")).collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with("api only") && lines[1].ends_with("web end") && lines[2].ends_with("web end"));
+        assert!(!text.contains("first") && !text.contains("web middle"));
+
+        // -o csv --first: the header, then one row per group
+        let options = DisplayOptions::new(OutputMode::Csv).with_bounds(true, false);
+        let mut out = Vec::new();
+        assert_eq!(display_results(entries, &options, &mut out).unwrap(), 2);
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "timestamp,region,log_group,log_stream,level,message");
+        assert!(lines[1].ends_with(",app/web,,,web start") && lines[2].ends_with(",app/api,,,api only"));
+    }
+
+    #[test]
+    fn test_csv_output_with_comma_and_tab_delimiters() {
+        let mut entries = vec![
+            entry(Some("us-east-1"), "/ecs/app", "ERROR payment failed, retrying"),
+            entry(None, "/ecs/api", "said \"hi\"\tand left"),
+        ];
+        entries[0].log_stream = Some("web/1".to_string());
+        entries[1].timestamp += chrono::Duration::seconds(1);
+
+        let render = |delimiter: Option<&str>| {
+            let options = DisplayOptions::new(OutputMode::Csv).with_delimiter(delimiter).unwrap();
+            let mut out = Vec::new();
+            display_results(entries.clone(), &options, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        // Fields containing the delimiter or a quote are quoted; a tab is just text here
+        assert_eq!(
+            render(None),
+            "timestamp,region,log_group,log_stream,level,message\n\
+             2026-01-23T05:36:05.000Z,us-east-1,/ecs/app,web/1,ERROR,\"ERROR payment failed, retrying\"\n\
+             2026-01-23T05:36:06.000Z,,/ecs/api,,,\"said \"\"hi\"\"\tand left\"\n"
+        );
+
+        // With tabs the comma is just text, and the message holding a tab is quoted
+        assert_eq!(
+            render(Some("\\t")),
+            "timestamp\tregion\tlog_group\tlog_stream\tlevel\tmessage\n\
+             2026-01-23T05:36:05.000Z\tus-east-1\t/ecs/app\tweb/1\tERROR\tERROR payment failed, retrying\n\
+             2026-01-23T05:36:06.000Z\t\t/ecs/api\t\t\t\"said \"\"hi\"\"\tand left\"\n"
+        );
+        assert_eq!(render(Some("tab")), render(Some("\\t")));
+
+        assert_eq!(parse_delimiter(";").unwrap(), ';');
+        assert!(parse_delimiter(",,").is_err());
+        assert!(parse_delimiter("\"").is_err());
+        assert!(parse_delimiter("").is_err());
+    }
+
+    #[test]
+    fn test_exclude_where_drops_matching_json_fields() {
+        let entries = || {
+            vec![
+                entry(None, "api/prod", r#"{"status":200,"path":"/health","level":"info"}"#),
+                entry(None, "api/prod", r#"{"status":"503","path":"/orders","level":"error"}"#),
+                entry(None, "api/prod", r#"{"status":404,"path":"/missing","level":"warn"}"#),
+                entry(None, "api/prod", "plain text status=200"),
+            ]
+        };
+        let kept = |specs: &[&str]| {
+            let specs: Vec<String> = specs.iter().map(|s| s.to_string()).collect();
+            let options = DisplayOptions::default().with_exclude_where(&specs).unwrap();
+            entries().into_iter().filter(|e| options.shows(e)).map(|e| e.message).collect::<Vec<_>>()
+        };
+
+        // Numeric: numbers and numeric strings compare as numbers; non-JSON messages are kept
+        assert_eq!(kept(&["status==200"]).len(), 3);
+        assert!(!kept(&["status==200"]).iter().any(|m| m.contains("/health")));
+        assert_eq!(kept(&["status>=400"]), vec![r#"{"status":200,"path":"/health","level":"info"}"#, "plain text status=200"]);
+
+        // String, and several exclusions drop anything matching one of them
+        assert_eq!(
+            kept(&["level==info", "path==/missing"]),
+            vec![r#"{"status":"503","path":"/orders","level":"error"}"#, "plain text status=200"]
+        );
+
+        let error = DisplayOptions::default().with_exclude_where(&["status".to_string()]).unwrap_err();
+        assert!(error.to_string().contains("--exclude-where"), "{}", error);
+    }
+
+    #[test]
+    fn test_control_characters_are_visible_in_human_output_and_escaped_in_json() {
+        colored::control::set_override(false);
+        let message = "bin\0ary \x1b[2J\x1b]0;owned\x07 ok\tdone\r\nnext\rline\u{9b}";
+        let render = |mode: OutputMode, message: &str| {
+            let mut out = Vec::new();
+            display_results(vec![entry(None, "app/prod", message)], &DisplayOptions::new(mode).with_quiet(true), &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert!(render(OutputMode::Interleaved, message).ends_with("[prod] bin␀ary ␛[2J␛]0;owned␇ ok\tdone\r\nnext␍line\u{FFFD}\n"));
+
+        // JSON keeps the message as it was, escaped
+        let json: serde_json::Value = serde_json::from_str(&render(OutputMode::Json, message)).unwrap();
+        assert_eq!(json["results"][0]["message"], message);
+        assert!(!render(OutputMode::Json, message).contains('\x1b'));
+
+        assert!(matches!(display::sanitize("plain\ttext\n"), std::borrow::Cow::Borrowed(_)));
+
+        // Raw output is byte for byte what was logged
+        assert_eq!(render(OutputMode::Raw, message), format!("{}\n", message));
+
+        // An app's own colors are dropped rather than shown as ␛[31m
+        let colored = "\x1b[1;31mERROR\x1b[0m payment failed \x1b[38;5;244m(retry 2)\x1b[m";
+        assert!(render(OutputMode::Interleaved, colored).ends_with("[prod] ERROR payment failed (retry 2)\n"));
+        assert_eq!(render(OutputMode::Raw, colored), format!("{}\n", colored));
+    }
+
+    #[test]
+    fn test_raw_output_is_messages_only() {
+        colored::control::set_override(false);
+        let options = DisplayOptions::new(OutputMode::Raw).with_sample(Some(5)).with_page(Some(Page { number: 1, size: 5 }));
+
+        let entries = vec![
+            entry(Some("us-east-1"), "app/prod", r#"{"level":"error","msg":"boom"}"#),
+            LogEntry { timestamp: Utc.with_ymd_and_hms(2026, 1, 23, 5, 0, 0).unwrap(), ..entry(None, "api/prod", "ERROR first") },
+        ];
+        let mut out = Vec::new();
+        assert_eq!(display_results(entries, &options, &mut out).unwrap(), 2);
+        assert_eq!(String::from_utf8(out).unwrap(), "ERROR first\n{\"level\":\"error\",\"msg\":\"boom\"}\n");
+
+        // Nothing found prints nothing, so pipes see no noise
+        let mut out = Vec::new();
+        display_results(vec![], &options, &mut out).unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_exclude_streams_drops_matching_streams() {
+        let with_stream = |stream: Option<&str>, message: &str| LogEntry {
+            log_stream: stream.map(|s| s.to_string()),
+            ..entry(None, "/aws/app/rails-web", message)
+        };
+        let entries = vec![
+            with_stream(Some("ecs/rails-web/canary-1"), "from canary"),
+            with_stream(Some("ecs/rails-web/web-1"), "from web-1"),
+            with_stream(Some("ecs/rails-worker/worker-1"), "from worker"),
+            with_stream(None, "no stream"),
+        ];
+
+        let options = DisplayOptions::new(OutputMode::JsonCompact)
+            .with_exclude_streams(vec!["canary".to_string(), "worker".to_string()]);
+        let mut out = Vec::new();
+        display_results(entries, &options, &mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["total"], 2);
+        assert_eq!(json["results"][0]["message"], "from web-1");
+        assert_eq!(json["results"][1]["message"], "no stream");
+    }
+
+    #[test]
+    fn test_flatten_newlines_one_line_per_entry() {
+        colored::control::set_override(false);
+
+        let entries = vec![
+            entry(None, "/aws/app/rails-web", "RuntimeError: boom\n  app/models/user.rb:12\r\n  app/jobs/sync.rb:3\n"),
+            entry(None, "/aws/app/rails-web", "{\n  \"status\": 500\r}"),
+        ];
+
+        for mode in [OutputMode::Interleaved, OutputMode::Columns, OutputMode::Grouped] {
+            let mut out = Vec::new();
+            let options = DisplayOptions::new(mode).with_flatten_newlines(true);
+            display_results(entries.clone(), &options, &mut out).unwrap();
+
+            let output = String::from_utf8(out).unwrap();
+            let entry_lines: Vec<&str> = output.lines().filter(|l| l.starts_with("2026-")).collect();
+            assert_eq!(entry_lines.len(), 2);
+            assert!(entry_lines[0].ends_with("RuntimeError: boom ⏎   app/models/user.rb:12 ⏎   app/jobs/sync.rb:3"));
+            assert!(!output.contains('\r'));
+        }
+
+        // JSON keeps the original message
+        let mut out = Vec::new();
+        let options = DisplayOptions::new(OutputMode::JsonCompact).with_flatten_newlines(true);
+        display_results(entries, &options, &mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains(r"boom\n  app/models"));
+    }
+
+    #[test]
+    fn test_grouped_sections_by_key() {
+        colored::control::set_override(false);
+
+        let with_stream = |region: Option<&str>, group: &str, stream: Option<&str>| LogEntry {
+            log_stream: stream.map(|s| s.to_string()),
+            ..entry(region, group, &format!("{} {:?} {:?}", group, region, stream))
+        };
+        let entries = vec![
+            with_stream(Some("us-east-1"), "app/web", Some("i-0b")),
+            with_stream(Some("ap-northeast-1"), "app/worker", Some("i-0a")),
+            with_stream(Some("us-east-1"), "app/worker", Some("i-0b")),
+            with_stream(None, "app/web", None),
+        ];
+        let sections = |group_by: GroupBy| {
+            let mut out = Vec::new();
+            let options = DisplayOptions::new(OutputMode::Grouped).with_group_by(group_by);
+            display_results(entries.clone(), &options, &mut out).unwrap();
+            String::from_utf8(out)
+                .unwrap()
+                .lines()
+                .filter_map(|l| l.strip_prefix("━━━ ").map(str::to_string))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(sections(GroupBy::Group), ["app/web (2 results)", "app/worker (2 results)"]);
+        assert_eq!(
+            sections(GroupBy::Region),
+            ["ap-northeast-1 (1 results)", "default (1 results)", "us-east-1 (2 results)"]
+        );
+        assert_eq!(sections(GroupBy::Stream), ["(no stream) (1 results)", "i-0a (1 results)", "i-0b (2 results)"]);
+    }
+
+    #[test]
+    fn test_json_nested_by_region_and_group() {
+        let entries = vec![
+            entry(Some("us-east-1"), "app/prod", "a"),
+            entry(Some("ap-northeast-1"), "api/prod", "b"),
+            entry(Some("us-east-1"), "api/prod", "c"),
+            entry(Some("ap-northeast-1"), "api/prod", "d"),
+        ];
+
+        let mut out = Vec::new();
+        let options = DisplayOptions::new(OutputMode::JsonCompact).with_json_nested(true);
+        display_results(entries, &options, &mut out).unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["total"], 4);
+        let messages = |region: &str, group: &str| -> Vec<String> {
+            json["regions"][region][group]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|e| e["message"].as_str().unwrap().to_string())
+                .collect()
+        };
+        assert_eq!(messages("us-east-1", "app/prod"), vec!["a"]);
+        assert_eq!(messages("us-east-1", "api/prod"), vec!["c"]);
+        assert_eq!(messages("ap-northeast-1", "api/prod"), vec!["b", "d"]);
+        assert_eq!(json["regions"].as_object().unwrap().len(), 2);
+        assert!(json.get("results").is_none());
+    }
+
+    #[test]
+    fn test_merge_duplicates_across_groups() {
+        colored::control::set_override(false);
+
+        let at = |group: &str, minute: u32, message: &str| LogEntry { log_group: group.to_string(), ..entry_at(minute, message) };
+        let entries = vec![
+            at("/aws/app", 1, "boom"),
+            at("/aws/aggregated", 1, "boom"),
+            at("/aws/app", 1, "other"),
+            at("/aws/app", 2, "boom"),
+            at("/aws/aggregated", 2, "boom"),
+            at("/aws/aggregated", 3, "only here"),
+        ];
+
+        let mut out = Vec::new();
+        let options = DisplayOptions::new(OutputMode::Interleaved).with_merge_duplicates(true);
+        display_results(entries.clone(), &options, &mut out).unwrap();
+        let output = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = output.lines().filter(|l| l.starts_with("2026-")).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "2026-01-23 05:01:00.000 [app,aggregated] boom",
+                "2026-01-23 05:01:00.000 [app] other",
+                "2026-01-23 05:02:00.000 [app,aggregated] boom",
+                "2026-01-23 05:03:00.000 [aggregated] only here",
+            ]
+        );
+
+        // Without the flag every copy is kept
+        let mut out = Vec::new();
+        display_results(entries, &DisplayOptions::new(OutputMode::Interleaved), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap().lines().filter(|l| l.starts_with("2026-")).count(), 6);
+    }
+
+    #[test]
+    fn test_dedupe_window_keeps_repeats_after_quiet_spells() {
+        colored::control::set_override(false);
+
+        let entries = vec![
+            entry_at(0, "db timeout"),
+            entry_at(2, "db timeout"),
+            entry_at(3, "cache miss"),
+            entry_at(6, "db timeout"),
+            entry_at(20, "db timeout"),
+            entry_at(40, "cache miss"),
+        ];
+
+        // The burst at 0-6 (each repeat within 5m of the last) shows once; 20 and 40 are new occurrences
+        let options = DisplayOptions::new(OutputMode::Interleaved).with_dedupe_window(Some("5m")).unwrap();
+        let mut out = Vec::new();
+        assert_eq!(display_results(entries.clone(), &options, &mut out).unwrap(), 4);
+        let output = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = output.lines().filter(|l| l.starts_with("2026-")).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "2026-01-23 05:00:00.000 [prod] db timeout",
+                "2026-01-23 05:03:00.000 [prod] cache miss",
+                "2026-01-23 05:20:00.000 [prod] db timeout",
+                "2026-01-23 05:40:00.000 [prod] cache miss",
+            ]
+        );
+
+        // A window wider than the gaps collapses each message to one line
+        let options = DisplayOptions::new(OutputMode::Interleaved).with_dedupe_window(Some("1h")).unwrap();
+        assert_eq!(display_results(entries, &options, &mut Vec::new()).unwrap(), 2);
+
+        assert!(DisplayOptions::default().with_dedupe_window(Some("soon")).is_err());
+    }
+
+    #[test]
+    fn test_show_stream_in_entry_line() {
+        colored::control::set_override(false);
+
+        let mut with_stream = entry(None, "/aws/app/rails-web", "boom");
+        with_stream.log_stream = Some("ecs/rails-web/9f1c2b3a4d5e6f708192a3b4c5d6e7f8".to_string());
+
+        let line = |options: &DisplayOptions| {
+            let mut out = Vec::new();
+            write_entry(&mut out, &with_stream, options).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!(line(&DisplayOptions::default()), "2026-01-23 05:36:05.000 [rails-web] boom\n");
+        assert_eq!(
+            line(&DisplayOptions::default().with_show_stream(true)),
+            "2026-01-23 05:36:05.000 [rails-web] [9f1c2b3a4d…4c5d6e7f8] boom\n"
+        );
+    }
+
+    #[test]
+    fn test_columns_align_messages() {
+        colored::control::set_override(false);
+
+        let entries = [
+            entry(Some("ap-northeast-1"), "/aws/app/rails-web", "first"),
+            entry(Some("us-east-1"), "/aws/api", "second"),
+        ];
+        let rows: Vec<ColumnRow> = entries.iter().map(|e| ColumnRow::new(e, &DisplayOptions::default())).collect();
+        let widths = ColumnWidths::of(&rows);
+        assert_eq!(widths, ColumnWidths { timestamp: 23, region: 3, group: 3, stream: 0 });
+
+        let first = rows[0].format(&widths, "first");
+        let second = rows[1].format(&widths, "second");
+        assert_eq!(first.find("first"), second.find("second"));
+    }
+
+    #[test]
+    fn test_correlator_extracts_ids() {
+        let field = Correlator::new("request_id").unwrap();
+        assert_eq!(field.extract("GET / request_id=abc-123 status=200").as_deref(), Some("abc-123"));
+        assert_eq!(field.extract(r#"{"request_id":"abc-123","status":200}"#).as_deref(), Some("abc-123"));
+        assert_eq!(field.extract("no id here"), None);
+
+        let regex = Correlator::new(r"^\[(?P<id>[0-9a-f-]+)\]").unwrap();
+        assert_eq!(regex.extract("[9f2c-01] Started GET /").as_deref(), Some("9f2c-01"));
+
+        assert!(Correlator::new("(unclosed").is_err());
+    }
+
+    #[test]
+    fn test_correlate_groups_by_first_seen() {
+        let correlator = Correlator::new("rid").unwrap();
+        let mut entries = vec![
+            entry(None, "/aws/api", "rid=b start"),
+            entry(None, "/aws/api", "rid=a start"),
+            entry(None, "/aws/web", "rid=b end"),
+            entry(None, "/aws/web", "unrelated"),
+        ];
+        for (i, e) in entries.iter_mut().enumerate() {
+            e.timestamp += chrono::Duration::seconds(i as i64);
+        }
+
+        let groups = correlate(entries, &correlator);
+        let summary: Vec<(Option<&str>, usize)> = groups
+            .iter()
+            .map(|g| (g.id.as_deref(), g.results.len()))
+            .collect();
+        assert_eq!(summary, vec![(Some("b"), 2), (Some("a"), 1), (None, 1)]);
+    }
+
+    #[test]
+    fn test_limit_total_keeps_newest_and_flags_truncation() {
+        let mut entries: Vec<LogEntry> = (0..5)
+            .map(|i| {
+                let mut e = entry(None, "/aws/api", &format!("line {}", i));
+                e.timestamp += chrono::Duration::seconds(i);
+                e
+            })
+            .collect();
+
+        assert!(!apply_limit_total(&mut entries.clone(), Some(5), false));
+        assert!(!apply_limit_total(&mut entries.clone(), None, false));
+
+        let truncated = apply_limit_total(&mut entries, Some(2), false);
+        assert!(truncated);
+        let messages: Vec<&str> = entries.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["line 3", "line 4"]);
+
+        let json = to_json(&JsonOutput { total: entries.len(), truncated, time_range: None, results: entries }, &OutputMode::JsonCompact).unwrap();
+        assert!(json.starts_with(r#"{"total":2,"truncated":true,"results":["#));
+    }
+
+    #[test]
+    fn test_pretty_json_message() {
+        colored::control::set_override(false);
+
+        let pretty = pretty_json_message(r#"{"level":"error","ctx":{"user_id":42}}"#).unwrap();
+        assert_eq!(
+            pretty,
+            "  {\n    \"level\": \"error\",\n    \"ctx\": {\n      \"user_id\": 42\n    }\n  }"
+        );
+
+        assert_eq!(pretty_json_message("Started GET /health"), None);
+        assert_eq!(pretty_json_message("{not json"), None);
+        assert_eq!(pretty_json_message("[1, 2, 3]"), None);
+    }
+
+    #[test]
+    fn test_json_entries_have_stable_ids() {
+        let a = entry(Some("us-east-1"), "app/prod", "ERROR boom");
+        let same = entry(Some("us-east-1"), "app/prod", "ERROR boom");
+        let other_message = entry(Some("us-east-1"), "app/prod", "ERROR bang");
+        let other_region = entry(Some("ap-northeast-1"), "app/prod", "ERROR boom");
+
+        assert_eq!(entry_id(&a), entry_id(&same));
+        assert_ne!(entry_id(&a), entry_id(&other_message));
+        assert_ne!(entry_id(&a), entry_id(&other_region));
+        assert_eq!(entry_id(&a).len(), 16);
+        // Pinned, so ids from earlier runs stay valid
+        assert_eq!(entry_id(&a), "13f8ca75f68a5c54");
+
+        let mut out = Vec::new();
+        display_results(vec![a.clone()], &DisplayOptions::new(OutputMode::JsonCompact), &mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["results"][0]["id"], entry_id(&a));
+        assert_eq!(json["results"][0]["message"], "ERROR boom");
+    }
+
+    #[test]
+    fn test_json_includes_record_pointer() {
+        let with_ptr = LogEntry { ptr: Some("CmAKJgoi".to_string()), ..entry(None, "app/prod", "ERROR boom") };
+        let without = entry(None, "app/prod", "ERROR bang");
+
+        let mut out = Vec::new();
+        display_results(vec![with_ptr.clone(), without], &DisplayOptions::new(OutputMode::JsonCompact), &mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let results = json["results"].as_array().unwrap();
+        let pointers: Vec<Option<&str>> = results.iter().map(|e| e.get("ptr").and_then(|p| p.as_str())).collect();
+        assert!(pointers.contains(&Some("CmAKJgoi")));
+        assert!(pointers.contains(&None));
+
+        // The pointer differs between runs, so it isn't part of the stable id
+        assert_eq!(entry_id(&with_ptr), entry_id(&entry(None, "app/prod", "ERROR boom")));
+    }
+
+    #[test]
+    fn test_json_entries_carry_detected_level() {
+        let entries = vec![
+            entry(None, "app/prod", "WARN retrying"),
+            entry(None, "app/prod", "GET /health 200"),
+        ];
+        let mut out = Vec::new();
+        display_results(entries, &DisplayOptions::new(OutputMode::JsonCompact), &mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["results"][0]["level"], "WARN");
+        // No level detected: the field is left out
+        assert!(json["results"][1].get("level").is_none());
+    }
+
+    #[test]
+    fn test_page_slicing() {
+        let page = |number| Page { number, size: 100 };
+        assert_eq!(page(1).range(450), 0..100);
+        assert_eq!(page(2).range(450), 100..200);
+        // The last page is partial, and pages past it are empty
+        assert_eq!(page(5).range(450), 400..450);
+        assert_eq!(page(6).range(450), 450..450);
+        assert_eq!(page(5).count(450), 5);
+        assert_eq!(page(1).count(400), 4);
+        assert_eq!(page(1).count(0), 1);
+
+        colored::control::set_override(false);
+        let entries: Vec<LogEntry> = (0..5).map(|i| entry(None, "app/prod", &format!("line {}", i))).collect();
+        let options = DisplayOptions::new(OutputMode::Interleaved).with_page(Some(Page { number: 3, size: 2 }));
+        let mut out = Vec::new();
+        assert_eq!(display_results(entries, &options, &mut out).unwrap(), 1);
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("line 4"));
+        assert!(!text.contains("line 3"));
+        // The "Page 3/3" footer goes to stderr
+        assert!(!text.contains("Page 3/3"));
+    }
+
+    #[test]
+    fn test_timestamp_bounds_clip_results() {
+        let at = |minute: u32| entry_at(minute, &format!("minute {}", minute));
+        let entries: Vec<LogEntry> = (0..6).map(|m| at(m * 10)).collect();
+
+        let options = DisplayOptions::new(OutputMode::JsonCompact)
+            .with_timestamp_bounds(Some("2026-01-23 05:10"), Some("2026-01-23T05:30:00Z"))
+            .unwrap();
+        let mut out = Vec::new();
+        // Both bounds are inclusive
+        assert_eq!(display_results(entries.clone(), &options, &mut out).unwrap(), 3);
+        assert!(options.shows(&at(10)) && options.shows(&at(30)));
+        assert!(!options.shows(&at(0)) && !options.shows(&at(40)));
+
+        let open_ended = DisplayOptions::default().with_timestamp_bounds(Some("2026-01-23 05:45"), None).unwrap();
+        assert_eq!(entries.iter().filter(|e| open_ended.shows(e)).count(), 1);
+
+        assert!(DisplayOptions::default().with_timestamp_bounds(None, Some("yesterday")).is_err());
+    }
+
+    #[test]
+    fn test_exclude_ranges_drop_windows() {
+        let at = |minute: u32| entry_at(minute, &format!("minute {}", minute));
+        let entries: Vec<LogEntry> = (0..12).map(|m| at(m * 5)).collect();
+
+        let options = DisplayOptions::new(OutputMode::JsonCompact)
+            .with_exclude_ranges(&[
+                "2026-01-23 05:10..2026-01-23 05:20".to_string(),
+                "2026-01-23T05:50:00Z .. 2026-01-23T05:55:00Z".to_string(),
+            ])
+            .unwrap();
+        let mut out = Vec::new();
+        // 10, 15, 20 and 50, 55 fall inside the windows (ends included)
+        assert_eq!(display_results(entries, &options, &mut out).unwrap(), 7);
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let minutes: Vec<&str> = json["results"].as_array().unwrap().iter().map(|r| r["message"].as_str().unwrap()).collect();
+        assert_eq!(minutes, ["minute 0", "minute 5", "minute 25", "minute 30", "minute 35", "minute 40", "minute 45"]);
+
+        assert!(DisplayOptions::default().with_exclude_ranges(&["2026-01-23 05:10".to_string()]).is_err());
+        assert!(DisplayOptions::default().with_exclude_ranges(&["2026-01-23 06:00..2026-01-23 05:00".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_sample_is_sized_ordered_and_deterministic() {
+        let entries: Vec<LogEntry> = (0..1000).map(|i| entry(None, "app/prod", &format!("line {}", i))).collect();
+        let messages = |sampled: &[LogEntry]| sampled.iter().map(|e| e.message.clone()).collect::<Vec<_>>();
+
+        let first = sample(entries.clone(), 50, 42);
+        assert_eq!(first.len(), 50);
+        assert_eq!(messages(&first), messages(&sample(entries.clone(), 50, 42)));
+        assert_ne!(messages(&first), messages(&sample(entries.clone(), 50, 7)));
+
+        // Original order is kept, and the sample spans the whole set rather than one end
+        let positions: Vec<usize> = first.iter().map(|e| e.message[5..].parse().unwrap()).collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+        assert!(positions[0] < 200 && *positions.last().unwrap() > 800);
+
+        assert_eq!(sample(entries[..10].to_vec(), 50, 42).len(), 10);
+
+        colored::control::set_override(false);
+        let mut out = Vec::new();
+        let options = DisplayOptions::new(OutputMode::Interleaved).with_sample(Some(3));
+        assert_eq!(display_results(entries, &options, &mut out).unwrap(), 3);
+        // Only the sampled lines; the "Showing a sample" footer goes to stderr
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().filter(|line| !line.is_empty()).count(), 3, "{}", text);
+        assert!(!text.contains("Showing a sample"));
+    }
+
+    #[test]
+    fn test_search_summary_footer() {
+        colored::control::set_override(false);
+
+        let mut out = Vec::new();
+        let summary = SearchSummary {
+            results: 42,
+            sources: 3,
+            source_kind: "log group",
+            failed: 1,
+            elapsed: std::time::Duration::from_millis(2340),
+        };
+        write_summary(&mut out, &summary).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\n── 42 results from 3 log groups (1 failed) in 2.3s\n");
+
+        let mut out = Vec::new();
+        let summary = SearchSummary { results: 1, sources: 1, source_kind: "server", failed: 0, elapsed: Default::default() };
+        write_summary(&mut out, &summary).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\n── 1 result from 1 server in 0.0s\n");
+    }
+
+    #[test]
+    fn test_json_stream_matches_buffered_json() {
+        let range = TimeRange::resolve(Some("2026-01-31 11:00"), Some("2026-01-31 12:00"), "1h").unwrap();
+        let entries = vec![
+            entry(Some("us-east-1"), "app/prod", r#"{"level":"error","msg":"a \"quoted\", comma"}"#),
+            LogEntry { timestamp: Utc.with_ymd_and_hms(2026, 1, 23, 5, 36, 6).unwrap(), ..entry(None, "api/prod", "WARN second") },
+        ];
+
+        for (entries, time_range) in [(entries, Some(range)), (vec![], None)] {
+            let mut options = DisplayOptions::new(OutputMode::JsonCompact);
+            options.time_range = time_range;
+            let mut buffered = Vec::new();
+            display_results(entries.clone(), &options, &mut buffered).unwrap();
+
+            let mut streamed = Vec::new();
+            let mut writer = JsonStreamWriter::begin(&mut streamed, time_range).unwrap();
+            for entry in &entries {
+                writer.write(&mut streamed, entry).unwrap();
+            }
+            assert_eq!(writer.finish(&mut streamed).unwrap(), entries.len());
+
+            let streamed: serde_json::Value = serde_json::from_slice(&streamed).unwrap();
+            let buffered: serde_json::Value = serde_json::from_slice(&buffered).unwrap();
+            assert_eq!(streamed, buffered);
+        }
+    }
+
+    #[test]
+    fn test_json_reports_time_range() {
+        let end = Utc.with_ymd_and_hms(2026, 1, 31, 12, 0, 0).unwrap();
+        let range = TimeRange::ending_at("1h", end).unwrap();
+        let options = DisplayOptions::new(OutputMode::JsonCompact).with_time_range(range);
+
+        let mut out = Vec::new();
+        display_results(vec![], &options, &mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["time_range"]["start"], "2026-01-31T11:00:00Z");
+        assert_eq!(json["time_range"]["end"], "2026-01-31T12:00:00Z");
+    }
+
+    #[test]
+    fn test_json_compact_is_single_line() {
+        let output = JsonOutput { total: 0, truncated: false, time_range: None, results: vec![] };
+
+        let compact = to_json(&output, &OutputMode::JsonCompact).unwrap();
+        assert_eq!(compact, r#"{"total":0,"results":[]}"#);
+
+        let pretty = to_json(&output, &OutputMode::Json).unwrap();
+        assert!(pretty.contains('\n'));
     }
 }
@@ -0,0 +1,274 @@
+use colored::Colorize;
+use std::collections::VecDeque;
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+const FRAMES: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const TICK: Duration = Duration::from_millis(100);
+
+/// A stderr spinner shown while a long query runs; the line is cleared when it stops
+pub struct Spinner {
+    task: Option<JoinHandle<()>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl Spinner {
+    /// Start a spinner, unless output is being piped (stdout or stderr isn't a terminal)
+    pub fn start(message: String, enabled: bool) -> Self {
+        let stopped = Arc::new(AtomicBool::new(false));
+        if !enabled || !io::stdout().is_terminal() || !io::stderr().is_terminal() {
+            return Self { task: None, stopped };
+        }
+
+        let started = Instant::now();
+        let task_stopped = stopped.clone();
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TICK);
+            for frame in FRAMES.iter().cycle() {
+                interval.tick().await;
+                let mut stderr = io::stderr().lock();
+                // Checked under the lock so a frame can't land after the line was cleared
+                if task_stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                let _ = write!(stderr, "\r{} {} ({}s)", frame, message, started.elapsed().as_secs());
+                let _ = stderr.flush();
+            }
+        });
+
+        Self { task: Some(task), stopped }
+    }
+
+    /// Stop the spinner and clear its line so results start on a clean line
+    pub fn finish(mut self) {
+        self.clear();
+    }
+
+    fn clear(&mut self) {
+        if let Some(task) = self.task.take() {
+            let mut stderr = io::stderr().lock();
+            self.stopped.store(true, Ordering::SeqCst);
+            task.abort();
+            let _ = write!(stderr, "\r\x1b[2K");
+            let _ = stderr.flush();
+        }
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Status line kept under followed output on stderr: "● FOLLOWING • 42/s"
+/// Cleared before each entry is printed and redrawn after, so it stays on the last line
+pub struct FollowStatus {
+    enabled: bool,
+    rate: LineRate,
+    drawn_at: Option<Instant>,
+}
+
+impl FollowStatus {
+    /// Only shown when stderr is a terminal
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled: enabled && io::stderr().is_terminal(),
+            rate: LineRate::default(),
+            drawn_at: None,
+        }
+    }
+
+    /// Count followed lines towards the rate
+    pub fn record(&mut self, count: usize) {
+        self.rate.record(Instant::now(), count);
+    }
+
+    /// Redraw the status line
+    pub fn draw(&mut self) {
+        if !self.enabled {
+            return;
+        }
+        let now = Instant::now();
+        let mut stderr = io::stderr().lock();
+        let _ = write!(stderr, "\r\x1b[2K{} • {}", "● FOLLOWING".red(), self.rate.label(now));
+        let _ = stderr.flush();
+        self.drawn_at = Some(now);
+    }
+
+    /// Redraw once a second while no lines arrive, so the rate falls back as the window slides
+    pub fn tick(&mut self) {
+        if self.drawn_at.is_none_or(|at| at.elapsed() >= Duration::from_secs(1)) {
+            self.draw();
+        }
+    }
+
+    /// Clear the status line so the next output starts on a clean line
+    pub fn clear(&mut self) {
+        if self.drawn_at.take().is_some() {
+            let mut stderr = io::stderr().lock();
+            let _ = write!(stderr, "\r\x1b[2K");
+            let _ = stderr.flush();
+        }
+    }
+}
+
+/// Stops a follow once no matching lines have arrived for a while (--follow-idle-timeout)
+/// The clock starts at the first check, so time spent before following doesn't count
+#[derive(Debug, Clone, Default)]
+pub struct IdleTimer {
+    timeout: Option<Duration>,
+    last_activity: Option<Instant>,
+}
+
+impl IdleTimer {
+    /// A timer that never expires when `timeout` is None
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self { timeout, last_activity: None }
+    }
+
+    /// Lines arrived at `now`
+    pub fn activity(&mut self, now: Instant) {
+        self.last_activity = Some(now);
+    }
+
+    /// Whether the timeout has passed since the last activity (or the first check)
+    pub fn expired(&mut self, now: Instant) -> bool {
+        let Some(timeout) = self.timeout else {
+            return false;
+        };
+        let last = *self.last_activity.get_or_insert(now);
+        now.saturating_duration_since(last) >= timeout
+    }
+
+    /// Forget the last activity, for the next follow
+    pub fn reset(&mut self) {
+        self.last_activity = None;
+    }
+}
+
+/// Lines per second over a sliding window, for the follow-mode status
+#[derive(Debug)]
+pub struct LineRate {
+    window: Duration,
+    /// When the first lines arrived; the rate is averaged over less than the window until then
+    started: Option<Instant>,
+    arrivals: VecDeque<(Instant, usize)>,
+}
+
+impl Default for LineRate {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(10))
+    }
+}
+
+impl LineRate {
+    pub fn new(window: Duration) -> Self {
+        Self { window, started: None, arrivals: VecDeque::new() }
+    }
+
+    /// Record `count` lines arriving at `now`, dropping arrivals that have left the window
+    pub fn record(&mut self, now: Instant, count: usize) {
+        if count == 0 {
+            return;
+        }
+        self.started.get_or_insert(now);
+        self.arrivals.push_back((now, count));
+        self.prune(now);
+    }
+
+    /// Lines per second over the window ending at `now` (or since the first line, at least 1s)
+    pub fn per_second(&self, now: Instant) -> f64 {
+        let Some(started) = self.started else {
+            return 0.0;
+        };
+        let span = now.saturating_duration_since(started).clamp(Duration::from_secs(1), self.window);
+        let lines: usize = self
+            .arrivals
+            .iter()
+            .filter(|(at, _)| now.saturating_duration_since(*at) <= self.window)
+            .map(|(_, count)| count)
+            .sum();
+        lines as f64 / span.as_secs_f64()
+    }
+
+    /// The rate as shown in status lines: "4.2/s" below 10, "42/s" above
+    pub fn label(&self, now: Instant) -> String {
+        let rate = self.per_second(now);
+        if rate < 10.0 {
+            format!("{:.1}/s", rate)
+        } else {
+            format!("{:.0}/s", rate)
+        }
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while self.arrivals.front().is_some_and(|(at, _)| now.saturating_duration_since(*at) > self.window) {
+            self.arrivals.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_timer() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        let mut disabled = IdleTimer::new(None);
+        assert!(!disabled.expired(at(0)));
+        assert!(!disabled.expired(at(86_400)));
+
+        let mut timer = IdleTimer::new(Some(Duration::from_secs(60)));
+        // The first check starts the clock
+        assert!(!timer.expired(at(100)));
+        assert!(!timer.expired(at(159)));
+        assert!(timer.expired(at(160)));
+
+        // Each arrival pushes the deadline back
+        let mut timer = IdleTimer::new(Some(Duration::from_secs(60)));
+        assert!(!timer.expired(at(0)));
+        timer.activity(at(50));
+        assert!(!timer.expired(at(100)));
+        timer.activity(at(100));
+        assert!(!timer.expired(at(159)));
+        assert!(timer.expired(at(160)));
+
+        // A reset timer starts over at its next check
+        timer.reset();
+        assert!(!timer.expired(at(1000)));
+        assert!(timer.expired(at(1060)));
+    }
+
+    #[test]
+    fn test_line_rate_over_sliding_window() {
+        let start = Instant::now();
+        let at = |secs: f64| start + Duration::from_secs_f64(secs);
+        let mut rate = LineRate::new(Duration::from_secs(10));
+        assert_eq!(rate.per_second(start), 0.0);
+
+        // Less than a second in: averaged over 1s rather than spiking
+        rate.record(at(0.0), 5);
+        assert_eq!(rate.per_second(at(0.5)), 5.0);
+
+        // 5 + 15 lines over the first 4 seconds
+        rate.record(at(2.0), 15);
+        assert_eq!(rate.per_second(at(4.0)), 5.0);
+        assert_eq!(rate.label(at(4.0)), "5.0/s");
+
+        // A burst: 400 more lines; the full 10s window is used once it has passed
+        rate.record(at(10.0), 400);
+        assert_eq!(rate.per_second(at(10.0)), 42.0);
+        assert_eq!(rate.label(at(10.0)), "42/s");
+
+        // The early lines slide out of the window, then the burst does too
+        assert_eq!(rate.per_second(at(15.0)), 40.0);
+        assert_eq!(rate.per_second(at(21.0)), 0.0);
+    }
+}
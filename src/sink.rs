@@ -0,0 +1,230 @@
+use crate::cli::{OutputMode, PagerMode};
+use crate::clipboard;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+/// Pager used when $PAGER isn't set
+const DEFAULT_PAGER: &str = "less";
+
+/// Where search results are written: stdout, a file, a gzip-compressed file, the clipboard, or a pager
+pub enum OutputSink {
+    Stdout(io::Stdout),
+    File(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Clipboard(Vec<u8>),
+    Pager(Child, ChildStdin),
+}
+
+impl OutputSink {
+    /// Open the sink for `--output-file` (stdout when no path is given)
+    pub fn open(path: Option<&str>, gzip: bool) -> Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::Stdout(io::stdout()));
+        };
+
+        let file = File::create(path).with_context(|| format!("Failed to create output file: {}", path))?;
+        let writer = BufWriter::new(file);
+
+        Ok(if gzip {
+            Self::Gzip(GzEncoder::new(writer, Compression::default()))
+        } else {
+            Self::File(writer)
+        })
+    }
+
+    /// Collect the output for `--copy`, placed on the clipboard by `finish`
+    pub fn clipboard() -> Self {
+        Self::Clipboard(Vec::new())
+    }
+
+    /// Pipe results through $PAGER (`less` by default); stdout if the pager can't be started
+    /// LESS defaults to FRX like git does: keep colors, and exit right away when the output fits on one screen
+    pub fn pager() -> Self {
+        let pager = std::env::var("PAGER").ok().filter(|p| !p.trim().is_empty());
+        let mut command = if cfg!(unix) {
+            let mut command = Command::new("sh");
+            command.arg("-c").arg(pager.as_deref().unwrap_or(DEFAULT_PAGER));
+            command
+        } else {
+            Command::new(pager.as_deref().unwrap_or(DEFAULT_PAGER))
+        };
+        if std::env::var_os("LESS").is_none() {
+            command.env("LESS", "FRX");
+        }
+
+        match command.stdin(Stdio::piped()).spawn() {
+            Ok(mut child) => match child.stdin.take() {
+                Some(stdin) => Self::Pager(child, stdin),
+                None => Self::Stdout(io::stdout()),
+            },
+            Err(_) => Self::Stdout(io::stdout()),
+        }
+    }
+
+    /// Whether results should go through a pager: `auto` pages human-readable (non-streaming)
+    /// output on a terminal only, so pipes, scripts and JSON/raw/CSV consumers get plain stdout
+    pub fn wants_pager(pager: PagerMode, mode: &OutputMode, stdout_is_terminal: bool) -> bool {
+        match pager {
+            PagerMode::Always => true,
+            PagerMode::Never => false,
+            PagerMode::Auto => stdout_is_terminal && !mode.is_bare() && *mode != OutputMode::Streaming,
+        }
+    }
+
+    /// Whether output lands on the terminal (directly or through a pager), where colors belong
+    pub fn is_terminal_output(&self) -> bool {
+        matches!(self, Self::Stdout(_) | Self::Pager(..))
+    }
+
+    /// Flush everything to disk
+    /// For gzip this also writes the trailer, without which the file is truncated
+    /// For the clipboard, output that can't be copied (e.g. on a headless system) is printed instead
+    pub fn finish(self) -> Result<()> {
+        match self {
+            Self::Stdout(mut out) => out.flush()?,
+            Self::Pager(mut child, stdin) => {
+                // Closing stdin lets the pager see the end of the output; then wait until the user quits it
+                drop(stdin);
+                child.wait().context("Failed to wait for the pager")?;
+            }
+            Self::Clipboard(buffer) => {
+                let text = clipboard_text(&buffer);
+                match clipboard::copy(&text) {
+                    Ok(()) => eprintln!("Copied {} lines to the clipboard", text.lines().count()),
+                    Err(e) => {
+                        eprintln!("{} Could not copy to the clipboard ({:#}), printing instead", "Warning:".yellow(), e);
+                        let mut out = io::stdout();
+                        out.write_all(&buffer)?;
+                        out.flush()?;
+                    }
+                }
+            }
+            Self::File(mut writer) => writer.flush().context("Failed to write output file")?,
+            Self::Gzip(encoder) => {
+                encoder
+                    .finish()
+                    .and_then(|mut writer| writer.flush())
+                    .context("Failed to finish gzip output file")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Text placed on the clipboard for `--copy`: the results as they would print, without colors
+/// (`open_sink` turns them off for anything that isn't a terminal)
+fn clipboard_text(buffer: &[u8]) -> std::borrow::Cow<'_, str> {
+    String::from_utf8_lossy(buffer)
+}
+
+impl Write for OutputSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Stdout(out) => out.write(buf),
+            Self::File(writer) => writer.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+            Self::Clipboard(buffer) => buffer.write(buf),
+            // Quitting the pager early closes the pipe; drop the rest instead of failing
+            Self::Pager(_, stdin) => match stdin.write(buf) {
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(buf.len()),
+                result => result,
+            },
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout(out) => out.flush(),
+            Self::File(writer) => writer.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Clipboard(_) => Ok(()),
+            Self::Pager(_, stdin) => match stdin.flush() {
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+                result => result,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::{BufRead, BufReader};
+
+    #[test]
+    fn test_pager_only_for_terminal_human_output() {
+        // Not a terminal: auto never pages, so pipes and redirects get plain output
+        assert!(!OutputSink::wants_pager(PagerMode::Auto, &OutputMode::Interleaved, false));
+        assert!(OutputSink::wants_pager(PagerMode::Auto, &OutputMode::Interleaved, true));
+        assert!(!OutputSink::wants_pager(PagerMode::Auto, &OutputMode::Json, true));
+        assert!(!OutputSink::wants_pager(PagerMode::Auto, &OutputMode::Streaming, true));
+        assert!(!OutputSink::wants_pager(PagerMode::Auto, &OutputMode::Csv, true));
+        assert!(!OutputSink::wants_pager(PagerMode::Auto, &OutputMode::Raw, true));
+
+        assert!(OutputSink::wants_pager(PagerMode::Always, &OutputMode::Json, false));
+        assert!(!OutputSink::wants_pager(PagerMode::Never, &OutputMode::Interleaved, true));
+    }
+
+    #[test]
+    fn test_clipboard_text_is_the_plain_formatted_results() {
+        use crate::aws::LogEntry;
+        use crate::output::{self, DisplayOptions};
+        use chrono::{TimeZone, Utc};
+
+        colored::control::set_override(false);
+        let mut sink = OutputSink::clipboard();
+        assert!(!sink.is_terminal_output());
+
+        let entry = |second: u32, message: &str| LogEntry {
+            timestamp: Utc.with_ymd_and_hms(2026, 1, 23, 5, 36, second).unwrap(),
+            message: message.to_string(),
+            log_group: "/aws/app/prod".to_string(),
+            log_stream: None,
+            region: Some("us-east-1".to_string()),
+            also_in: Vec::new(),
+            ptr: None,
+        };
+        let options = DisplayOptions::new(OutputMode::Interleaved).with_quiet(true);
+        output::display_results(vec![entry(6, "second"), entry(5, "first")], &options, &mut sink).unwrap();
+
+        let OutputSink::Clipboard(buffer) = &sink else {
+            panic!("not a clipboard sink");
+        };
+        assert_eq!(
+            clipboard_text(buffer),
+            "2026-01-23 05:36:05.000 [us-east-1:prod] first\n2026-01-23 05:36:06.000 [us-east-1:prod] second\n"
+        );
+    }
+
+    #[test]
+    fn test_gzip_ndjson_round_trip() {
+        let path = std::env::temp_dir().join(format!("log-hound-sink-{}.ndjson.gz", std::process::id()));
+        let path_str = path.to_str().unwrap();
+
+        let lines = [
+            serde_json::json!({ "message": "first", "log_group": "app/prod" }),
+            serde_json::json!({ "message": "second", "log_group": "api/prod" }),
+        ];
+
+        let mut sink = OutputSink::open(Some(path_str), true).unwrap();
+        for line in &lines {
+            writeln!(sink, "{}", line).unwrap();
+        }
+        sink.finish().unwrap();
+
+        let reader = BufReader::new(GzDecoder::new(File::open(&path).unwrap()));
+        let read_back: Vec<serde_json::Value> = reader
+            .lines()
+            .map(|l| serde_json::from_str(&l.unwrap()).unwrap())
+            .collect();
+        assert_eq!(read_back, lines);
+
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,275 @@
+use crate::aws::LogEntry;
+use crate::cli::{MatchField, ResolvedSearch};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Get the directory used for persisted state (~/.local/state/log-hound on Linux)
+pub fn state_dir() -> PathBuf {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("log-hound")
+}
+
+/// 64-bit FNV-1a hash, stable across runs and Rust versions
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Build the checkpoint key for a search definition
+/// Searches over the same source, groups/deploy file, patterns, region and profile share a checkpoint
+pub fn checkpoint_key(search: &ResolvedSearch) -> String {
+    let mut groups = search.groups.clone();
+    groups.sort();
+
+    let mut definition = format!(
+        "{:?}|{}|{}|{}|{}",
+        search.source,
+        groups.join(","),
+        search.deploy_file.as_deref().unwrap_or(""),
+        search.patterns.join(","),
+        search.exclude.join(","),
+    );
+    // Appended only when set so existing checkpoints keep their keys
+    if let Some(stream) = &search.stream {
+        definition.push_str(&format!("|{:?}", stream));
+    }
+    if search.word {
+        definition.push_str("|word");
+    }
+    if search.invert_match {
+        definition.push_str("|invert");
+    }
+    if search.match_field != MatchField::Message {
+        definition.push_str(&format!("|field:{:?}", search.match_field));
+    }
+    if let Some(prefix) = &search.starts_with {
+        definition.push_str(&format!("|starts:{}", prefix));
+    }
+    if let Some(suffix) = &search.ends_with {
+        definition.push_str(&format!("|ends:{}", suffix));
+    }
+    if let Some(query) = &search.query {
+        definition.push_str(&format!("|query:{}", query));
+    }
+    // Unqualified groups live in a different account or region per --profile/--region
+    if let Some(region) = &search.region {
+        definition.push_str(&format!("|region:{}", region));
+    }
+    if let Some(profile) = &search.profile {
+        definition.push_str(&format!("|profile:{}", profile));
+    }
+    // --env picks the deploy file itself, so deploy_file is unset
+    if let Some(env) = &search.env {
+        definition.push_str(&format!("|env:{}", env));
+    }
+
+    format!("{:016x}", stable_hash(definition.as_bytes()))
+}
+
+/// TUI settings remembered between sessions
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TuiState {
+    /// Last AWS profile selected in the TUI
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+impl TuiState {
+    fn path() -> PathBuf {
+        state_dir().join("tui.json")
+    }
+
+    /// Load the saved TUI state, or defaults if missing or unreadable
+    pub fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create state directory: {:?}", dir))?;
+        }
+        fs::write(&path, serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write TUI state: {:?}", path))?;
+        Ok(())
+    }
+}
+
+/// Last seen timestamp for a search, persisted between runs
+#[derive(Debug, Serialize, Deserialize)]
+struct Checkpoint {
+    last_timestamp: DateTime<Utc>,
+}
+
+/// Stores `--since-last` checkpoints as one JSON file per search key
+pub struct CheckpointStore {
+    dir: PathBuf,
+}
+
+impl CheckpointStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Store checkpoints under the default state directory
+    pub fn default_location() -> Self {
+        Self::new(state_dir().join("checkpoints"))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// Load the last seen timestamp, or None on first run
+    pub fn load(&self, key: &str) -> Result<Option<DateTime<Utc>>> {
+        let path = self.path(key);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read checkpoint: {:?}", path))?;
+        let checkpoint: Checkpoint = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse checkpoint: {:?}", path))?;
+
+        Ok(Some(checkpoint.last_timestamp))
+    }
+
+    /// Save the last seen timestamp
+    pub fn save(&self, key: &str, last_timestamp: DateTime<Utc>) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create state directory: {:?}", self.dir))?;
+
+        let path = self.path(key);
+        let contents = serde_json::to_string(&Checkpoint { last_timestamp })?;
+        fs::write(&path, contents)
+            .with_context(|| format!("Failed to write checkpoint: {:?}", path))?;
+
+        Ok(())
+    }
+}
+
+/// Tracks the `--since-last` checkpoint for a single search run
+pub struct SinceLast {
+    store: CheckpointStore,
+    key: String,
+    /// Newest timestamp seen by the previous run (None on first run)
+    pub previous: Option<DateTime<Utc>>,
+    newest: Option<DateTime<Utc>>,
+}
+
+impl SinceLast {
+    /// Load the checkpoint for a search from the default state directory
+    pub fn load(search: &ResolvedSearch) -> Result<Self> {
+        let store = CheckpointStore::default_location();
+        let key = checkpoint_key(search);
+        let previous = store.load(&key)?;
+
+        Ok(Self {
+            store,
+            key,
+            previous,
+            newest: previous,
+        })
+    }
+
+    /// Whether an entry was not already seen by the previous run
+    pub fn is_new(&self, entry: &LogEntry) -> bool {
+        self.previous.is_none_or(|ts| entry.timestamp > ts)
+    }
+
+    /// Record an entry returned by this run
+    pub fn observe(&mut self, entry: &LogEntry) {
+        if self.newest.is_none_or(|ts| entry.timestamp > ts) {
+            self.newest = Some(entry.timestamp);
+        }
+    }
+
+    /// Persist the newest timestamp seen so the next run resumes after it
+    pub fn commit(&self) -> Result<()> {
+        match self.newest {
+            Some(ts) if self.newest != self.previous => self.store.save(&self.key, ts),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::LogSource;
+    use chrono::TimeZone;
+
+    fn search(groups: &[&str], patterns: &[&str]) -> ResolvedSearch {
+        ResolvedSearch {
+            source: LogSource::Cloudwatch,
+            groups: groups.iter().map(|s| s.to_string()).collect(),
+            patterns: patterns.iter().map(|s| s.to_string()).collect(),
+            since_last: true,
+            ..ResolvedSearch::default()
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_key_is_stable() {
+        let a = checkpoint_key(&search(&["app/prod", "api/prod"], &["ERROR"]));
+        let b = checkpoint_key(&search(&["api/prod", "app/prod"], &["ERROR"]));
+        let c = checkpoint_key(&search(&["app/prod", "api/prod"], &["WARN"]));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(a.len(), 16);
+
+        // Keys from before region/profile were part of it don't change when neither is set
+        assert_eq!(a, format!("{:016x}", stable_hash(b"Cloudwatch|api/prod,app/prod||ERROR|")));
+        let in_region = |region: &str| checkpoint_key(&ResolvedSearch { region: Some(region.to_string()), ..search(&["app/prod", "api/prod"], &["ERROR"]) });
+        assert_ne!(in_region("us-east-1"), a);
+        assert_ne!(in_region("us-east-1"), in_region("eu-west-1"));
+        let with_profile = ResolvedSearch { profile: Some("staging".to_string()), ..search(&["app/prod", "api/prod"], &["ERROR"]) };
+        assert_ne!(checkpoint_key(&with_profile), a);
+        let in_env = |env: &str| checkpoint_key(&ResolvedSearch { env: Some(env.to_string()), ..search(&["app/prod", "api/prod"], &["ERROR"]) });
+        assert_ne!(in_env("staging"), a);
+        assert_ne!(in_env("staging"), in_env("production"));
+    }
+
+    #[test]
+    fn test_checkpoint_key_covers_match_options() {
+        let base = || search(&["app/prod"], &["ERROR"]);
+        let keys = [
+            checkpoint_key(&base()),
+            checkpoint_key(&ResolvedSearch { invert_match: true, ..base() }),
+            checkpoint_key(&ResolvedSearch { match_field: MatchField::Stream, ..base() }),
+            checkpoint_key(&ResolvedSearch { match_field: MatchField::Any, ..base() }),
+            checkpoint_key(&ResolvedSearch { starts_with: Some("GET".to_string()), ..base() }),
+            checkpoint_key(&ResolvedSearch { ends_with: Some("GET".to_string()), ..base() }),
+        ];
+        let distinct: std::collections::HashSet<_> = keys.iter().collect();
+        assert_eq!(distinct.len(), keys.len(), "{:?}", keys);
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let dir = std::env::temp_dir().join(format!("log-hound-checkpoints-{}", std::process::id()));
+        let store = CheckpointStore::new(dir.clone());
+
+        assert_eq!(store.load("abc").unwrap(), None);
+
+        let ts = Utc.with_ymd_and_hms(2026, 1, 23, 5, 36, 5).unwrap();
+        store.save("abc", ts).unwrap();
+        assert_eq!(store.load("abc").unwrap(), Some(ts));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -1,9 +1,12 @@
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Duration, Utc};
 use regex::Regex;
+use serde::Serialize;
 use std::sync::LazyLock;
 
 /// Represents a time range for log queries
+/// Serializes as `{ "start": ..., "end": ... }` in RFC3339, as written in JSON output
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub struct TimeRange {
     pub start: DateTime<Utc>,
     pub end: DateTime<Utc>,
@@ -26,16 +29,61 @@ impl TimeRange {
 
     /// Create a time range relative to now (e.g., "1h", "30m", "2d")
     pub fn from_relative(duration_str: &str) -> Result<Self> {
+        Self::ending_at(duration_str, Utc::now())
+    }
+
+    /// Create a time range of the given duration ending at `end`
+    pub fn ending_at(duration_str: &str, end: DateTime<Utc>) -> Result<Self> {
         let duration = parse_duration(duration_str)?;
-        let end = Utc::now();
-        let start = end - duration;
+        Ok(Self { start: end - duration, end })
+    }
+
+    /// Resolve the search options: --start[/--end] is explicit, --end alone anchors the --last
+    /// window to end there, otherwise --last ends now
+    pub fn resolve(start: Option<&str>, end: Option<&str>, last: &str) -> Result<Self> {
+        match (start, end) {
+            (Some(start), end) => Self::from_explicit(start, end),
+            (None, Some(end)) => Self::ending_at(last, parse_datetime(end)?),
+            (None, None) => Self::from_relative(last),
+        }
+    }
+
+    /// Parse `<start>..<end>` (e.g. for --exclude-range), each side in a --start/--end format
+    pub fn parse_span(spec: &str) -> Result<Self> {
+        let (start, end) = spec
+            .split_once("..")
+            .ok_or_else(|| anyhow!("Invalid range '{}': expected <start>..<end>", spec))?;
+        let range = Self {
+            start: parse_datetime(start.trim())?,
+            end: parse_datetime(end.trim())?,
+        };
+        if range.start > range.end {
+            return Err(anyhow!("Invalid range '{}': start is after end", spec));
+        }
+        Ok(range)
+    }
+
+    /// Whether a time falls inside the range (both ends included)
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.start <= time && time <= self.end
+    }
+
+    /// Create a time range from a start time up to now
+    pub fn since(start: DateTime<Utc>) -> Self {
+        Self {
+            start,
+            end: Utc::now(),
+        }
+    }
 
-        Ok(Self { start, end })
+    /// The last `duration` of the range, or all of it when it's shorter
+    pub fn tail(&self, duration: Duration) -> Self {
+        Self { start: self.start.max(self.end - duration), end: self.end }
     }
 }
 
 /// Parse a datetime string into UTC DateTime
-fn parse_datetime(input: &str) -> Result<DateTime<Utc>> {
+pub fn parse_datetime(input: &str) -> Result<DateTime<Utc>> {
     // Try RFC3339 first
     if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
         return Ok(dt.with_timezone(&Utc));
@@ -77,7 +125,7 @@ static DURATION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
 ///   - Combined: "1h30m", "2d12h", "1w2d"
 ///   - Decimals: "1.5h", "0.5d"
 ///   - Verbose: "2hours", "30mins", "1week"
-fn parse_duration(input: &str) -> Result<Duration> {
+pub fn parse_duration(input: &str) -> Result<Duration> {
     let input = input.trim().to_lowercase();
 
     if input.is_empty() {
@@ -115,6 +163,25 @@ fn parse_duration(input: &str) -> Result<Duration> {
     Ok(Duration::seconds(total_seconds as i64))
 }
 
+/// A positive duration string as a std Duration, for timers (--follow-idle-timeout)
+pub fn parse_timeout(input: &str) -> Result<std::time::Duration> {
+    let duration = parse_duration(input)?.to_std()?;
+    if duration.is_zero() {
+        return Err(anyhow!("Timeout '{}' must be longer than zero", input.trim()));
+    }
+    Ok(duration)
+}
+
+/// Check a strftime pattern for timestamps in human output (--time-format), e.g. "%H:%M:%S" or "%s"
+pub fn validate_time_format(format: &str) -> Result<()> {
+    use chrono::format::{Item, StrftimeItems};
+
+    if format.is_empty() || StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(anyhow!("Invalid time format '{}': expected a strftime pattern such as %Y-%m-%d %H:%M:%S", format));
+    }
+    Ok(())
+}
+
 /// Convert a duration string to Docker's --since format
 /// Docker accepts: "1h30m", "2h", "30m", etc.
 pub fn to_docker_since(duration_str: &str) -> Result<String> {
@@ -169,6 +236,28 @@ mod tests {
         assert_eq!(parse_duration("1D").unwrap(), Duration::days(1));
     }
 
+    #[test]
+    fn test_resolve_time_range() {
+        let end = parse_datetime("2026-01-01 12:00").unwrap();
+
+        // --last only: the window ends now
+        let range = TimeRange::resolve(None, None, "1h").unwrap();
+        assert_eq!(range.end - range.start, Duration::hours(1));
+        assert!((Utc::now() - range.end).num_seconds() < 5);
+
+        // --start/--end: explicit, --last is ignored
+        let range = TimeRange::resolve(Some("2026-01-01 09:00"), Some("2026-01-01 12:00"), "1h").unwrap();
+        assert_eq!(range.start, parse_datetime("2026-01-01 09:00").unwrap());
+        assert_eq!(range.end, end);
+
+        // --last with --end: the window ending at --end
+        let range = TimeRange::resolve(None, Some("2026-01-01 12:00"), "1h").unwrap();
+        assert_eq!(range.start, parse_datetime("2026-01-01 11:00").unwrap());
+        assert_eq!(range.end, end);
+
+        assert!(TimeRange::resolve(None, Some("noon"), "1h").is_err());
+    }
+
     #[test]
     fn test_invalid_input() {
         assert!(parse_duration("").is_err());
@@ -1,22 +1,30 @@
-use crate::aws::{LogEntry, MultiRegionSearcher, SearchParams};
+use crate::aws::{ConsoleQuery, LogEntry, MultiRegionSearcher, QueryStats, SearchParams};
+use crate::cli::split_list;
 use crate::config::Config;
-use crate::kamal::{KamalSearcher, KamalSearchParams};
-use crate::time::TimeRange;
+use crate::display::group_key;
+use crate::progress::{IdleTimer, LineRate};
+use crate::state::TuiState;
+use crate::kamal::{KamalSearcher, KamalSearchParams, DEFAULT_DEPLOY_FILE};
+use crate::time::{self, TimeRange};
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use chrono::SecondsFormat;
 use crossterm::{
+    cursor::Show,
     event::{self, Event, KeyCode, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io;
+use std::io::{self, IsTerminal};
 use tokio::sync::mpsc;
 
 use super::ui;
 
+/// Time ranges offered when the config has no tui_time_ranges
 const TIME_RANGES: &[(&str, &str)] = &[
     ("5m", "5 minutes"),
     ("15m", "15 minutes"),
@@ -30,8 +38,32 @@ const TIME_RANGES: &[(&str, &str)] = &[
     ("1w", "1 week"),
 ];
 
+/// Limits offered when the config has no tui_limits
 const LIMIT_OPTIONS: &[i32] = &[100, 500, 1000, 5000, 10000];
 
+/// Preset selected at startup (1 hour / 1000 with the built-in lists), clamped to shorter lists
+const DEFAULT_TIME_RANGE_INDEX: usize = 3;
+const DEFAULT_LIMIT_INDEX: usize = 2;
+
+// Follow mode drops the oldest entries beyond this many
+const MAX_FOLLOW_RESULTS: usize = 10000;
+
+/// Timestamp format in the results list, unless time_format is configured
+const DEFAULT_TIME_FORMAT: &str = "%H:%M:%S%.3f";
+
+/// History loaded when following starts from Results, unless follow_backfill is configured
+const DEFAULT_FOLLOW_BACKFILL: &str = "5m";
+
+/// Wait this long after focus leaves changed Regions before reloading log groups,
+/// so tabbing back and forth doesn't list them again each time
+const GROUP_RELOAD_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// How often CloudWatch follow mode polls when Live Tail isn't available
+const FOLLOW_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Minutes either side of an entry that its console link ('o' on Results) covers
+const CONSOLE_LINK_WINDOW_MINUTES: i64 = 5;
+
 // Common AWS regions
 const AWS_REGIONS: &[&str] = &[
     "ap-east-1",
@@ -55,6 +87,7 @@ const AWS_REGIONS: &[&str] = &[
     "us-west-2",
 ];
 
+// Regions selected at startup when the config has no tui_default_regions
 const DEFAULT_ENABLED_REGIONS: &[&str] = &["ap-east-2", "ap-northeast-1"];
 
 #[derive(Debug, Clone, PartialEq)]
@@ -70,6 +103,50 @@ pub enum Focus {
     Results,
 }
 
+/// What is shown before each message in the results list (cycled with 'v')
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum ResultsView {
+    /// Time, region and group abbreviations
+    #[default]
+    Full,
+    /// Time, group abbreviation and log stream
+    Stream,
+    NoRegion,
+    MessageOnly,
+    /// Full timestamp and log group name, unabbreviated
+    Raw,
+}
+
+impl ResultsView {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Full => Self::Stream,
+            Self::Stream => Self::NoRegion,
+            Self::NoRegion => Self::MessageOnly,
+            Self::MessageOnly => Self::Raw,
+            Self::Raw => Self::Full,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Stream => "stream",
+            Self::NoRegion => "no region",
+            Self::MessageOnly => "message",
+            Self::Raw => "raw",
+        }
+    }
+}
+
+/// Which end of the absolute time range is being edited
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum TimeInputField {
+    #[default]
+    Start,
+    End,
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub enum SourceMode {
     #[default]
@@ -82,7 +159,11 @@ pub enum SearchState {
     Idle,
     LoadingGroups,
     Searching,
+    /// Re-running the last search ('r'), with its results still shown
+    Refreshing,
     Complete(usize),
+    /// Following stopped by follow_idle_timeout, with this many results
+    IdleStopped(usize),
     Error(String),
 }
 
@@ -102,12 +183,30 @@ pub struct LogGroupItem {
 pub struct App {
     pub patterns_input: String,
     pub exclude_input: String,
+    /// Excluded from every search (config global_exclude)
+    pub global_exclude: Vec<String>,
+    /// Lines fetched per wanted result when tailing Kamal containers (config kamal.tail_multiplier)
+    pub tail_multiplier: usize,
+    /// Relative time range presets as (value, label)
+    pub time_ranges: Vec<(String, String)>,
     pub time_range_index: usize,
+    pub limit_options: Vec<i32>,
     pub limit_index: usize,
+
+    // Absolute time range (edited with 'e' on the Time Range panel)
+    // An empty start falls back to the preset range
+    pub time_input_mode: bool,
+    pub time_input_field: TimeInputField,
+    pub time_start_input: String,
+    pub time_end_input: String,
+
     pub focus: Focus,
     pub results: Vec<LogEntry>,
     pub results_scroll: usize,
+    pub results_view: ResultsView,
     pub search_state: SearchState,
+    /// Insights scan statistics of the last CloudWatch search
+    pub query_stats: Option<QueryStats>,
     pub should_quit: bool,
 
     // Source selection (CloudWatch vs Kamal)
@@ -121,53 +220,122 @@ pub struct App {
     // Region selection (CloudWatch only)
     pub regions: Vec<RegionItem>,
     pub regions_cursor: usize,
+    pub regions_filter: String,
 
     // Log group selection (CloudWatch only)
     pub log_groups: Vec<LogGroupItem>,
     pub log_groups_cursor: usize,
     pub log_groups_filter: String,
 
-    // Track if we need to reload log groups
+    // Track if we need to reload log groups, and when the debounced reload is due
     pub regions_changed: bool,
+    pub group_reload_due: Option<std::time::Instant>,
 
     // Horizontal scroll for results
     pub horizontal_scroll: usize,
 
-    // Selected result index
-    pub selected_result: Option<usize>,
-
     // Show help overlay
     pub show_help: bool,
 
+    // AWS profile switcher (Ctrl+P)
+    pub profile: Option<String>,
+    pub show_profile_modal: bool,
+    pub profile_input: String,
+
+    // In-results filter ('/' on Results) and full-message detail popup (Enter on Results)
+    pub results_filter: String,
+    pub results_filter_editing: bool,
+    pub show_detail: bool,
+    // Results split into per-log-group sections ('s' on Results)
+    pub results_grouped: bool,
+    // Results colored by detected log level instead of log group ('c' on Results)
+    pub results_color_by_level: bool,
+    /// Shown in the status box until the next key, e.g. a console link that couldn't be opened ('o' on Results)
+    pub notice: Option<String>,
+    /// strftime pattern for result timestamps (config time_format)
+    pub time_format: String,
+    /// Region labels in place of abbreviations (config region_aliases)
+    pub region_aliases: HashMap<String, String>,
+
     // Follow mode - stream logs in real-time
     pub follow_mode: bool,
     pub is_following: bool,
     pub follow_receiver: Option<mpsc::Receiver<LogEntry>>,
+    /// Lines per second arriving while following
+    pub follow_rate: LineRate,
+    /// Stops following after follow_idle_timeout from the config without new lines
+    pub follow_idle: IdleTimer,
     pub follow_stop_flag: Option<Arc<AtomicBool>>,
+    /// How far back to search before streaming when 'f' starts following (config follow_backfill)
+    pub follow_backfill: String,
 }
 
 impl App {
-    pub fn new(_config: &Config) -> Self {
-        let regions: Vec<RegionItem> = AWS_REGIONS
+    pub fn new(config: &Config) -> Self {
+        // Pre-select the configured regions plus the default region (--region / default_region)
+        let mut enabled: Vec<String> = if config.tui_default_regions.is_empty() {
+            DEFAULT_ENABLED_REGIONS.iter().map(|r| r.to_string()).collect()
+        } else {
+            config.tui_default_regions.clone()
+        };
+        enabled.extend(config.default_region.clone());
+
+        let mut regions: Vec<RegionItem> = AWS_REGIONS
             .iter()
             .map(|&r| RegionItem {
                 name: r.to_string(),
-                selected: DEFAULT_ENABLED_REGIONS.contains(&r),
+                selected: enabled.iter().any(|e| e == r),
             })
             .collect();
 
+        // Regions missing from the built-in list are added so they can still be selected
+        for region in enabled {
+            if !regions.iter().any(|r| r.name == region) {
+                regions.push(RegionItem { name: region, selected: true });
+            }
+        }
+
         // Detect deploy files from config/ folder
         let deploy_files = Self::detect_deploy_files();
 
+        // Configured lists replace the built-in ones; built-in labels are kept for known values
+        let time_ranges: Vec<(String, String)> = if config.tui_time_ranges.is_empty() {
+            TIME_RANGES.iter().map(|(value, label)| (value.to_string(), label.to_string())).collect()
+        } else {
+            config
+                .tui_time_ranges
+                .iter()
+                .map(|value| {
+                    let label = TIME_RANGES.iter().find(|(v, _)| v == value).map_or(value.as_str(), |(_, l)| l);
+                    (value.clone(), label.to_string())
+                })
+                .collect()
+        };
+        let limit_options = if config.tui_limits.is_empty() {
+            LIMIT_OPTIONS.to_vec()
+        } else {
+            config.tui_limits.clone()
+        };
+
         Self {
             patterns_input: String::new(),
             exclude_input: String::new(),
-            time_range_index: 3,
-            limit_index: 2,
+            global_exclude: config.global_exclude.clone(),
+            tail_multiplier: config.kamal.tail_multiplier(),
+            time_range_index: DEFAULT_TIME_RANGE_INDEX.min(time_ranges.len() - 1),
+            limit_index: DEFAULT_LIMIT_INDEX.min(limit_options.len() - 1),
+            time_ranges,
+            limit_options,
+            time_input_mode: false,
+            time_input_field: TimeInputField::Start,
+            time_start_input: String::new(),
+            time_end_input: String::new(),
             focus: Focus::Source,
             results: Vec::new(),
             results_scroll: 0,
+            results_view: ResultsView::Full,
             search_state: SearchState::Idle,
+            query_stats: None,
             should_quit: false,
             source_mode: SourceMode::CloudWatch,
             deploy_files,
@@ -175,17 +343,35 @@ impl App {
             deploy_files_filter: String::new(),
             regions,
             regions_cursor: 0,
+            regions_filter: String::new(),
             log_groups: Vec::new(),
             log_groups_cursor: 0,
             log_groups_filter: String::new(),
             regions_changed: true,
+            group_reload_due: None,
             horizontal_scroll: 0,
-            selected_result: None,
             show_help: false,
+            profile: None,
+            show_profile_modal: false,
+            profile_input: String::new(),
+            results_filter: String::new(),
+            results_filter_editing: false,
+            show_detail: false,
+            notice: None,
+            results_grouped: false,
+            results_color_by_level: false,
+            time_format: config.time_format.clone().unwrap_or_else(|| DEFAULT_TIME_FORMAT.to_string()),
+            region_aliases: config.region_aliases.clone(),
             follow_mode: false,
             is_following: false,
             follow_receiver: None,
             follow_stop_flag: None,
+            follow_backfill: config.follow_backfill.clone().unwrap_or_else(|| DEFAULT_FOLLOW_BACKFILL.to_string()),
+            follow_rate: LineRate::default(),
+            // Validated when the config was loaded
+            follow_idle: IdleTimer::new(
+                config.follow_idle_timeout.as_deref().and_then(|timeout| crate::time::parse_timeout(timeout).ok()),
+            ),
         }
     }
 
@@ -204,18 +390,111 @@ impl App {
         self.is_following = false;
         self.follow_receiver = None;
         self.follow_stop_flag = None;
+        self.follow_rate = LineRate::default();
+        self.follow_idle.reset();
         self.search_state = SearchState::Complete(self.results.len());
     }
 
+    /// Stop following once follow_idle_timeout has passed without new lines
+    pub fn check_follow_idle(&mut self, now: std::time::Instant) {
+        if self.is_following && self.follow_idle.expired(now) {
+            self.stop_following();
+            self.search_state = SearchState::IdleStopped(self.results.len());
+        }
+    }
+
+    /// Schedule a log group reload when focus has left Regions with changed regions, by any key
+    /// Coming back to Regions cancels it; the reload waits until focus leaves again
+    pub fn focus_changed(&mut self, previous: &Focus, now: std::time::Instant) {
+        if self.focus == Focus::Regions {
+            self.group_reload_due = None;
+        } else if *previous == Focus::Regions && self.regions_changed && self.source_mode == SourceMode::CloudWatch {
+            self.group_reload_due = Some(now + GROUP_RELOAD_DEBOUNCE);
+        }
+    }
+
+    /// Whether the debounced log group reload is due
+    pub fn group_reload_ready(&self, now: std::time::Instant) -> bool {
+        self.regions_changed && self.group_reload_due.is_some_and(|due| now >= due)
+    }
+
+    /// Whether the loaded log groups lag behind the region selection, so a search must reload first
+    pub fn groups_stale(&self) -> bool {
+        self.source_mode == SourceMode::CloudWatch && self.regions_changed
+    }
+
     pub fn time_range_label(&self) -> &str {
-        TIME_RANGES[self.time_range_index].1
+        &self.time_ranges[self.time_range_index].1
     }
 
     pub fn time_range_value(&self) -> &str {
-        TIME_RANGES[self.time_range_index].0
+        &self.time_ranges[self.time_range_index].0
+    }
+
+    /// Whether an absolute start time has been entered
+    pub fn has_absolute_time_range(&self) -> bool {
+        !self.time_start_input.trim().is_empty()
+    }
+
+    /// Resolve the window to search: the absolute range if entered, otherwise the selected preset
+    pub fn time_range(&self) -> Result<TimeRange> {
+        if self.has_absolute_time_range() {
+            let end = Some(self.time_end_input.trim()).filter(|e| !e.is_empty());
+            TimeRange::from_explicit(self.time_start_input.trim(), end)
+        } else {
+            TimeRange::from_relative(self.time_range_value())
+        }
+    }
+
+    /// Docker `--since` value for the current time range
+    pub fn docker_since(&self) -> Result<String> {
+        if self.has_absolute_time_range() {
+            Ok(self.time_range()?.start.to_rfc3339_opts(SecondsFormat::Secs, true))
+        } else {
+            time::to_docker_since(self.time_range_value())
+        }
+    }
+
+    pub fn is_editing_time_range(&self) -> bool {
+        self.focus == Focus::TimeRange && self.time_input_mode
+    }
+
+    /// Whether letter keys go into a text input or list filter rather than acting as shortcuts
+    pub fn is_typing(&self) -> bool {
+        matches!(
+            self.focus,
+            Focus::Patterns | Focus::Exclude | Focus::Regions | Focus::LogGroups | Focus::DeployFile
+        ) || self.is_editing_time_range()
+    }
+
+    pub fn toggle_time_input_mode(&mut self) {
+        self.time_input_mode = !self.time_input_mode;
+        self.time_input_field = TimeInputField::Start;
+    }
+
+    pub fn toggle_time_input_field(&mut self) {
+        self.time_input_field = match self.time_input_field {
+            TimeInputField::Start => TimeInputField::End,
+            TimeInputField::End => TimeInputField::Start,
+        };
+    }
+
+    pub fn time_input_mut(&mut self) -> &mut String {
+        match self.time_input_field {
+            TimeInputField::Start => &mut self.time_start_input,
+            TimeInputField::End => &mut self.time_end_input,
+        }
+    }
+
+    /// Drop the absolute range and go back to the presets
+    pub fn clear_absolute_time_range(&mut self) {
+        self.time_start_input.clear();
+        self.time_end_input.clear();
     }
 
     pub fn next_focus(&mut self) {
+        self.time_input_mode = false;
+        self.results_filter_editing = false;
         self.focus = match (&self.focus, &self.source_mode) {
             (Focus::Source, SourceMode::CloudWatch) => Focus::Patterns,
             (Focus::Source, SourceMode::Kamal) => Focus::DeployFile,
@@ -232,6 +511,8 @@ impl App {
     }
 
     pub fn prev_focus(&mut self) {
+        self.time_input_mode = false;
+        self.results_filter_editing = false;
         self.focus = match (&self.focus, &self.source_mode) {
             (Focus::Source, _) => Focus::Results,
             (Focus::DeployFile, _) => Focus::Source,
@@ -259,39 +540,39 @@ impl App {
         };
     }
 
-    /// Detect deploy*.yml files from config/ folder
+    /// Detect deploy*.yml files from config/, .kamal/ and the project root
     fn detect_deploy_files() -> Vec<String> {
-        let config_path = Path::new("config");
-        let mut files = Vec::new();
-
-        if let Ok(entries) = std::fs::read_dir(config_path) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    if name.starts_with("deploy") && name.ends_with(".yml") {
-                        files.push(path.to_string_lossy().to_string());
-                    }
-                }
-            }
-        }
+        let mut files: Vec<String> = crate::kamal::find_deploy_files(Path::new("."))
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
 
         // Sort by simplified display name (alphabetically)
-        files.sort_by(|a, b| {
-            let name_a = Self::extract_deploy_name(a);
-            let name_b = Self::extract_deploy_name(b);
-            name_a.cmp(&name_b)
-        });
+        let all = files.clone();
+        files.sort_by_cached_key(|file| Self::extract_deploy_name(file, &all));
 
         // If no files found, add a default
         if files.is_empty() {
-            files.push("config/deploy.yml".to_string());
+            files.push(DEFAULT_DEPLOY_FILE.to_string());
         }
 
         files
     }
 
     /// Extract simplified name from deploy file path: deploy.production.yml -> production
-    fn extract_deploy_name(path: &str) -> String {
+    /// When another of `files` has the same name (config/deploy.yml and .kamal/deploy.yml), the
+    /// directory is kept so they can be told apart: config/default, .kamal/default
+    pub fn extract_deploy_name(path: &str, files: &[String]) -> String {
+        let name = Self::deploy_name(path);
+        let ambiguous = files.iter().any(|other| other != path && Self::deploy_name(other) == name);
+        match Path::new(path).parent().and_then(|dir| dir.to_str()) {
+            Some(dir) if ambiguous && !dir.is_empty() => format!("{}/{}", dir, name),
+            _ => name,
+        }
+    }
+
+    /// A deploy file's name without its directory: deploy.yml -> default
+    fn deploy_name(path: &str) -> String {
         let filename = Path::new(path)
             .file_name()
             .and_then(|n| n.to_str())
@@ -303,22 +584,18 @@ impl App {
             .trim_end_matches(".yml")
             .trim_end_matches(".yaml");
 
-        if name.is_empty() || name == "yml" {
-            "default".to_string()
-        } else {
-            name.to_string()
-        }
+        if name.is_empty() || name == "yml" { "default" } else { name }.to_string()
     }
 
     /// Get the currently selected deploy file
     pub fn selected_deploy_file(&self) -> &str {
         let filtered = self.filtered_deploy_files_indices();
         if let Some(&idx) = filtered.iter().find(|&&i| i == self.deploy_files_cursor) {
-            self.deploy_files.get(idx).map(|s| s.as_str()).unwrap_or("config/deploy.yml")
+            self.deploy_files.get(idx).map(|s| s.as_str()).unwrap_or(DEFAULT_DEPLOY_FILE)
         } else if let Some(&first_idx) = filtered.first() {
-            self.deploy_files.get(first_idx).map(|s| s.as_str()).unwrap_or("config/deploy.yml")
+            self.deploy_files.get(first_idx).map(|s| s.as_str()).unwrap_or(DEFAULT_DEPLOY_FILE)
         } else {
-            "config/deploy.yml"
+            DEFAULT_DEPLOY_FILE
         }
     }
 
@@ -332,7 +609,7 @@ impl App {
             .iter()
             .enumerate()
             .filter(|(_, path)| {
-                let name = Self::extract_deploy_name(path).to_lowercase();
+                let name = Self::extract_deploy_name(path, &self.deploy_files).to_lowercase();
                 name.contains(&filter_lower)
             })
             .map(|(i, _)| i)
@@ -371,23 +648,25 @@ impl App {
     }
 
     pub fn next_time_range(&mut self) {
-        if self.time_range_index < TIME_RANGES.len() - 1 {
+        self.clear_absolute_time_range();
+        if self.time_range_index < self.time_ranges.len() - 1 {
             self.time_range_index += 1;
         }
     }
 
     pub fn prev_time_range(&mut self) {
+        self.clear_absolute_time_range();
         if self.time_range_index > 0 {
             self.time_range_index -= 1;
         }
     }
 
     pub fn limit_value(&self) -> i32 {
-        LIMIT_OPTIONS[self.limit_index]
+        self.limit_options[self.limit_index]
     }
 
     pub fn next_limit(&mut self) {
-        if self.limit_index < LIMIT_OPTIONS.len() - 1 {
+        if self.limit_index < self.limit_options.len() - 1 {
             self.limit_index += 1;
         }
     }
@@ -398,29 +677,194 @@ impl App {
         }
     }
 
-    // Region navigation
+    /// Get filtered region indices based on current filter
+    pub fn filtered_regions_indices(&self) -> Vec<usize> {
+        if self.regions_filter.is_empty() {
+            return (0..self.regions.len()).collect();
+        }
+        let filter_lower = self.regions_filter.to_lowercase();
+        self.regions
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.name.to_lowercase().contains(&filter_lower))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    // Region navigation (filter-aware)
     pub fn regions_down(&mut self) {
-        if !self.regions.is_empty() && self.regions_cursor < self.regions.len() - 1 {
-            self.regions_cursor += 1;
+        let filtered = self.filtered_regions_indices();
+        if filtered.is_empty() {
+            return;
+        }
+        if let Some(pos) = filtered.iter().position(|&i| i == self.regions_cursor) {
+            if pos < filtered.len() - 1 {
+                self.regions_cursor = filtered[pos + 1];
+            }
+        } else {
+            self.regions_cursor = filtered[0];
         }
     }
 
     pub fn regions_up(&mut self) {
-        if self.regions_cursor > 0 {
-            self.regions_cursor -= 1;
+        let filtered = self.filtered_regions_indices();
+        if filtered.is_empty() {
+            return;
+        }
+        if let Some(pos) = filtered.iter().position(|&i| i == self.regions_cursor) {
+            if pos > 0 {
+                self.regions_cursor = filtered[pos - 1];
+            }
+        } else {
+            self.regions_cursor = filtered[0];
+        }
+    }
+
+    pub fn reset_regions_cursor(&mut self) {
+        let filtered = self.filtered_regions_indices();
+        if let Some(&first) = filtered.first() {
+            self.regions_cursor = first;
         }
     }
 
     pub fn toggle_region(&mut self) {
+        // The cursor may rest on a region the filter hides; only toggle what's visible
+        if !self.filtered_regions_indices().contains(&self.regions_cursor) {
+            return;
+        }
         if let Some(item) = self.regions.get_mut(self.regions_cursor) {
             item.selected = !item.selected;
             self.regions_changed = true;
         }
     }
 
+    /// Results matching the in-results filter (case-insensitive), in display order
+    /// When grouped, entries are ordered by log group and newest first within each group
+    pub fn visible_results(&self) -> Vec<&LogEntry> {
+        let filter_lower = self.results_filter.to_lowercase();
+        let mut visible: Vec<&LogEntry> = self
+            .results
+            .iter()
+            .filter(|e| filter_lower.is_empty() || e.message.to_lowercase().contains(&filter_lower))
+            .collect();
+
+        if self.results_grouped {
+            visible.sort_by(|a, b| {
+                group_key(a.region.as_deref(), &a.log_group)
+                    .cmp(&group_key(b.region.as_deref(), &b.log_group))
+                    .then(b.timestamp.cmp(&a.timestamp))
+            });
+        }
+        visible
+    }
+
+    /// Switch between the flat and per-log-group results layout
+    pub fn toggle_results_grouped(&mut self) {
+        self.results_grouped = !self.results_grouped;
+        self.results_scroll = 0;
+    }
+
+    /// The entry at the top of the results view, shown in the detail popup
+    pub fn selected_result(&self) -> Option<&LogEntry> {
+        self.visible_results().get(self.results_scroll).copied()
+    }
+
+    /// CloudWatch console link running the current query on an entry's log group, over
+    /// CONSOLE_LINK_WINDOW_MINUTES either side of it; None for Kamal entries, which have no console
+    pub fn console_url(&self, entry: &LogEntry) -> Option<String> {
+        if self.source_mode == SourceMode::Kamal {
+            return None;
+        }
+        let window = chrono::Duration::minutes(CONSOLE_LINK_WINDOW_MINUTES);
+        let params = SearchParams::new(self.get_patterns(), self.get_exclude(), self.limit_value());
+        let links = ConsoleQuery {
+            default_region: None,
+            time_range: TimeRange { start: entry.timestamp - window, end: entry.timestamp + window },
+            query: params.insights_query(),
+        };
+        links.url(entry.region.as_deref(), &entry.log_group)
+    }
+
+    /// Open the selected entry's log group in the CloudWatch console in the default browser ('o' on Results)
+    pub fn open_selected_in_console(&mut self) {
+        let Some(entry) = self.selected_result() else {
+            return;
+        };
+        self.notice = Some(match self.console_url(entry) {
+            Some(url) => match open::that_detached(&url) {
+                Ok(()) => format!("Opened {} in the AWS console", entry.log_group),
+                Err(_) => format!("Couldn't open a browser: {}", url),
+            },
+            None if self.source_mode == SourceMode::Kamal => "Kamal logs have no console link".to_string(),
+            None => format!("No region known for {}", entry.log_group),
+        });
+    }
+
+    /// Follow CloudWatch on Live Tail's channel once it has started, or else by polling, saying why
+    pub fn start_following(&mut self, live_tail: Result<(mpsc::Receiver<LogEntry>, Arc<AtomicBool>)>) {
+        match live_tail {
+            Ok((receiver, stop_flag)) => {
+                self.follow_receiver = Some(receiver);
+                self.follow_stop_flag = Some(stop_flag);
+            }
+            Err(e) => {
+                self.notice = Some(format!(
+                    "Live Tail unavailable: {:#}, polling every {}s",
+                    e,
+                    FOLLOW_POLL_INTERVAL.as_secs()
+                ));
+            }
+        }
+        self.is_following = true;
+    }
+
+    /// Add entries streamed in follow mode (in arrival order, oldest first) to the top of the results
+    /// At the top the view keeps showing the newest entry; scrolled away it stays on the same entry, like `less +F`
+    pub fn add_followed_entries(&mut self, entries: Vec<LogEntry>) {
+        let filter_lower = self.results_filter.to_lowercase();
+        let added_visible = entries
+            .iter()
+            .filter(|e| e.message.to_lowercase().contains(&filter_lower))
+            .count();
+        let now = std::time::Instant::now();
+        self.follow_rate.record(now, entries.len());
+        if !entries.is_empty() {
+            self.follow_idle.activity(now);
+        }
+
+        for entry in entries {
+            self.results.insert(0, entry);
+        }
+
+        // Keep results from growing unbounded
+        self.results.truncate(MAX_FOLLOW_RESULTS);
+
+        if self.results_scroll > 0 {
+            self.results_scroll = (self.results_scroll + added_visible).min(self.visible_results().len().saturating_sub(1));
+        }
+        self.search_state = SearchState::Complete(self.results.len());
+    }
+
+    /// Merge entries found by the search run before following, skipping ones already shown
+    /// They are older than anything streamed afterwards, so they go below the followed entries
+    pub fn add_backfilled_entries(&mut self, entries: Vec<LogEntry>) {
+        for entry in entries {
+            if !self.results.iter().any(|e| e.timestamp == entry.timestamp && e.message == entry.message) {
+                self.results.push(entry);
+            }
+        }
+        self.results.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+        self.results.truncate(MAX_FOLLOW_RESULTS);
+        self.search_state = SearchState::Complete(self.results.len());
+    }
+
+    pub fn results_filter_changed(&mut self) {
+        self.results_scroll = 0;
+    }
+
     // Results navigation
     pub fn scroll_results_down(&mut self) {
-        if self.results_scroll < self.results.len().saturating_sub(1) {
+        if self.results_scroll < self.visible_results().len().saturating_sub(1) {
             self.results_scroll += 1;
         }
     }
@@ -433,7 +877,7 @@ impl App {
 
     pub fn page_down(&mut self, page_size: usize) {
         self.results_scroll = (self.results_scroll + page_size)
-            .min(self.results.len().saturating_sub(1));
+            .min(self.visible_results().len().saturating_sub(1));
     }
 
     pub fn page_up(&mut self, page_size: usize) {
@@ -448,17 +892,6 @@ impl App {
         self.horizontal_scroll = self.horizontal_scroll.saturating_add(10);
     }
 
-    pub fn select_result_at_row(&mut self, row: usize) {
-        let index = self.results_scroll + row;
-        if index < self.results.len() {
-            self.selected_result = Some(index);
-        }
-    }
-
-    pub fn clear_selection(&mut self) {
-        self.selected_result = None;
-    }
-
     pub fn filtered_log_groups_indices(&self) -> Vec<usize> {
         if self.log_groups_filter.is_empty() {
             return (0..self.log_groups.len()).collect();
@@ -513,32 +946,16 @@ impl App {
         }
     }
 
-    pub fn select_all_log_groups(&mut self) {
-        for item in &mut self.log_groups {
-            item.selected = true;
-        }
-    }
-
-    pub fn deselect_all_log_groups(&mut self) {
-        for item in &mut self.log_groups {
-            item.selected = false;
-        }
-    }
-
+    /// Comma-separated patterns; `\,` is a literal comma
     pub fn get_patterns(&self) -> Vec<String> {
-        self.patterns_input
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect()
+        split_list(&self.patterns_input)
     }
 
+    /// The config's global_exclude, then the comma-separated excludes typed in (`\,` is a literal comma)
     pub fn get_exclude(&self) -> Vec<String> {
-        self.exclude_input
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect()
+        let mut exclude = self.global_exclude.clone();
+        exclude.extend(split_list(&self.exclude_input));
+        exclude
     }
 
     pub fn get_selected_regions(&self) -> Vec<String> {
@@ -568,27 +985,218 @@ impl App {
     pub fn toggle_help(&mut self) {
         self.show_help = !self.show_help;
     }
+
+    pub fn open_profile_modal(&mut self) {
+        self.profile_input = self.profile.clone().unwrap_or_default();
+        self.show_profile_modal = true;
+    }
+
+    /// Run a search with the current settings for the selected source, following afterwards
+    /// when follow mode is on (Enter, or 'r' to refresh)
+    #[tracing::instrument(name = "tui_search", level = "debug", skip_all, fields(source = ?self.source_mode))]
+    pub async fn execute_search(&mut self, searcher: &MultiRegionSearcher) {
+        // Search based on source mode
+        match self.source_mode {
+            SourceMode::CloudWatch => {
+                let patterns = self.get_patterns();
+                let exclude = self.get_exclude();
+                let groups = self.get_selected_log_groups();
+
+                if !groups.is_empty() {
+                    self.search_state = SearchState::Searching;
+                    self.results.clear();
+
+                    let time_range = self.time_range();
+                    match time_range {
+                        Ok(tr) => {
+                            let params = SearchParams::new(
+                                patterns,
+                                exclude,
+                                self.limit_value(),
+                            );
+
+                            let results = searcher
+                                .search_log_groups(&groups, &params, tr.start, tr.end)
+                                .await;
+
+                            let mut all_entries = Vec::new();
+                            let mut errors = Vec::new();
+                            self.query_stats = Some(results.stats);
+
+                            for result in results.groups {
+                                match result {
+                                    Ok(entries) => all_entries.extend(entries),
+                                    Err(e) => errors.push(e.to_string()),
+                                }
+                            }
+
+                            all_entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+
+                            let count = all_entries.len();
+                            self.results = all_entries;
+                            self.results_scroll = 0;
+
+                            if errors.is_empty() || count > 0 {
+                                self.search_state = SearchState::Complete(count);
+                            } else {
+                                self.search_state = SearchState::Error(errors.join("; "));
+                            }
+
+                            // Start following if requested
+                            if self.follow_mode {
+                                start_cloudwatch_follow(self, searcher).await;
+                            }
+
+                            self.focus = Focus::Results;
+                        }
+                        Err(e) => {
+                            self.search_state = SearchState::Error(e.to_string());
+                        }
+                    }
+                } else if self.search_state == SearchState::Refreshing {
+                    // Nothing selected to refresh
+                    self.search_state = SearchState::Idle;
+                }
+            }
+            SourceMode::Kamal => {
+                // Search using KamalSearcher
+                let patterns = self.get_patterns();
+                let exclude = self.get_exclude();
+
+                self.search_state = SearchState::Searching;
+                self.results.clear();
+
+                match KamalSearcher::from_file(self.selected_deploy_file()) {
+                    Ok(kamal_searcher) => {
+                        let since = self.docker_since();
+                        match since {
+                            Ok(since_str) => {
+                                let params = KamalSearchParams {
+                                    patterns,
+                                    exclude,
+                                    limit: self.limit_value() as usize,
+                                    since: Some(since_str),
+                                    tail_multiplier: self.tail_multiplier,
+                                    ..KamalSearchParams::default()
+                                };
+
+                                if self.follow_mode {
+                                    // Start follow mode with channel
+                                    let (tx, rx) = mpsc::channel(1000);
+                                    let stop_flag = Arc::new(AtomicBool::new(false));
+
+                                    match kamal_searcher.follow_logs_channel(&params, tx, stop_flag.clone()).await {
+                                        Ok(()) => {
+                                            self.follow_receiver = Some(rx);
+                                            self.follow_stop_flag = Some(stop_flag);
+                                            self.is_following = true;
+                                            self.search_state = SearchState::Searching;
+                                            self.focus = Focus::Results;
+                                        }
+                                        Err(e) => {
+                                            self.search_state = SearchState::Error(format!("Follow failed: {}", e));
+                                        }
+                                    }
+                                } else {
+                                    // Regular search
+                                    let results = kamal_searcher.search_logs(&params).await;
+
+                                    let mut all_entries = Vec::new();
+                                    let mut errors = Vec::new();
+
+                                    for result in results {
+                                        match result {
+                                            Ok(entries) => all_entries.extend(entries),
+                                            Err(e) => errors.push(e.to_string()),
+                                        }
+                                    }
+
+                                    // Docker only takes --since; apply the end of an absolute range here
+                                    if self.has_absolute_time_range() {
+                                        if let Ok(tr) = self.time_range() {
+                                            all_entries.retain(|e| e.timestamp <= tr.end);
+                                        }
+                                    }
+
+                                    all_entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+
+                                    let count = all_entries.len();
+                                    self.results = all_entries;
+                                    self.results_scroll = 0;
+
+                                    if errors.is_empty() || count > 0 {
+                                        self.search_state = SearchState::Complete(count);
+                                    } else {
+                                        self.search_state = SearchState::Error(errors.join("; "));
+                                    }
+
+                                    self.focus = Focus::Results;
+                                }
+                            }
+                            Err(e) => {
+                                self.search_state = SearchState::Error(e.to_string());
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        self.search_state = SearchState::Error(format!("Failed to load deploy file: {}", e));
+                    }
+                }
+            }
+        }
+    }
 }
 
-pub async fn run_tui(searcher: MultiRegionSearcher, config: Config) -> Result<()> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+/// Puts the terminal into raw mode + alternate screen and restores it on drop,
+/// so an error or panic mid-run doesn't leave the terminal broken
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> Result<Self> {
+        enable_raw_mode().context("Failed to enable raw mode")?;
+        // Construct before entering the alternate screen so a failure there still restores raw mode
+        let guard = Self;
+        execute!(io::stdout(), EnterAlternateScreen).context("Failed to enter the alternate screen")?;
+        Ok(guard)
+    }
 
-    let mut app = App::new(&config);
+    fn restore() {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, Show);
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        Self::restore();
+    }
+}
 
-    let result = run_app(&mut terminal, &mut app, &searcher).await;
+pub async fn run_tui(mut searcher: MultiRegionSearcher, config: Config) -> Result<()> {
+    if !io::stdout().is_terminal() || !io::stdin().is_terminal() {
+        bail!("The TUI needs an interactive terminal. Use `log-hound search` for scripts and pipes.");
+    }
 
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+    // Restore the terminal before the panic message is printed
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        TerminalGuard::restore();
+        default_hook(info);
+    }));
 
-    result
+    let _guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
+    let mut terminal = Terminal::new(backend)?;
+
+    let mut app = App::new(&config);
+    app.profile = searcher.profile().map(|p| p.to_string());
+
+    run_app(&mut terminal, &mut app, &mut searcher).await
 }
 
 async fn load_log_groups(app: &mut App, searcher: &MultiRegionSearcher) {
+    // Failures aren't retried until the next reload trigger
+    app.group_reload_due = None;
     let selected_regions = app.get_selected_regions();
     if selected_regions.is_empty() {
         app.log_groups.clear();
@@ -596,6 +1204,14 @@ async fn load_log_groups(app: &mut App, searcher: &MultiRegionSearcher) {
         return;
     }
 
+    // Groups still listed after the reload stay selected
+    let previously_selected: Vec<(String, String)> = app
+        .log_groups
+        .iter()
+        .filter(|g| g.selected)
+        .map(|g| (g.region.clone(), g.name.clone()))
+        .collect();
+
     app.search_state = SearchState::LoadingGroups;
     app.log_groups.clear();
 
@@ -603,10 +1219,11 @@ async fn load_log_groups(app: &mut App, searcher: &MultiRegionSearcher) {
         match searcher.list_log_groups(Some(region), None).await {
             Ok(groups) => {
                 for name in groups {
+                    let selected = previously_selected.iter().any(|(r, n)| r == region && *n == name);
                     app.log_groups.push(LogGroupItem {
                         name,
                         region: region.clone(),
-                        selected: false,
+                        selected,
                     });
                 }
             }
@@ -622,62 +1239,102 @@ async fn load_log_groups(app: &mut App, searcher: &MultiRegionSearcher) {
     app.regions_changed = false;
 }
 
+/// Reload log groups first if the regions changed since they were listed, so a search never uses stale groups
+async fn ensure_groups_loaded(app: &mut App, searcher: &MultiRegionSearcher) {
+    if app.groups_stale() {
+        load_log_groups(app, searcher).await;
+    }
+}
+
+/// Search the last follow_backfill of the selected log groups, so following starts with context
+async fn backfill_cloudwatch_follow(app: &mut App, searcher: &MultiRegionSearcher) {
+    let groups = app.get_selected_log_groups();
+    if groups.is_empty() {
+        return;
+    }
+    let Ok(tr) = TimeRange::from_relative(&app.follow_backfill) else {
+        return;
+    };
+
+    let params = SearchParams::new(app.get_patterns(), app.get_exclude(), app.limit_value());
+    let results = searcher.search_log_groups(&groups, &params, tr.start, tr.end).await;
+    app.add_backfilled_entries(results.groups.into_iter().flatten().flatten().collect());
+}
+
+/// Start following CloudWatch with Live Tail, falling back to polling where it isn't available
+async fn start_cloudwatch_follow(app: &mut App, searcher: &MultiRegionSearcher) {
+    let groups = app.get_selected_log_groups();
+    let params = SearchParams::new(app.get_patterns(), app.get_exclude(), app.limit_value());
+
+    let (tx, rx) = mpsc::channel(1000);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+
+    let live_tail = searcher.follow_log_groups(&groups, &params, tx, stop_flag.clone()).await;
+    app.start_following(live_tail.map(|()| (rx, stop_flag)));
+}
+
 async fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut App,
-    searcher: &MultiRegionSearcher,
+    searcher: &mut MultiRegionSearcher,
 ) -> Result<()> {
     load_log_groups(app, searcher).await;
 
-    // For CloudWatch polling in follow mode
+    // For CloudWatch polling in follow mode, when Live Tail isn't available
     let mut last_poll_time = std::time::Instant::now();
-    const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+    let mut last_focus = app.focus.clone();
 
     loop {
+        // Focus can leave Regions through several keys, so watch for the change itself
+        let now = std::time::Instant::now();
+        if app.focus != last_focus {
+            app.focus_changed(&last_focus, now);
+            last_focus = app.focus.clone();
+        }
+        if app.group_reload_ready(now) {
+            load_log_groups(app, searcher).await;
+        }
+
         // Check for new entries from follow mode channel
         if let Some(ref mut receiver) = app.follow_receiver {
+            let mut received = Vec::new();
             while let Ok(entry) = receiver.try_recv() {
-                // Insert at beginning (newest first) and maintain scroll position
-                app.results.insert(0, entry);
-                // Keep results from growing unbounded
-                if app.results.len() > 10000 {
-                    app.results.pop();
-                }
-                app.search_state = SearchState::Complete(app.results.len());
+                received.push(entry);
+            }
+            if !received.is_empty() {
+                app.add_followed_entries(received);
             }
         }
-
-        // CloudWatch follow mode: periodic polling
-        if app.is_following && app.source_mode == SourceMode::CloudWatch {
-            if last_poll_time.elapsed() >= POLL_INTERVAL {
-                last_poll_time = std::time::Instant::now();
-
-                let patterns = app.get_patterns();
-                let exclude = app.get_exclude();
-                let groups = app.get_selected_log_groups();
-
-                if !groups.is_empty() {
-                    if let Ok(tr) = TimeRange::from_relative("1m") {
-                        let params = SearchParams::new(patterns, exclude, 100);
-                        let results = searcher.search_log_groups(&groups, &params, tr.start, tr.end).await;
-
-                        for result in results {
-                            if let Ok(entries) = result {
-                                for entry in entries {
-                                    // Avoid duplicates by checking timestamp
-                                    if !app.results.iter().take(100).any(|e| e.timestamp == entry.timestamp && e.message == entry.message) {
-                                        app.results.insert(0, entry);
-                                    }
-                                }
+        app.check_follow_idle(std::time::Instant::now());
+
+        // CloudWatch follow mode without Live Tail: periodic polling
+        if app.is_following
+            && app.source_mode == SourceMode::CloudWatch
+            && app.follow_receiver.is_none()
+            && last_poll_time.elapsed() >= FOLLOW_POLL_INTERVAL
+        {
+            last_poll_time = std::time::Instant::now();
+
+            let patterns = app.get_patterns();
+            let exclude = app.get_exclude();
+            let groups = app.get_selected_log_groups();
+
+            if !groups.is_empty() {
+                if let Ok(tr) = TimeRange::from_relative("1m") {
+                    let params = SearchParams::new(patterns, exclude, 100);
+                    let results = searcher.search_log_groups(&groups, &params, tr.start, tr.end).await;
+
+                    let mut new_entries = Vec::new();
+                    for entries in results.groups.into_iter().flatten() {
+                        for entry in entries {
+                            // Avoid duplicates by checking timestamp
+                            if !app.results.iter().take(100).any(|e| e.timestamp == entry.timestamp && e.message == entry.message) {
+                                new_entries.push(entry);
                             }
                         }
-                        // Sort and limit
-                        app.results.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-                        if app.results.len() > 10000 {
-                            app.results.truncate(10000);
-                        }
-                        app.search_state = SearchState::Complete(app.results.len());
                     }
+                    new_entries.sort_by_key(|e| e.timestamp);
+                    app.add_followed_entries(new_entries);
                 }
             }
         }
@@ -688,8 +1345,11 @@ async fn run_app(
             let event = event::read()?;
 
             if let Event::Key(key) = event {
+                // Notices last until the next key
+                app.notice = None;
+
                 // Help toggle
-                if key.code == KeyCode::F(1) || (key.code == KeyCode::Char('?') && app.focus == Focus::Results) {
+                if key.code == KeyCode::F(1) || (key.code == KeyCode::Char('?') && app.focus == Focus::Results && !app.results_filter_editing) {
                     app.toggle_help();
                     continue;
                 }
@@ -700,176 +1360,84 @@ async fn run_app(
                     continue;
                 }
 
+                // Close the detail popup with any key
+                if app.show_detail {
+                    app.show_detail = false;
+                    continue;
+                }
+
                 // Global keybindings
-                if key.modifiers.contains(KeyModifiers::CONTROL) {
-                    if key.code == KeyCode::Char('c') || key.code == KeyCode::Char('q') {
-                        app.should_quit = true;
-                    }
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    && (key.code == KeyCode::Char('c') || key.code == KeyCode::Char('q'))
+                {
+                    app.should_quit = true;
                 }
 
                 if app.should_quit {
                     return Ok(());
                 }
 
-                match key.code {
-                    KeyCode::Tab => {
-                        if app.source_mode == SourceMode::CloudWatch && app.focus == Focus::Regions && app.regions_changed {
-                            load_log_groups(app, searcher).await;
+                // AWS profile switcher
+                if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('p') {
+                    app.open_profile_modal();
+                    continue;
+                }
+
+                if app.show_profile_modal {
+                    match key.code {
+                        KeyCode::Enter => {
+                            app.show_profile_modal = false;
+                            let profile = Some(app.profile_input.trim().to_string()).filter(|p| !p.is_empty());
+                            if profile != app.profile {
+                                searcher.set_profile(profile.clone());
+                                app.profile = profile.clone();
+                                let _ = TuiState { profile }.save();
+
+                                // Groups differ per account, so reload them with the new credentials
+                                app.results.clear();
+                                app.results_scroll = 0;
+                                load_log_groups(app, searcher).await;
+                            }
                         }
-                        app.next_focus();
+                        KeyCode::Esc => app.show_profile_modal = false,
+                        code => handle_text_input(code, &mut app.profile_input),
                     }
-                    KeyCode::BackTab => {
-                        if app.source_mode == SourceMode::CloudWatch && app.focus == Focus::LogGroups && app.regions_changed {
-                            load_log_groups(app, searcher).await;
+                    continue;
+                }
+
+                // Typing into the in-results filter
+                if app.results_filter_editing {
+                    match key.code {
+                        KeyCode::Enter => app.results_filter_editing = false,
+                        KeyCode::Esc => {
+                            app.results_filter.clear();
+                            app.results_filter_editing = false;
+                            app.results_filter_changed();
+                        }
+                        code => {
+                            handle_text_input(code, &mut app.results_filter);
+                            app.results_filter_changed();
                         }
-                        app.prev_focus();
                     }
-                    KeyCode::Enter => {
-                        if app.focus != Focus::Results {
-                            // Search based on source mode
-                            match app.source_mode {
-                                SourceMode::CloudWatch => {
-                                    let patterns = app.get_patterns();
-                                    let exclude = app.get_exclude();
-                                    let groups = app.get_selected_log_groups();
-
-                                    if !groups.is_empty() {
-                                        app.search_state = SearchState::Searching;
-                                        app.results.clear();
-
-                                        let time_range = TimeRange::from_relative(app.time_range_value());
-                                        match time_range {
-                                            Ok(tr) => {
-                                                let params = SearchParams::new(
-                                                    patterns,
-                                                    exclude,
-                                                    app.limit_value(),
-                                                );
-
-                                                let results = searcher
-                                                    .search_log_groups(&groups, &params, tr.start, tr.end)
-                                                    .await;
-
-                                                let mut all_entries = Vec::new();
-                                                let mut errors = Vec::new();
-
-                                                for result in results {
-                                                    match result {
-                                                        Ok(entries) => all_entries.extend(entries),
-                                                        Err(e) => errors.push(e.to_string()),
-                                                    }
-                                                }
-
-                                                all_entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-
-                                                let count = all_entries.len();
-                                                app.results = all_entries;
-                                                app.results_scroll = 0;
-
-                                                if errors.is_empty() {
-                                                    app.search_state = SearchState::Complete(count);
-                                                } else if count > 0 {
-                                                    app.search_state = SearchState::Complete(count);
-                                                } else {
-                                                    app.search_state = SearchState::Error(errors.join("; "));
-                                                }
-
-                                                // Enable follow mode polling if requested
-                                                if app.follow_mode {
-                                                    app.is_following = true;
-                                                    last_poll_time = std::time::Instant::now();
-                                                }
-
-                                                app.focus = Focus::Results;
-                                            }
-                                            Err(e) => {
-                                                app.search_state = SearchState::Error(e.to_string());
-                                            }
-                                        }
-                                    }
-                                }
-                                SourceMode::Kamal => {
-                                    // Search using KamalSearcher
-                                    let patterns = app.get_patterns();
-                                    let exclude = app.get_exclude();
+                    continue;
+                }
 
-                                    app.search_state = SearchState::Searching;
-                                    app.results.clear();
-
-                                    match KamalSearcher::from_file(app.selected_deploy_file()) {
-                                        Ok(kamal_searcher) => {
-                                            let since = crate::time::to_docker_since(app.time_range_value());
-                                            match since {
-                                                Ok(since_str) => {
-                                                    let params = KamalSearchParams {
-                                                        patterns,
-                                                        exclude,
-                                                        limit: app.limit_value() as usize,
-                                                        since: Some(since_str),
-                                                        follow: app.follow_mode,
-                                                    };
-
-                                                    if app.follow_mode {
-                                                        // Start follow mode with channel
-                                                        let (tx, rx) = mpsc::channel(1000);
-                                                        let stop_flag = Arc::new(AtomicBool::new(false));
-
-                                                        match kamal_searcher.follow_logs_channel(&params, tx, stop_flag.clone()).await {
-                                                            Ok(()) => {
-                                                                app.follow_receiver = Some(rx);
-                                                                app.follow_stop_flag = Some(stop_flag);
-                                                                app.is_following = true;
-                                                                app.search_state = SearchState::Searching;
-                                                                app.focus = Focus::Results;
-                                                            }
-                                                            Err(e) => {
-                                                                app.search_state = SearchState::Error(format!("Follow failed: {}", e));
-                                                            }
-                                                        }
-                                                    } else {
-                                                        // Regular search
-                                                        let results = kamal_searcher.search_logs(&params).await;
-
-                                                        let mut all_entries = Vec::new();
-                                                        let mut errors = Vec::new();
-
-                                                        for result in results {
-                                                            match result {
-                                                                Ok(entries) => all_entries.extend(entries),
-                                                                Err(e) => errors.push(e.to_string()),
-                                                            }
-                                                        }
-
-                                                        all_entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-
-                                                        let count = all_entries.len();
-                                                        app.results = all_entries;
-                                                        app.results_scroll = 0;
-
-                                                        if errors.is_empty() {
-                                                            app.search_state = SearchState::Complete(count);
-                                                        } else if count > 0 {
-                                                            app.search_state = SearchState::Complete(count);
-                                                        } else {
-                                                            app.search_state = SearchState::Error(errors.join("; "));
-                                                        }
-
-                                                        app.focus = Focus::Results;
-                                                    }
-                                                }
-                                                Err(e) => {
-                                                    app.search_state = SearchState::Error(e.to_string());
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            app.search_state = SearchState::Error(format!("Failed to load deploy file: {}", e));
-                                        }
-                                    }
-                                }
-                            }
+                match key.code {
+                    KeyCode::Tab => app.next_focus(),
+                    KeyCode::BackTab => app.prev_focus(),
+                    KeyCode::Enter if app.focus == Focus::Results => {
+                        app.show_detail = app.selected_result().is_some();
+                    }
+                    KeyCode::Enter => {
+                        ensure_groups_loaded(app, searcher).await;
+                        app.execute_search(searcher).await;
+                        if app.is_following {
+                            last_poll_time = std::time::Instant::now();
                         }
                     }
+                    KeyCode::Char(' ') if app.is_editing_time_range() => {
+                        app.time_input_mut().push(' ');
+                    }
                     KeyCode::Char(' ') => {
                         match app.focus {
                             Focus::Regions => {
@@ -885,17 +1453,35 @@ async fn run_app(
                         if app.is_following {
                             // Stop follow mode
                             app.stop_following();
+                        } else if app.is_editing_time_range() {
+                            app.toggle_time_input_mode();
                         } else if app.focus == Focus::Results {
                             app.focus = Focus::Patterns;
                         } else if app.focus == Focus::DeployFile && !app.deploy_files_filter.is_empty() {
                             app.deploy_files_filter.clear();
                             app.reset_deploy_files_cursor();
+                        } else if app.focus == Focus::Regions && !app.regions_filter.is_empty() {
+                            app.regions_filter.clear();
+                            app.reset_regions_cursor();
                         } else if app.focus == Focus::LogGroups && !app.log_groups_filter.is_empty() {
                             app.log_groups_filter.clear();
                             app.reset_log_groups_cursor();
                         }
                     }
-                    KeyCode::Char('f') if app.focus != Focus::Patterns && app.focus != Focus::Exclude && app.focus != Focus::LogGroups && app.focus != Focus::DeployFile => {
+                    KeyCode::Char('r') if !app.is_typing() => {
+                        // Re-run the current search, restarting a follow
+                        if app.is_following {
+                            app.stop_following();
+                        }
+                        app.search_state = SearchState::Refreshing;
+                        terminal.draw(|f| ui::render(f, app))?;
+                        ensure_groups_loaded(app, searcher).await;
+                        app.execute_search(searcher).await;
+                        if app.is_following {
+                            last_poll_time = std::time::Instant::now();
+                        }
+                    }
+                    KeyCode::Char('f') if !app.is_typing() => {
                         if app.is_following {
                             // Stop following
                             app.stop_following();
@@ -905,8 +1491,12 @@ async fn run_app(
 
                             match app.source_mode {
                                 SourceMode::CloudWatch => {
-                                    // For CloudWatch, just enable polling mode
-                                    app.is_following = true;
+                                    // Load recent history first, like Kamal's --since, then stream
+                                    app.search_state = SearchState::Searching;
+                                    terminal.draw(|f| ui::render(f, app))?;
+                                    ensure_groups_loaded(app, searcher).await;
+                                    backfill_cloudwatch_follow(app, searcher).await;
+                                    start_cloudwatch_follow(app, searcher).await;
                                     last_poll_time = std::time::Instant::now();
                                 }
                                 SourceMode::Kamal => {
@@ -915,13 +1505,14 @@ async fn run_app(
                                     let exclude = app.get_exclude();
 
                                     if let Ok(kamal_searcher) = KamalSearcher::from_file(app.selected_deploy_file()) {
-                                        if let Ok(since_str) = crate::time::to_docker_since("1m") {
+                                        if let Ok(since_str) = crate::time::to_docker_since(&app.follow_backfill) {
                                             let params = KamalSearchParams {
                                                 patterns,
                                                 exclude,
                                                 limit: app.limit_value() as usize,
                                                 since: Some(since_str),
-                                                follow: true,
+                                                tail_multiplier: app.tail_multiplier,
+                                                ..KamalSearchParams::default()
                                             };
 
                                             let (tx, rx) = mpsc::channel(1000);
@@ -977,8 +1568,17 @@ async fn run_app(
                                 handle_text_input(key.code, &mut app.exclude_input)
                             }
                             Focus::Regions => match key.code {
+                                // Region names never contain j/k, so they stay navigation keys
                                 KeyCode::Up | KeyCode::Char('k') => app.regions_up(),
                                 KeyCode::Down | KeyCode::Char('j') => app.regions_down(),
+                                KeyCode::Char(c) => {
+                                    app.regions_filter.push(c);
+                                    app.reset_regions_cursor();
+                                }
+                                KeyCode::Backspace => {
+                                    app.regions_filter.pop();
+                                    app.reset_regions_cursor();
+                                }
                                 _ => {}
                             },
                             Focus::LogGroups => match key.code {
@@ -994,9 +1594,15 @@ async fn run_app(
                                 }
                                 _ => {}
                             },
+                            Focus::TimeRange if app.time_input_mode => match key.code {
+                                KeyCode::Up | KeyCode::Down => app.toggle_time_input_field(),
+                                _ => handle_text_input(key.code, app.time_input_mut()),
+                            },
                             Focus::TimeRange => match key.code {
                                 KeyCode::Left | KeyCode::Char('h') => app.prev_time_range(),
                                 KeyCode::Right | KeyCode::Char('l') => app.next_time_range(),
+                                KeyCode::Char('e') => app.toggle_time_input_mode(),
+                                KeyCode::Backspace | KeyCode::Delete => app.clear_absolute_time_range(),
                                 _ => {}
                             },
                             Focus::Limit => match key.code {
@@ -1015,8 +1621,13 @@ async fn run_app(
                                     app.results_scroll = 0;
                                     app.horizontal_scroll = 0;
                                 }
+                                KeyCode::Char('/') => app.results_filter_editing = true,
+                                KeyCode::Char('v') => app.results_view = app.results_view.next(),
+                                KeyCode::Char('s') => app.toggle_results_grouped(),
+                                KeyCode::Char('c') => app.results_color_by_level = !app.results_color_by_level,
+                                KeyCode::Char('o') => app.open_selected_in_console(),
                                 KeyCode::End | KeyCode::Char('G') => {
-                                    app.results_scroll = app.results.len().saturating_sub(1);
+                                    app.results_scroll = app.visible_results().len().saturating_sub(1);
                                 }
                                 _ => {}
                             },
@@ -1041,3 +1652,342 @@ fn handle_text_input(key: KeyCode, input: &mut String) {
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn entry(message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: Utc::now(),
+            message: message.to_string(),
+            log_group: "app/prod".to_string(),
+            log_stream: None,
+            region: None,
+            also_in: Vec::new(),
+            ptr: None,
+        }
+    }
+
+    #[test]
+    fn test_default_regions_from_config() {
+        let selected = |app: &App| app.regions.iter().filter(|r| r.selected).map(|r| r.name.clone()).collect::<Vec<_>>();
+
+        let app = App::new(&Config::default());
+        assert_eq!(selected(&app), vec!["ap-east-2", "ap-northeast-1"]);
+
+        let config = Config {
+            tui_default_regions: vec!["us-east-1".to_string(), "eu-west-1".to_string()],
+            default_region: Some("il-central-1".to_string()),
+            ..Config::default()
+        };
+        let app = App::new(&config);
+        assert_eq!(selected(&app), vec!["eu-west-1", "us-east-1", "il-central-1"]);
+    }
+
+    #[test]
+    fn test_regions_filter_narrows_and_moves_cursor() {
+        let mut app = App::new(&Config::default());
+        let name = |app: &App, idx: usize| app.regions[idx].name.clone();
+
+        app.regions_filter = "EU-".to_string();
+        app.reset_regions_cursor();
+        let filtered = app.filtered_regions_indices();
+        assert!(!filtered.is_empty());
+        assert!(filtered.iter().all(|&i| name(&app, i).starts_with("eu-")));
+        assert_eq!(app.regions_cursor, filtered[0]);
+
+        // Navigation stays within the filtered regions
+        app.regions_down();
+        assert_eq!(app.regions_cursor, filtered[1]);
+        for _ in 0..app.regions.len() {
+            app.regions_down();
+        }
+        assert_eq!(app.regions_cursor, *filtered.last().unwrap());
+
+        app.regions_filter = "eu-west-1".to_string();
+        app.reset_regions_cursor();
+        let was_selected = app.regions[app.regions_cursor].selected;
+        app.toggle_region();
+        assert_eq!(name(&app, app.regions_cursor), "eu-west-1");
+        assert_ne!(app.regions[app.regions_cursor].selected, was_selected);
+
+        // No match leaves the cursor on a hidden region, which Space doesn't toggle
+        app.regions_filter = "nowhere".to_string();
+        app.reset_regions_cursor();
+        assert!(app.filtered_regions_indices().is_empty());
+        let before = app.selected_regions_count();
+        app.toggle_region();
+        assert_eq!(app.selected_regions_count(), before);
+
+        app.regions_filter.clear();
+        assert_eq!(app.filtered_regions_indices().len(), app.regions.len());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_without_selection() {
+        let mut app = App::new(&Config::default());
+        let searcher = MultiRegionSearcher::new(None, None);
+
+        // 'r' is a shortcut only where it can't be typed
+        app.focus = Focus::Results;
+        assert!(!app.is_typing());
+        app.focus = Focus::Patterns;
+        assert!(app.is_typing());
+
+        // With no log groups selected there is nothing to re-run, so the status doesn't stay on "Refreshing..."
+        app.search_state = SearchState::Refreshing;
+        app.execute_search(&searcher).await;
+        assert_eq!(app.search_state, SearchState::Idle);
+    }
+
+    #[test]
+    fn test_group_reload_triggers() {
+        let mut app = App::new(&Config::default());
+        let start = std::time::Instant::now();
+        let at = |millis: u64| start + std::time::Duration::from_millis(millis);
+        app.regions_changed = false;
+
+        // Leaving Regions without changes schedules nothing
+        app.focus = Focus::Regions;
+        app.focus_changed(&Focus::Exclude, at(0));
+        app.focus = Focus::LogGroups;
+        app.focus_changed(&Focus::Regions, at(0));
+        assert_eq!(app.group_reload_due, None);
+        assert!(!app.groups_stale());
+
+        // Toggling a region marks the groups stale; leaving by any path schedules the reload
+        app.focus = Focus::Regions;
+        app.focus_changed(&Focus::LogGroups, at(0));
+        app.toggle_region();
+        assert!(app.groups_stale());
+        app.focus = Focus::Results;
+        app.focus_changed(&Focus::Regions, at(100));
+        assert!(!app.group_reload_ready(at(599)));
+        assert!(app.group_reload_ready(at(600)));
+
+        // Coming back before it's due cancels it, and leaving again restarts the wait
+        app.focus = Focus::Regions;
+        app.focus_changed(&Focus::Results, at(200));
+        assert!(!app.group_reload_ready(at(10_000)));
+        app.focus = Focus::Exclude;
+        app.focus_changed(&Focus::Regions, at(300));
+        assert!(!app.group_reload_ready(at(700)));
+        assert!(app.group_reload_ready(at(800)));
+
+        // Once reloaded nothing is due, even if the deadline passed
+        app.regions_changed = false;
+        assert!(!app.group_reload_ready(at(900)));
+        assert!(!app.groups_stale());
+
+        // Kamal has no log groups to reload
+        app.toggle_source();
+        app.regions_changed = true;
+        app.group_reload_due = None;
+        app.focus = Focus::Patterns;
+        app.focus_changed(&Focus::Regions, at(1_000));
+        assert_eq!(app.group_reload_due, None);
+        assert!(!app.groups_stale());
+    }
+
+    #[test]
+    fn test_follow_stops_when_idle() {
+        let config = Config { follow_idle_timeout: Some("1m".to_string()), ..Config::default() };
+        let mut app = App::new(&config);
+        let start = std::time::Instant::now();
+        let at = |secs: u64| start + std::time::Duration::from_secs(secs);
+
+        app.is_following = true;
+        app.check_follow_idle(at(0));
+        app.check_follow_idle(at(59));
+        assert!(app.is_following);
+        app.check_follow_idle(at(60));
+        assert!(!app.is_following);
+        assert!(matches!(app.search_state, SearchState::IdleStopped(0)));
+
+        // Without a timeout, following never stops on its own
+        let mut app = App::new(&Config::default());
+        app.is_following = true;
+        app.check_follow_idle(at(0));
+        app.check_follow_idle(at(86_400));
+        assert!(app.is_following);
+    }
+
+    #[test]
+    fn test_live_tail_failure_falls_back_to_polling_with_a_notice() {
+        let mut app = App::new(&Config::default());
+        app.start_following(Err(anyhow::anyhow!("StartLiveTail failed: AccessDeniedException")));
+        assert!(app.is_following);
+        assert!(app.follow_receiver.is_none());
+        assert_eq!(
+            app.notice.as_deref(),
+            Some("Live Tail unavailable: StartLiveTail failed: AccessDeniedException, polling every 5s")
+        );
+
+        let mut app = App::new(&Config::default());
+        let (_tx, rx) = mpsc::channel(1);
+        app.start_following(Ok((rx, Arc::new(AtomicBool::new(false)))));
+        assert!(app.is_following && app.follow_receiver.is_some());
+        assert_eq!(app.notice, None);
+    }
+
+    #[test]
+    fn test_deploy_names_keep_the_directory_only_when_ambiguous() {
+        let files: Vec<String> = ["config/deploy.yml", "config/deploy.staging.yml", ".kamal/deploy.yml", "deploy.production.yml"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let names: Vec<String> = files.iter().map(|f| App::extract_deploy_name(f, &files)).collect();
+        assert_eq!(names, vec!["config/default", "staging", ".kamal/default", "production"]);
+
+        // A single base deploy.yml is just "default", wherever it is
+        let files = vec![".kamal/deploy.yml".to_string(), ".kamal/deploy.production.yml".to_string()];
+        assert_eq!(App::extract_deploy_name(&files[0], &files), "default");
+        assert_eq!(App::extract_deploy_name(&files[1], &files), "production");
+    }
+
+    #[test]
+    fn test_console_url_for_selected_entry() {
+        let mut app = App::new(&Config::default());
+        app.patterns_input = "timeout".to_string();
+        let entry = LogEntry {
+            timestamp: Utc.with_ymd_and_hms(2026, 1, 31, 12, 0, 0).unwrap(),
+            log_group: "/ecs/api".to_string(),
+            region: Some("ap-northeast-1".to_string()),
+            ..entry("timeout")
+        };
+
+        // Five minutes either side of the entry, on its own region and group, with the current query
+        let url = app.console_url(&entry).unwrap();
+        assert!(url.starts_with("https://ap-northeast-1.console.aws.amazon.com/"), "{}", url);
+        assert!(url.contains("end~'2026-01-31T12*3a05*3a00.000Z~start~'2026-01-31T11*3a55*3a00.000Z"), "{}", url);
+        assert!(url.contains("*2fecs*2fapi") && url.contains("timeout"), "{}", url);
+
+        // CloudWatch entries always carry their region; without one there's nothing to link to
+        assert_eq!(app.console_url(&LogEntry { region: None, ..entry.clone() }), None);
+
+        app.source_mode = SourceMode::Kamal;
+        assert_eq!(app.console_url(&entry), None);
+        app.results = vec![entry];
+        app.open_selected_in_console();
+        assert_eq!(app.notice.as_deref(), Some("Kamal logs have no console link"));
+    }
+
+    #[test]
+    fn test_backfill_before_followed_entries() {
+        let mut app = App::new(&Config::default());
+        assert_eq!(app.follow_backfill, "5m");
+        let at = |second: u32| LogEntry {
+            timestamp: Utc.with_ymd_and_hms(2026, 1, 23, 5, 0, second).unwrap(),
+            ..entry(&format!("line {}", second))
+        };
+        app.results = vec![at(2)];
+
+        // Backfill merges with what's shown, then streamed lines land on top
+        app.add_backfilled_entries(vec![at(1), at(3), at(2)]);
+        app.add_followed_entries(vec![at(4), at(5)]);
+
+        let messages: Vec<_> = app.results.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["line 5", "line 4", "line 3", "line 2", "line 1"]);
+        assert_eq!(app.search_state, SearchState::Complete(5));
+
+        let config = Config { follow_backfill: Some("15m".to_string()), ..Config::default() };
+        assert_eq!(App::new(&config).follow_backfill, "15m");
+    }
+
+    #[test]
+    fn test_configured_limits_and_time_ranges() {
+        let app = App::new(&Config::default());
+        assert_eq!(app.limit_value(), 1000);
+        assert_eq!(app.time_range_value(), "1h");
+
+        let config = Config {
+            tui_limits: vec![1000, 50000],
+            tui_time_ranges: vec!["1d".to_string(), "30d".to_string()],
+            ..Config::default()
+        };
+        let mut app = App::new(&config);
+        // The default positions are past the end of the shorter lists, so they clamp to the last entry
+        assert_eq!(app.limit_value(), 50000);
+        assert_eq!(app.time_range_value(), "30d");
+        assert_eq!(app.time_range_label(), "30d");
+
+        app.prev_time_range();
+        assert_eq!(app.time_range_label(), "1 day");
+        app.prev_time_range();
+        assert_eq!(app.time_range_value(), "1d");
+        app.next_limit();
+        assert_eq!(app.limit_value(), 50000);
+        app.prev_limit();
+        assert_eq!(app.limit_value(), 1000);
+    }
+
+    #[test]
+    fn test_grouped_results_order_by_group_then_newest() {
+        let mut app = App::new(&Config::default());
+        let at = |group: &str, second: u32| LogEntry {
+            timestamp: Utc.with_ymd_and_hms(2026, 1, 23, 5, 0, second).unwrap(),
+            log_group: group.to_string(),
+            ..entry(&format!("{} {}", group, second))
+        };
+        app.results = vec![at("web", 4), at("api", 3), at("web", 2), at("api", 1)];
+
+        let messages = |app: &App| app.visible_results().iter().map(|e| e.message.clone()).collect::<Vec<_>>();
+        assert_eq!(messages(&app), vec!["web 4", "api 3", "web 2", "api 1"]);
+
+        app.results_scroll = 2;
+        app.toggle_results_grouped();
+        assert_eq!(app.results_scroll, 0);
+        assert_eq!(messages(&app), vec!["api 3", "api 1", "web 4", "web 2"]);
+
+        // Scrolling runs straight across section boundaries
+        app.scroll_results_down();
+        app.scroll_results_down();
+        assert_eq!(app.selected_result().unwrap().message, "web 4");
+    }
+
+    #[test]
+    fn test_followed_entries_keep_scrolled_viewport() {
+        let mut app = App::new(&Config::default());
+        app.results = vec![entry("c"), entry("b"), entry("a")];
+
+        // At the top: keep showing the newest entry
+        app.add_followed_entries(vec![entry("d")]);
+        assert_eq!(app.results_scroll, 0);
+        assert_eq!(app.selected_result().unwrap().message, "d");
+
+        // Scrolled away: stay on the same entry as new ones arrive
+        app.results_scroll = 2;
+        app.add_followed_entries(vec![entry("e"), entry("f")]);
+        assert_eq!(app.results[0].message, "f");
+        assert_eq!(app.selected_result().unwrap().message, "b");
+
+        // Only entries visible through the filter shift the view
+        app.results = vec![entry("keep 1"), entry("noise"), entry("keep 0")];
+        app.results_filter = "keep".to_string();
+        app.results_scroll = 1;
+        app.add_followed_entries(vec![entry("noise"), entry("keep 2")]);
+        assert_eq!(app.results_scroll, 2);
+        assert_eq!(app.selected_result().unwrap().message, "keep 0");
+    }
+
+    #[test]
+    fn test_absolute_time_range_falls_back_to_preset() {
+        let mut app = App::new(&Config::default());
+
+        let preset = app.time_range().unwrap();
+        assert_eq!(preset.end - preset.start, chrono::Duration::hours(1));
+
+        app.time_start_input = "2026-01-23 05:00".to_string();
+        app.time_end_input = "2026-01-23 06:00".to_string();
+        let absolute = app.time_range().unwrap();
+        assert_eq!(absolute.start, Utc.with_ymd_and_hms(2026, 1, 23, 5, 0, 0).unwrap());
+        assert_eq!(absolute.end, Utc.with_ymd_and_hms(2026, 1, 23, 6, 0, 0).unwrap());
+        assert_eq!(app.docker_since().unwrap(), "2026-01-23T05:00:00Z");
+
+        app.next_time_range();
+        assert!(!app.has_absolute_time_range());
+        assert_eq!(app.docker_since().unwrap(), "2h");
+    }
+}
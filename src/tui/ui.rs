@@ -5,150 +5,68 @@ use ratatui::{
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
     Frame,
 };
+
+use super::app::{App, Focus, ResultsView, SearchState, SourceMode, TimeInputField};
+use crate::aws::LogEntry;
+use crate::display::{self, group_key, region_label, shorten_group, shorten_stream, ColorMap};
+use crate::level;
 use std::collections::HashMap;
 
-use super::app::{App, Focus, SearchState, SourceMode};
-use std::path::Path;
-
-const LOG_GROUP_COLORS: &[Color] = &[
-    Color::Cyan,
-    Color::Magenta,
-    Color::Yellow,
-    Color::Green,
-    Color::Blue,
-    Color::LightCyan,
-    Color::LightMagenta,
-    Color::LightYellow,
-    Color::LightGreen,
-    Color::LightBlue,
-];
-
-fn strip_ansi_codes(s: &str) -> String {
-    let mut result = String::with_capacity(s.len());
-    let mut chars = s.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        if c == '\x1b' {
-            if chars.peek() == Some(&'[') {
-                chars.next();
-                while let Some(&next) = chars.peek() {
-                    chars.next();
-                    if next.is_ascii_alphabetic() {
-                        break;
-                    }
-                }
-            }
-        } else {
-            result.push(c);
-        }
-    }
-    result
+/// Search patterns are highlighted in red
+fn pattern_highlight_style() -> Style {
+    Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD)
 }
 
-fn highlight_patterns(message: &str, patterns: &[String]) -> Vec<Span<'static>> {
-    if patterns.is_empty() || message.is_empty() {
-        return vec![Span::styled(message.to_string(), Style::default().fg(Color::Gray))];
-    }
+/// The in-results filter term is highlighted in cyan
+fn filter_highlight_style() -> Style {
+    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+}
 
-    let highlight_style = Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD);
+/// Highlight case-insensitive occurrences of several term sets, each with its own style
+/// Where matches from different sets overlap, the earlier set wins
+fn highlight_terms(message: &str, sets: &[(&[String], Style)]) -> Vec<Span<'static>> {
     let normal_style = Style::default().fg(Color::Gray);
 
-    let mut matches: Vec<(usize, usize)> = Vec::new();
-    let message_lower = message.to_lowercase();
-
-    for pattern in patterns {
-        if pattern.is_empty() {
-            continue;
-        }
-        let pattern_lower = pattern.to_lowercase();
-        let mut start = 0;
-        while let Some(pos) = message_lower[start..].find(&pattern_lower) {
-            let abs_pos = start + pos;
-            matches.push((abs_pos, abs_pos + pattern.len()));
-            start = abs_pos + 1;
-        }
-    }
-
-    if matches.is_empty() {
-        return vec![Span::styled(message.to_string(), normal_style)];
-    }
-
-    matches.sort_by_key(|m| m.0);
-    let mut merged: Vec<(usize, usize)> = Vec::new();
-    for (start, end) in matches {
-        if let Some(last) = merged.last_mut() {
-            if start <= last.1 {
-                last.1 = last.1.max(end);
+    // Style index per byte of the message (None = normal)
+    let mut styles: Vec<Option<usize>> = vec![None; message.len()];
+    for (set_idx, (terms, _)) in sets.iter().enumerate() {
+        for term in terms.iter().filter(|t| !t.is_empty()) {
+            let Ok(re) = regex::RegexBuilder::new(&regex::escape(term)).case_insensitive(true).build() else {
                 continue;
+            };
+            for m in re.find_iter(message) {
+                for slot in &mut styles[m.start()..m.end()] {
+                    slot.get_or_insert(set_idx);
+                }
             }
         }
-        merged.push((start, end));
     }
 
+    // Coalesce runs of the same style into spans (match bounds are always char boundaries)
     let mut spans: Vec<Span<'static>> = Vec::new();
-    let mut pos = 0;
-
-    for (start, end) in merged {
-        if pos < start {
-            spans.push(Span::styled(message[pos..start].to_string(), normal_style));
+    let mut run_start = 0;
+    for i in 1..=message.len() {
+        if (i == message.len() || styles[i] != styles[run_start]) && message.is_char_boundary(i) {
+            let style = styles[run_start].map_or(normal_style, |idx| sets[idx].1);
+            spans.push(Span::styled(message[run_start..i].to_string(), style));
+            run_start = i;
         }
-        spans.push(Span::styled(message[start..end].to_string(), highlight_style));
-        pos = end;
     }
 
-    if pos < message.len() {
-        spans.push(Span::styled(message[pos..].to_string(), normal_style));
+    if spans.is_empty() {
+        spans.push(Span::styled(message.to_string(), normal_style));
     }
 
     spans
 }
 
-fn shorten_region(region: &str) -> String {
-    let parts: Vec<&str> = region.split('-').collect();
-    if parts.len() >= 3 {
-        let prefix = match (parts[0], parts[1]) {
-            ("ap", "northeast") => "AN",
-            ("ap", "southeast") => "AS",
-            ("ap", "south") => "AO",
-            ("ap", "east") => "AE",
-            ("us", "east") => "UE",
-            ("us", "west") => "UW",
-            ("eu", "west") => "EW",
-            ("eu", "central") => "EC",
-            ("eu", "north") => "EN",
-            ("ca", "central") => "CC",
-            ("sa", "east") => "SE",
-            _ => return region.chars().take(6).collect(),
-        };
-        format!("{}{}", prefix, parts.last().unwrap_or(&""))
-    } else {
-        region.chars().take(6).collect()
-    }
-}
-
-fn shorten_group(group: &str) -> String {
-    let name = group.rsplit('/').next().unwrap_or(group);
-    let abbrev: String = name
-        .split(|c| c == '-' || c == '_' || c == '.')
-        .filter(|s| !s.is_empty())
-        .map(|s| s.chars().next().unwrap_or(' '))
-        .collect::<String>()
-        .to_uppercase();
-
-    if abbrev.len() >= 2 {
-        abbrev
-    } else {
-        name.chars().take(3).collect::<String>().to_uppercase()
-    }
-}
-
-fn build_color_map(app: &App) -> HashMap<String, Color> {
-    let mut map = HashMap::new();
-    for (idx, group) in app.log_groups.iter().filter(|g| g.selected).enumerate() {
-        let key = format!("{}:{}", group.region, group.name);
-        map.insert(key, LOG_GROUP_COLORS[idx % LOG_GROUP_COLORS.len()]);
-    }
-    map
+fn build_color_map(app: &App) -> ColorMap {
+    display::build_color_map(
+        app.log_groups
+            .iter()
+            .filter(|g| g.selected)
+            .map(|g| group_key(Some(&g.region), &g.name)),
+    )
 }
 
 pub fn render(f: &mut Frame, app: &App) {
@@ -219,6 +137,14 @@ pub fn render(f: &mut Frame, app: &App) {
     if app.show_help {
         render_help_overlay(f);
     }
+
+    if app.show_detail {
+        render_detail_popup(f, app);
+    }
+
+    if app.show_profile_modal {
+        render_profile_modal(f, app);
+    }
 }
 
 fn render_source_selector(f: &mut Frame, app: &App, area: Rect) {
@@ -228,8 +154,11 @@ fn render_source_selector(f: &mut Frame, app: &App, area: Rect) {
         Style::default()
     };
 
+    let profile_title = format!(" AWS profile: {} (Ctrl+P) ", app.profile.as_deref().unwrap_or("default"));
+
     let block = Block::default()
         .title(" Source (h/l or ←/→) ")
+        .title(Line::from(Span::styled(profile_title, Style::default().fg(Color::DarkGray))).right_aligned())
         .borders(Borders::ALL)
         .border_style(style);
 
@@ -282,7 +211,7 @@ fn render_deploy_file_input(f: &mut Frame, app: &App, area: Rect) {
         .border_style(style);
 
     if app.deploy_files.is_empty() {
-        let paragraph = Paragraph::new("No deploy*.yml found in config/")
+        let paragraph = Paragraph::new("No deploy*.yml found in config/, .kamal/ or the project root")
             .block(block)
             .style(Style::default().fg(Color::DarkGray));
         f.render_widget(paragraph, area);
@@ -300,25 +229,8 @@ fn render_deploy_file_input(f: &mut Frame, app: &App, area: Rect) {
         for &idx in &filtered_indices {
             let file_path = &app.deploy_files[idx];
 
-            // Extract simplified name: deploy.production.yml -> production
-            let filename = Path::new(file_path)
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or(file_path);
-
-            // Remove "deploy." prefix and ".yml"/".yaml" suffix
-            let display_name = filename
-                .strip_prefix("deploy.")
-                .unwrap_or(filename)
-                .trim_end_matches(".yml")
-                .trim_end_matches(".yaml");
-
-            // Handle edge case: deploy.yml becomes empty after stripping
-            let display_name = if display_name.is_empty() || display_name == "yml" {
-                "default"
-            } else {
-                display_name
-            };
+            // Simplified name: config/deploy.production.yml -> production, with the directory if it's ambiguous
+            let display_name = App::extract_deploy_name(file_path, &app.deploy_files);
 
             let is_selected = idx == app.deploy_files_cursor;
 
@@ -423,7 +335,7 @@ fn render_selection_collapsed(f: &mut Frame, app: &App, area: Rect) {
         .regions
         .iter()
         .filter(|r| r.selected)
-        .map(|r| shorten_region(&r.name))
+        .map(|r| region_label(&r.name, &app.region_aliases))
         .collect();
 
     let selected_groups: Vec<String> = app
@@ -467,27 +379,45 @@ fn render_regions(f: &mut Frame, app: &App, area: Rect) {
     };
 
     let selected_count = app.selected_regions_count();
-    let title = format!(" Regions ({}) ", selected_count);
+    let filtered_indices = app.filtered_regions_indices();
+    let filter_info = if app.regions_filter.is_empty() {
+        String::new()
+    } else {
+        format!(" [filter: {}]", app.regions_filter)
+    };
+    let title = format!(" Regions ({}){} ", selected_count, filter_info);
 
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
         .border_style(style);
 
+    if filtered_indices.is_empty() {
+        let msg = format!("No regions match: {}", app.regions_filter);
+        let paragraph = Paragraph::new(msg)
+            .block(block)
+            .style(Style::default().fg(Color::Gray));
+        f.render_widget(paragraph, area);
+        return;
+    }
+
     let visible_height = area.height.saturating_sub(2) as usize;
-    let scroll_offset = if app.regions_cursor >= visible_height {
-        app.regions_cursor - visible_height + 1
+    let cursor_pos_in_filtered = filtered_indices
+        .iter()
+        .position(|&i| i == app.regions_cursor)
+        .unwrap_or(0);
+    let scroll_offset = if cursor_pos_in_filtered >= visible_height {
+        cursor_pos_in_filtered - visible_height + 1
     } else {
         0
     };
 
-    let items: Vec<ListItem> = app
-        .regions
+    let items: Vec<ListItem> = filtered_indices
         .iter()
-        .enumerate()
         .skip(scroll_offset)
         .take(visible_height)
-        .map(|(idx, item)| {
+        .map(|&idx| {
+            let item = &app.regions[idx];
             let checkbox = if item.selected { "[x]" } else { "[ ]" };
             let is_cursor = idx == app.regions_cursor;
 
@@ -631,12 +561,51 @@ fn render_time_range(f: &mut Frame, app: &App, area: Rect) {
         Style::default()
     };
 
-    let time_text = format!("◄  {}  ►", app.time_range_label());
+    // Show the resolved window in the title once an absolute range is entered
+    let (title, title_style) = if app.has_absolute_time_range() {
+        match app.time_range() {
+            Ok(tr) => (
+                format!(" {} → {} UTC ", tr.start.format("%m-%d %H:%M"), tr.end.format("%m-%d %H:%M")),
+                style,
+            ),
+            Err(_) => (" Time Range (invalid) ".to_string(), Style::default().fg(Color::LightRed)),
+        }
+    } else {
+        (" Time Range ".to_string(), style)
+    };
+
     let block = Block::default()
-        .title(" Time Range ")
+        .title(Span::styled(title, title_style))
         .borders(Borders::ALL)
         .border_style(style);
 
+    if app.is_editing_time_range() {
+        let (label, input) = match app.time_input_field {
+            TimeInputField::Start => ("From: ", &app.time_start_input),
+            TimeInputField::End => ("To: ", &app.time_end_input),
+        };
+        let placeholder = match app.time_input_field {
+            TimeInputField::End if input.is_empty() => "now",
+            _ => "",
+        };
+        let line = Line::from(vec![
+            Span::styled(label, Style::default().fg(Color::DarkGray)),
+            Span::styled(input.as_str(), Style::default().fg(Color::White)),
+            Span::styled(placeholder, Style::default().fg(Color::DarkGray)),
+        ]);
+
+        f.render_widget(Paragraph::new(line).block(block), area);
+        f.set_cursor_position((area.x + (label.len() + input.len()) as u16 + 1, area.y + 1));
+        return;
+    }
+
+    let time_text = if app.has_absolute_time_range() {
+        let end = if app.time_end_input.trim().is_empty() { "now" } else { app.time_end_input.trim() };
+        format!("{} → {}", app.time_start_input.trim(), end)
+    } else {
+        format!("◄  {}  ►", app.time_range_label())
+    };
+
     let widget = Paragraph::new(time_text)
         .block(block)
         .style(Style::default().fg(Color::Cyan))
@@ -667,8 +636,11 @@ fn render_limit(f: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_status(f: &mut Frame, app: &App, area: Rect) {
-    let (status_text, status_color) = if app.is_following {
-        (format!("● FOLLOWING ({}) - Esc to stop", app.results.len()), Color::LightRed)
+    let (status_text, status_color) = if let Some(notice) = &app.notice {
+        (notice.clone(), Color::Cyan)
+    } else if app.is_following {
+        let rate = app.follow_rate.label(std::time::Instant::now());
+        (format!("● FOLLOWING ({}) • {} - Esc to stop", app.results.len(), rate), Color::LightRed)
     } else {
         match &app.search_state {
             SearchState::Idle => {
@@ -683,6 +655,7 @@ fn render_status(f: &mut Frame, app: &App, area: Rect) {
                 }
             }
             SearchState::LoadingGroups => ("Loading...".to_string(), Color::Yellow),
+            SearchState::Refreshing => ("Refreshing...".to_string(), Color::Yellow),
             SearchState::Searching => {
                 if app.follow_mode {
                     ("Starting follow...".to_string(), Color::Yellow)
@@ -692,7 +665,16 @@ fn render_status(f: &mut Frame, app: &App, area: Rect) {
             }
             SearchState::Complete(count) => {
                 let follow_indicator = if app.follow_mode { " [f]" } else { "" };
-                (format!("Found {} results{}", count, follow_indicator), Color::Green)
+                let scanned = match &app.query_stats {
+                    Some(stats) if app.source_mode == SourceMode::CloudWatch => {
+                        format!(" · {} scanned", display::format_bytes(stats.bytes_scanned))
+                    }
+                    _ => String::new(),
+                };
+                (format!("Found {} results{}{}", count, scanned, follow_indicator), Color::Green)
+            }
+            SearchState::IdleStopped(count) => {
+                (format!("Follow stopped: no new lines (idle) - {} results", count), Color::Yellow)
             }
             SearchState::Error(e) => {
                 let truncated = if e.len() > 35 {
@@ -727,10 +709,32 @@ fn render_results(f: &mut Frame, app: &App, area: Rect) {
         String::new()
     };
 
+    let results = app.visible_results();
+
+    let filter_indicator = if app.results_filter_editing || !app.results_filter.is_empty() {
+        format!(" /{}", app.results_filter)
+    } else {
+        String::new()
+    };
+
+    let mut view_indicator = if app.results_view != ResultsView::Full {
+        format!(" [{}]", app.results_view.label())
+    } else {
+        String::new()
+    };
+    if app.results_grouped {
+        view_indicator.push_str(" [grouped]");
+    }
+    if app.results_color_by_level {
+        view_indicator.push_str(" [by level]");
+    }
+
     let title = format!(
-        " Results ({}/{}){}  F1:Help ",
-        if app.results.is_empty() { 0 } else { app.results_scroll + 1 },
-        app.results.len(),
+        " Results ({}/{}){}{}{}  F1:Help ",
+        if results.is_empty() { 0 } else { app.results_scroll + 1 },
+        results.len(),
+        view_indicator,
+        filter_indicator,
         scroll_indicator
     );
 
@@ -739,9 +743,11 @@ fn render_results(f: &mut Frame, app: &App, area: Rect) {
         .borders(Borders::ALL)
         .border_style(style);
 
-    if app.results.is_empty() {
+    if results.is_empty() {
         let empty_msg = match &app.search_state {
+            _ if !app.results.is_empty() => "No results match the filter",
             SearchState::Searching => "Searching...",
+            SearchState::Refreshing => "Refreshing...",
             SearchState::LoadingGroups => "Loading...",
             SearchState::Error(_) => "Search failed",
             _ => "Press Enter to search",
@@ -756,48 +762,71 @@ fn render_results(f: &mut Frame, app: &App, area: Rect) {
         let show_line_numbers = app.horizontal_scroll > 0;
         let line_num_width = if show_line_numbers { 3 } else { 0 };
 
-        let items: Vec<ListItem> = app
-            .results
-            .iter()
+        let patterns = app.get_patterns();
+        let filter = [app.results_filter.clone()];
+        let highlights: [(&[String], Style); 2] = [
+            (&patterns, pattern_highlight_style()),
+            (&filter, filter_highlight_style()),
+        ];
+
+        // Grouped view: a header opens each log group section, and the top row's section always has one
+        let mut section_sizes: HashMap<String, usize> = HashMap::new();
+        if app.results_grouped {
+            for entry in &results {
+                *section_sizes.entry(group_key(entry.region.as_deref(), &entry.log_group)).or_default() += 1;
+            }
+        }
+        let mut current_section: Option<String> = None;
+
+        let items: Vec<ListItem> = results
+            .into_iter()
             .enumerate()
             .skip(app.results_scroll)
-            .take(area.height.saturating_sub(2) as usize)
-            .map(|(idx, entry)| {
-                let timestamp = entry.timestamp.format("%H:%M:%S%.3f").to_string();
-                let region_short = entry.region.as_ref().map(|r| shorten_region(r)).unwrap_or_default();
-                let group_short = shorten_group(&entry.log_group);
-
-                let group_key = entry.region.as_ref()
-                    .map(|r| format!("{}:{}", r, entry.log_group))
-                    .unwrap_or_else(|| entry.log_group.clone());
-                let group_color = color_map.get(&group_key).copied().unwrap_or(Color::Blue);
+            .flat_map(|(idx, entry)| {
+                let group_color = display::group_color(&color_map, entry);
+
+                let mut items = Vec::with_capacity(2);
+                if app.results_grouped {
+                    let key = group_key(entry.region.as_deref(), &entry.log_group);
+                    if current_section.as_ref() != Some(&key) {
+                        items.push(section_header(&key, section_sizes[&key], group_color));
+                        current_section = Some(key);
+                    }
+                }
+                // By level, the whole line takes the level's color; by group, only the prefix is colored
+                let level_color = app.results_color_by_level.then(|| display::level_color(level::detect(&entry.message)));
+                let prefix = result_prefix(entry, &app.results_view, &app.time_format, &app.region_aliases, level_color.unwrap_or(group_color));
 
-                let clean_message = strip_ansi_codes(&entry.message);
-                let patterns = app.get_patterns();
+                let clean_message = display::sanitize(&display::strip_sgr(&entry.message)).into_owned();
 
                 let line = if app.horizontal_scroll == 0 {
-                    let mut spans: Vec<Span<'static>> = vec![
-                        Span::styled(format!("{} ", timestamp), Style::default().fg(Color::DarkGray)),
-                        Span::styled(format!("[{}] ", region_short), Style::default().fg(group_color).add_modifier(Modifier::DIM)),
-                        Span::styled(format!("[{}] ", group_short), Style::default().fg(group_color)),
-                    ];
-                    spans.extend(highlight_patterns(&clean_message, &patterns));
+                    let mut spans = prefix;
+                    let mut message = highlight_terms(&clean_message, &highlights);
+                    if let Some(color) = level_color {
+                        for span in message.iter_mut().filter(|s| s.style == Style::default()) {
+                            span.style = span.style.fg(color);
+                        }
+                    }
+                    spans.extend(message);
                     Line::from(spans)
                 } else {
                     let line_num = format!("{:02} ", (idx % 100));
                     let content_width = available_width.saturating_sub(line_num_width);
-                    let full_content = format!("{} [{}] [{}] {}", timestamp, region_short, group_short, clean_message);
+                    let prefix_text: String = prefix.iter().map(|s| s.content.as_ref()).collect();
+                    let full_content = format!("{}{}", prefix_text, clean_message);
                     let scrolled_content: String = full_content.chars().skip(app.horizontal_scroll).take(content_width).collect();
 
                     let mut spans: Vec<Span<'static>> = vec![
                         Span::styled(line_num, Style::default().fg(Color::DarkGray)),
                     ];
-                    spans.extend(highlight_patterns(&scrolled_content, &patterns));
+                    spans.extend(highlight_terms(&scrolled_content, &highlights));
                     Line::from(spans)
                 };
 
-                ListItem::new(line)
+                items.push(ListItem::new(line));
+                items
             })
+            .take(area.height.saturating_sub(2) as usize)
             .collect();
 
         let list = List::new(items).block(block);
@@ -805,6 +834,55 @@ fn render_results(f: &mut Frame, app: &App, area: Rect) {
     }
 }
 
+/// Header line opening a log group section in the grouped results view
+fn section_header(key: &str, count: usize, group_color: Color) -> ListItem<'static> {
+    ListItem::new(Line::from(vec![
+        Span::styled("━━━ ", Style::default().fg(Color::Blue)),
+        Span::styled(key.to_string(), Style::default().fg(group_color).add_modifier(Modifier::BOLD)),
+        Span::styled(format!(" ({} results)", count), Style::default().fg(Color::DarkGray)),
+    ]))
+}
+
+/// Spans shown before the message for the active results view
+fn result_prefix(
+    entry: &LogEntry,
+    view: &ResultsView,
+    time_format: &str,
+    region_aliases: &HashMap<String, String>,
+    group_color: Color,
+) -> Vec<Span<'static>> {
+    let time_style = Style::default().fg(Color::DarkGray);
+    let group_style = Style::default().fg(group_color);
+
+    let time = || Span::styled(format!("{} ", entry.timestamp.format(time_format)), time_style);
+    let group = || Span::styled(format!("[{}] ", shorten_group(&entry.log_group)), group_style);
+
+    match view {
+        ResultsView::Full => {
+            let region_short = entry.region.as_deref().map(|r| region_label(r, region_aliases)).unwrap_or_default();
+            vec![
+                time(),
+                Span::styled(format!("[{}] ", region_short), group_style.add_modifier(Modifier::DIM)),
+                group(),
+            ]
+        }
+        ResultsView::Stream => {
+            let stream = entry.log_stream.as_deref().map(shorten_stream).unwrap_or_default();
+            vec![
+                time(),
+                group(),
+                Span::styled(format!("[{}] ", stream), group_style.add_modifier(Modifier::DIM)),
+            ]
+        }
+        ResultsView::NoRegion => vec![time(), group()],
+        ResultsView::MessageOnly => vec![],
+        ResultsView::Raw => vec![
+            Span::styled(format!("{} ", entry.timestamp.format("%Y-%m-%dT%H:%M:%S%.3fZ")), time_style),
+            Span::styled(format!("{} ", group_key(entry.region.as_deref(), &entry.log_group)), group_style),
+        ],
+    }
+}
+
 fn render_help_bar(f: &mut Frame, app: &App, area: Rect) {
     let help_text = match app.focus {
         Focus::DeployFile => Line::from(vec![
@@ -820,11 +898,31 @@ fn render_help_bar(f: &mut Frame, app: &App, area: Rect) {
             Span::raw(" Toggle  "),
             Span::styled("↑/↓", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(" Nav  "),
+            Span::styled("Type", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" Filter  "),
             Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(" Search  "),
             Span::styled("Tab", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(" Next"),
         ]),
+        Focus::TimeRange if app.time_input_mode => Line::from(vec![
+            Span::styled("↑/↓", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" From/To  "),
+            Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" Done  "),
+            Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" Search"),
+        ]),
+        Focus::TimeRange => Line::from(vec![
+            Span::styled("←/→", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" Preset  "),
+            Span::styled("e", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" Absolute  "),
+            Span::styled("Bksp", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" Clear  "),
+            Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" Search"),
+        ]),
         Focus::Results => {
             if app.is_following {
                 Line::from(vec![
@@ -841,8 +939,22 @@ fn render_help_bar(f: &mut Frame, app: &App, area: Rect) {
                     Span::raw(" Scroll  "),
                     Span::styled("h/l", Style::default().add_modifier(Modifier::BOLD)),
                     Span::raw(" Horiz  "),
+                    Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(" Detail  "),
+                    Span::styled("/", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(" Filter  "),
+                    Span::styled("v", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(" View  "),
+                    Span::styled("s", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(" Group  "),
+                    Span::styled("c", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(" Color  "),
+                    Span::styled("o", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(" Console  "),
                     Span::styled("f", Style::default().add_modifier(Modifier::BOLD)),
                     Span::raw(" Follow  "),
+                    Span::styled("r", Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(" Refresh  "),
                     Span::styled("Ctrl+C", Style::default().add_modifier(Modifier::BOLD)),
                     Span::raw(" Quit"),
                 ])
@@ -867,10 +979,88 @@ fn render_help_bar(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(help, area);
 }
 
+/// Full message of the selected result, with search patterns and the filter term highlighted
+fn render_detail_popup(f: &mut Frame, app: &App) {
+    let Some(entry) = app.selected_result() else {
+        return;
+    };
+
+    let area = f.area();
+    let popup_width = area.width * 4 / 5;
+    let popup_height = area.height * 3 / 5;
+    let popup_area = Rect::new(
+        (area.width.saturating_sub(popup_width)) / 2,
+        (area.height.saturating_sub(popup_height)) / 2,
+        popup_width,
+        popup_height,
+    );
+
+    f.render_widget(Clear, popup_area);
+
+    let patterns = app.get_patterns();
+    let filter = [app.results_filter.clone()];
+    let highlights: [(&[String], Style); 2] = [
+        (&patterns, pattern_highlight_style()),
+        (&filter, filter_highlight_style()),
+    ];
+
+    let label = Style::default().fg(Color::DarkGray);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Time    ", label),
+            Span::raw(entry.timestamp.format("%Y-%m-%d %H:%M:%S%.3f UTC").to_string()),
+        ]),
+        Line::from(vec![Span::styled("Group   ", label), Span::raw(entry.log_group.clone())]),
+    ];
+    if let Some(ref region) = entry.region {
+        lines.push(Line::from(vec![Span::styled("Region  ", label), Span::raw(region.clone())]));
+    }
+    if let Some(ref stream) = entry.log_stream {
+        lines.push(Line::from(vec![Span::styled("Stream  ", label), Span::raw(stream.clone())]));
+    }
+    lines.push(Line::from(""));
+    for message_line in display::sanitize(&display::strip_sgr(&entry.message)).lines() {
+        lines.push(Line::from(highlight_terms(message_line, &highlights)));
+    }
+
+    let block = Block::default()
+        .title(" Log Entry (any key to close) ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    f.render_widget(paragraph, popup_area);
+}
+
+fn render_profile_modal(f: &mut Frame, app: &App) {
+    let area = f.area();
+    let popup_width = 50.min(area.width);
+    let popup_area = Rect::new(
+        (area.width.saturating_sub(popup_width)) / 2,
+        (area.height.saturating_sub(3)) / 2,
+        popup_width,
+        3.min(area.height),
+    );
+
+    f.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" AWS Profile (Enter apply, Esc cancel, empty = default) ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+
+    let input = Paragraph::new(app.profile_input.as_str())
+        .block(block)
+        .style(Style::default().fg(Color::White));
+
+    f.render_widget(input, popup_area);
+    f.set_cursor_position((popup_area.x + app.profile_input.len() as u16 + 1, popup_area.y + 1));
+}
+
 fn render_help_overlay(f: &mut Frame) {
     let area = f.area();
     let popup_width = 60;
-    let popup_height = 15;
+    let popup_height = 23;
     let popup_area = Rect::new(
         (area.width.saturating_sub(popup_width)) / 2,
         (area.height.saturating_sub(popup_height)) / 2,
@@ -886,9 +1076,18 @@ fn render_help_overlay(f: &mut Frame) {
         Line::from(vec![Span::styled("Tab / Shift+Tab", Style::default().fg(Color::Cyan)), Span::raw("  Navigate sections")]),
         Line::from(vec![Span::styled("Enter", Style::default().fg(Color::Cyan)), Span::raw("            Execute search")]),
         Line::from(vec![Span::styled("f", Style::default().fg(Color::Cyan)), Span::raw("                Toggle follow mode")]),
+        Line::from(vec![Span::styled("r", Style::default().fg(Color::Cyan)), Span::raw("                Re-run the last search")]),
         Line::from(vec![Span::styled("Space", Style::default().fg(Color::Cyan)), Span::raw("            Toggle selection")]),
         Line::from(vec![Span::styled("↑/↓ or j/k", Style::default().fg(Color::Cyan)), Span::raw("       Navigate lists")]),
         Line::from(vec![Span::styled("←/→ or h/l", Style::default().fg(Color::Cyan)), Span::raw("       Adjust values / scroll")]),
+        Line::from(vec![Span::styled("e", Style::default().fg(Color::Cyan)), Span::raw("                Edit absolute time range")]),
+        Line::from(vec![Span::styled("/", Style::default().fg(Color::Cyan)), Span::raw("                Filter results")]),
+        Line::from(vec![Span::styled("Enter (results)", Style::default().fg(Color::Cyan)), Span::raw("  Show full message")]),
+        Line::from(vec![Span::styled("v", Style::default().fg(Color::Cyan)), Span::raw("                Cycle results view")]),
+        Line::from(vec![Span::styled("s", Style::default().fg(Color::Cyan)), Span::raw("                Group results by log group")]),
+        Line::from(vec![Span::styled("c", Style::default().fg(Color::Cyan)), Span::raw("                Color results by level / log group")]),
+        Line::from(vec![Span::styled("o", Style::default().fg(Color::Cyan)), Span::raw("                Open result in the AWS console")]),
+        Line::from(vec![Span::styled("Ctrl+P", Style::default().fg(Color::Cyan)), Span::raw("           Switch AWS profile")]),
         Line::from(vec![Span::styled("Esc", Style::default().fg(Color::Cyan)), Span::raw("              Stop follow / back")]),
         Line::from(vec![Span::styled("Ctrl+C", Style::default().fg(Color::Cyan)), Span::raw("           Quit")]),
         Line::from(""),
@@ -903,3 +1102,48 @@ fn render_help_overlay(f: &mut Frame) {
     let paragraph = Paragraph::new(help_text).block(block);
     f.render_widget(paragraph, popup_area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_result_prefix_per_view() {
+        use chrono::{TimeZone, Utc};
+
+        let entry = LogEntry {
+            timestamp: Utc.with_ymd_and_hms(2026, 1, 23, 5, 36, 5).unwrap(),
+            message: "boom".to_string(),
+            log_group: "/aws/app/rails-web".to_string(),
+            log_stream: Some("ecs/rails-web/web-1".to_string()),
+            region: Some("ap-northeast-1".to_string()),
+            also_in: Vec::new(),
+            ptr: None,
+        };
+        let text = |view| -> String {
+            result_prefix(&entry, &view, "%H:%M:%S%.3f", &HashMap::new(), Color::Cyan).iter().map(|s| s.content.to_string()).collect()
+        };
+
+        assert_eq!(text(ResultsView::Full), "05:36:05.000 [AN1] [RW] ");
+        assert_eq!(text(ResultsView::Stream), "05:36:05.000 [RW] [web-1] ");
+        assert_eq!(text(ResultsView::NoRegion), "05:36:05.000 [RW] ");
+        assert_eq!(text(ResultsView::MessageOnly), "");
+        assert_eq!(text(ResultsView::Raw), "2026-01-23T05:36:05.000Z ap-northeast-1:/aws/app/rails-web ");
+    }
+
+    #[test]
+    fn test_highlight_terms_styles_patterns_and_filter() {
+        let patterns = vec!["error".to_string()];
+        let filter = vec!["user_id".to_string()];
+        let spans = highlight_terms(
+            "ERROR: timeout for user_id=42",
+            &[(&patterns, pattern_highlight_style()), (&filter, filter_highlight_style())],
+        );
+
+        let styled: Vec<(&str, Style)> = spans.iter().map(|s| (s.content.as_ref(), s.style)).collect();
+        assert!(styled.contains(&("ERROR", pattern_highlight_style())));
+        assert!(styled.contains(&("user_id", filter_highlight_style())));
+        assert_ne!(pattern_highlight_style(), filter_highlight_style());
+        assert_eq!(spans.iter().map(|s| s.content.as_ref()).collect::<String>(), "ERROR: timeout for user_id=42");
+    }
+}
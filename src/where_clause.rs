@@ -0,0 +1,186 @@
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// Comparison operators, longest first so `>=` isn't read as `>`
+const OPERATORS: &[(&str, Op)] = &[
+    ("~=", Op::Matches),
+    (">=", Op::Ge),
+    ("<=", Op::Le),
+    ("==", Op::Eq),
+    ("!=", Op::Ne),
+    (">", Op::Gt),
+    ("<", Op::Lt),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Gt,
+    Lt,
+    Ge,
+    Le,
+    Eq,
+    Ne,
+    /// `~=`: the field's text matches a regex
+    Matches,
+}
+
+impl Op {
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Gt => ordering == Ordering::Greater,
+            Self::Lt => ordering == Ordering::Less,
+            Self::Ge => ordering != Ordering::Less,
+            Self::Le => ordering != Ordering::Greater,
+            Self::Eq => ordering == Ordering::Equal,
+            Self::Ne => ordering != Ordering::Equal,
+            // Not an ordering; `WhereClause::matches` runs the regex instead
+            Self::Matches => false,
+        }
+    }
+}
+
+/// A `--where` (or `--exclude-where`) comparison on a field of JSON log messages, e.g. `duration_ms>1000` or `level==error`
+#[derive(Debug, Clone)]
+pub struct WhereClause {
+    /// Field path; dots reach into nested objects (`http.status`)
+    field: String,
+    op: Op,
+    value: String,
+    /// The value compiled for `~=`
+    regex: Option<Regex>,
+}
+
+impl WhereClause {
+    /// Parse `<field><op><value>` with op one of `>`, `<`, `>=`, `<=`, `==`, `!=`, or `~=` for a regex
+    pub fn parse(spec: &str) -> Result<Self> {
+        Self::parse_flag("--where", spec)
+    }
+
+    /// Parse a comparison given to `flag`, which names it in errors
+    pub fn parse_flag(flag: &str, spec: &str) -> Result<Self> {
+        let invalid = || anyhow!("Invalid {} '{}': expected <field><op><value>, e.g. latency>=500", flag, spec);
+
+        let start = spec.find(['<', '>', '=', '!', '~']).ok_or_else(invalid)?;
+        let (symbol, op) = OPERATORS
+            .iter()
+            .find(|(symbol, _)| spec[start..].starts_with(symbol))
+            .ok_or_else(invalid)?;
+
+        let field = spec[..start].trim();
+        if field.is_empty() {
+            return Err(invalid());
+        }
+        let value = spec[start + symbol.len()..].trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+
+        let regex = match op {
+            Op::Matches => Some(Regex::new(value).map_err(|e| anyhow!("Invalid {} '{}': {}", flag, spec, e))?),
+            _ => None,
+        };
+
+        Ok(Self { field: field.to_string(), op: *op, value: value.to_string(), regex })
+    }
+
+    /// Whether a message is a JSON object whose field satisfies the comparison
+    /// Messages that aren't JSON, or lack the field, never match
+    pub fn matches(&self, message: &str) -> bool {
+        let trimmed = message.trim();
+        if !trimmed.starts_with('{') {
+            return false;
+        }
+        let Ok(json) = serde_json::from_str::<Value>(trimmed) else {
+            return false;
+        };
+
+        let field = self.field.split('.').try_fold(&json, |value, key| value.get(key));
+        match field {
+            Some(Value::Null) | Some(Value::Array(_)) | Some(Value::Object(_)) | None => false,
+            Some(field) => match &self.regex {
+                Some(regex) => regex.is_match(&text(field)),
+                None => self.compare(field).is_some_and(|ordering| self.op.holds(ordering)),
+            },
+        }
+    }
+
+    /// Numbers (and numeric strings) compare numerically, anything else as text
+    fn compare(&self, field: &Value) -> Option<Ordering> {
+        let text = text(field);
+
+        match (text.parse::<f64>(), self.value.parse::<f64>()) {
+            (Ok(actual), Ok(expected)) => actual.partial_cmp(&expected),
+            _ => Some(text.as_str().cmp(self.value.as_str())),
+        }
+    }
+}
+
+/// A scalar field as text: strings without their quotes, numbers and booleans as written
+fn text(field: &Value) -> String {
+    match field {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numeric_comparisons() {
+        let message = r#"{"path":"/api","duration_ms":1250,"http":{"status":"503"}}"#;
+
+        assert!(WhereClause::parse("duration_ms>1000").unwrap().matches(message));
+        assert!(WhereClause::parse("duration_ms >= 1250").unwrap().matches(message));
+        assert!(!WhereClause::parse("duration_ms<1000").unwrap().matches(message));
+        assert!(WhereClause::parse("duration_ms==1250.0").unwrap().matches(message));
+        assert!(WhereClause::parse("duration_ms!=1").unwrap().matches(message));
+        // Numeric strings and nested fields compare as numbers
+        assert!(WhereClause::parse("http.status>=500").unwrap().matches(message));
+        assert!(!WhereClause::parse("http.status<=499").unwrap().matches(message));
+    }
+
+    #[test]
+    fn test_string_comparisons() {
+        let message = r#"{"level":"error","user":"alice","cached":false}"#;
+
+        assert!(WhereClause::parse("level==error").unwrap().matches(message));
+        assert!(WhereClause::parse(r#"level=="error""#).unwrap().matches(message));
+        assert!(!WhereClause::parse("level!=error").unwrap().matches(message));
+        assert!(WhereClause::parse("user<bob").unwrap().matches(message));
+        assert!(WhereClause::parse("cached==false").unwrap().matches(message));
+    }
+
+    #[test]
+    fn test_regex_matches() {
+        let message = r#"{"path":"/health/live","status":200}"#;
+
+        assert!(WhereClause::parse("path~=^/health").unwrap().matches(message));
+        assert!(!WhereClause::parse("path~=^/api").unwrap().matches(message));
+        assert!(WhereClause::parse("status ~= ^2..$").unwrap().matches(message));
+        // The regex is anchored only where it says so
+        assert!(!WhereClause::parse("path~=^/health").unwrap().matches(r#"{"path":"/api/health"}"#));
+        assert!(WhereClause::parse("path~=(").is_err());
+    }
+
+    #[test]
+    fn test_non_json_and_missing_fields_never_match() {
+        let clause = WhereClause::parse("latency!=0").unwrap();
+        assert!(!clause.matches("GET /api latency=120"));
+        assert!(!clause.matches("{not json"));
+        assert!(!clause.matches(r#"{"status":200}"#));
+        assert!(!clause.matches(r#"{"latency":null}"#));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(WhereClause::parse("latency").is_err());
+        assert!(WhereClause::parse(">=500").is_err());
+        assert!(WhereClause::parse("latency=500").is_err());
+        assert!(WhereClause::parse("latency!500").is_err());
+    }
+}